@@ -10,13 +10,18 @@ use relm4::{
 use std::{
     cell::OnceCell,
     fmt::Display,
+    path::PathBuf,
     sync::{Arc, RwLock},
 };
+use tracing::warn;
 
 use crate::{
     accounts::account_list::{AccountList, AccountListInput, AccountListOutput},
     borgar::borgar_menu::{BorgarMenuInput, BORGAR_MENU_SENDER},
-    config::{self, general::MostRecentLogin},
+    config::{
+        self, general::MostRecentLogin, keybindings::Keybindings,
+        video_player_config::ResumeBehaviour,
+    },
     globals::CONFIG,
     jellyfin_api::api_client::ApiClient,
     library::{collection::Collection, Library, LibraryOutput, LIBRARY_BROKER},
@@ -26,8 +31,14 @@ use crate::{
     preferences::Preferences,
     servers::server_list::{ServerList, ServerListOutput},
     tr,
-    utils::{main_window::MAIN_APP_WINDOW_NAME, shift_state::shift_state_controller},
-    video_player::{VideoPlayer, VideoPlayerInput, VideoPlayerOutput, VIDEO_PLAYER_BROKER},
+    utils::{
+        main_window::MAIN_APP_WINDOW_NAME, playable::get_resume_position,
+        shift_state::shift_state_controller,
+    },
+    video_player::{
+        playstate_outbox, resume_dialog::ResumeDialog, VideoPlayer, VideoPlayerInput,
+        VideoPlayerOutput, VIDEO_PLAYER_BROKER,
+    },
 };
 
 pub static APP_BROKER: MessageBroker<AppInput> = MessageBroker::new();
@@ -70,6 +81,8 @@ pub struct App {
     server: Option<config::Server>,
     account: Option<config::Account>,
     preferences: Option<Controller<Preferences>>,
+    resume_dialog: Option<Controller<ResumeDialog>>,
+    keybindings: Keybindings,
 }
 
 #[derive(Debug)]
@@ -82,11 +95,21 @@ pub enum AppInput {
     ShowDetails(BaseItemDto),
     ShowCollection(BaseItemDto),
     PlayVideo(BaseItemDto),
+    /// Play a video the player moved on to by itself, like the next episode, without asking
+    /// whether to resume it
+    ContinuePlayback(BaseItemDto),
+    /// Play a video, starting from the given position in seconds
+    PlayVideoFrom(BaseItemDto, usize),
+    /// Return to the video player, after it was moved to a picture-in-picture window
+    ShowVideoPlayer,
     SignOut,
     SetThemeDark(bool),
+    KeybindingsUpdated(Keybindings),
     PagePopped(Option<String>),
     ShowPreferences,
     Toast(String, Option<u32>),
+    /// Show a toast with a button to open the folder containing the given file
+    FileToast(String, PathBuf),
 }
 
 #[relm4::component(pub)]
@@ -117,7 +140,7 @@ impl Component for App {
             add_controller: shift_state_controller(),
 
             #[wrap(Some)]
-            set_help_overlay = &keyboard_shortcuts(),
+            set_help_overlay = &keyboard_shortcuts(&config.video_player.keybindings),
 
 
             #[name = "toaster"]
@@ -187,12 +210,18 @@ impl Component for App {
             server: None,
             account: None,
             preferences: None,
+            resume_dialog: None,
+            keybindings: config.video_player.keybindings.clone(),
         };
 
         let widgets = view_output!();
 
         model.register_actions(&sender);
 
+        CONFIG.subscribe(sender.input_sender(), |config| {
+            AppInput::KeybindingsUpdated(config.video_player.keybindings.clone())
+        });
+
         if config.general.restore_most_recent_login {
             if let Some(MostRecentLogin {
                 server_id,
@@ -267,6 +296,9 @@ impl Component for App {
                 let api_client = Arc::new(api_client);
                 self.api_client = Some(api_client.clone());
 
+                // Send playback reports that couldn't be sent last time
+                playstate_outbox::flush(api_client.clone());
+
                 LIBRARY_BROKER.reset();
                 let library = Library::builder()
                     .launch_with_broker((server, account, api_client), &LIBRARY_BROKER.read())
@@ -312,28 +344,36 @@ impl Component for App {
                 }
             }
             AppInput::PlayVideo(item) => {
-                if self.video_player.get().is_none() {
-                    let video_player = VideoPlayer::builder()
-                        .launch_with_broker((), &VIDEO_PLAYER_BROKER)
-                        .forward(sender.input_sender(), convert_video_player_output);
-                    let video_player_widget = video_player.widget();
-                    video_player_widget.set_tag(Some(&AppPage::VideoPlayer.to_string()));
-                    widgets.navigation.add(video_player_widget);
-                    // We already checked that video_player is unset, ignore result
-                    let _ = self.video_player.set(video_player);
-                }
-
-                if let Some(api_client) = &self.api_client {
-                    self.video_player
-                        .get()
-                        .unwrap()
-                        .emit(VideoPlayerInput::PlayVideo(
-                            api_client.clone(),
-                            Box::new(item),
-                        ));
-                    navigation.push_by_tag(&AppPage::VideoPlayer.to_string());
+                let resume_behaviour = CONFIG.read().video_player.resume_behaviour;
+                match (get_resume_position(&item), resume_behaviour) {
+                    (Some(resume_position), ResumeBehaviour::Ask) => {
+                        self.resume_dialog = Some(
+                            ResumeDialog::builder()
+                                .transient_for(root)
+                                .launch((item, resume_position))
+                                .detach(),
+                        );
+                    }
+                    (Some(_), ResumeBehaviour::Restart) => {
+                        self.play_video(&sender, navigation, item, Some(0));
+                    }
+                    _ => {
+                        self.play_video(&sender, navigation, item, None);
+                    }
                 }
             }
+            AppInput::ContinuePlayback(item) => {
+                let restart =
+                    CONFIG.read().video_player.resume_behaviour == ResumeBehaviour::Restart;
+                self.play_video(&sender, navigation, item, restart.then_some(0));
+            }
+            AppInput::PlayVideoFrom(item, start_position) => {
+                self.play_video(&sender, navigation, item, Some(start_position));
+            }
+            AppInput::ShowVideoPlayer => {
+                navigation.push_by_tag(&AppPage::VideoPlayer.to_string());
+                root.present();
+            }
             AppInput::SignOut => {
                 navigation.pop_to_tag(&AppPage::Servers.to_string());
             }
@@ -346,6 +386,12 @@ impl Component for App {
                     root.add_css_class("light");
                 }
             }
+            AppInput::KeybindingsUpdated(keybindings) => {
+                if keybindings != self.keybindings {
+                    root.set_help_overlay(Some(&keyboard_shortcuts(&keybindings)));
+                    self.keybindings = keybindings;
+                }
+            }
             AppInput::PagePopped(tag) => {
                 match tag {
                     Some(tag) if tag == AppPage::MediaDetails.to_string() => {
@@ -373,6 +419,28 @@ impl Component for App {
                         .build(),
                 );
             }
+            AppInput::FileToast(toast, path) => {
+                let toast = Toast::builder()
+                    .title(toast)
+                    .button_label(tr!("app-open-folder"))
+                    .build();
+                toast.connect_button_clicked({
+                    let root = root.clone();
+                    move |_| {
+                        gtk::FileLauncher::new(Some(&gtk::gio::File::for_path(&path)))
+                            .open_containing_folder(
+                                Some(&root),
+                                None::<&gtk::gio::Cancellable>,
+                                |result| {
+                                    if let Err(err) = result {
+                                        warn!("Error opening folder: {err}");
+                                    }
+                                },
+                            );
+                    }
+                });
+                widgets.toaster.add_toast(toast);
+            }
         }
 
         self.update_view(widgets, sender);
@@ -380,6 +448,37 @@ impl Component for App {
 }
 
 impl App {
+    fn play_video(
+        &mut self,
+        sender: &ComponentSender<Self>,
+        navigation: &adw::NavigationView,
+        item: BaseItemDto,
+        start_position: Option<usize>,
+    ) {
+        if self.video_player.get().is_none() {
+            let video_player = VideoPlayer::builder()
+                .launch_with_broker((), &VIDEO_PLAYER_BROKER)
+                .forward(sender.input_sender(), convert_video_player_output);
+            let video_player_widget = video_player.widget();
+            video_player_widget.set_tag(Some(&AppPage::VideoPlayer.to_string()));
+            navigation.add(video_player_widget);
+            // We already checked that video_player is unset, ignore result
+            let _ = self.video_player.set(video_player);
+        }
+
+        if let Some(api_client) = &self.api_client {
+            self.video_player
+                .get()
+                .unwrap()
+                .emit(VideoPlayerInput::PlayVideo(
+                    api_client.clone(),
+                    Box::new(item),
+                    start_position,
+                ));
+            navigation.push_by_tag(&AppPage::VideoPlayer.to_string());
+        }
+    }
+
     fn register_actions(&self, sender: &ComponentSender<Self>) {
         let app = relm4::main_application();
         app.set_accels_for_action("win.show-help-overlay", &["<Ctrl>question"]);
@@ -412,10 +511,28 @@ impl App {
     }
 }
 
-fn keyboard_shortcuts() -> gtk::ShortcutsWindow {
-    gtk::Builder::from_string(&tera_tr(include_str!("../../data/ui/shortcuts.ui")).unwrap())
-        .object::<gtk::ShortcutsWindow>("shortcuts")
-        .unwrap()
+fn keyboard_shortcuts(keybindings: &Keybindings) -> gtk::ShortcutsWindow {
+    let builder =
+        gtk::Builder::from_string(&tera_tr(include_str!("../../data/ui/shortcuts.ui")).unwrap());
+
+    // Video player shortcuts can be changed in the preferences
+    let video_player_shortcuts = builder
+        .object::<gtk::ShortcutsGroup>("video_player_shortcuts")
+        .unwrap();
+    for action in keybindings.actions() {
+        let accelerators = keybindings.accelerators(action);
+        if accelerators.is_empty() {
+            continue;
+        }
+        video_player_shortcuts.add_shortcut(
+            &gtk::ShortcutsShortcut::builder()
+                .accelerator(accelerators.join(" "))
+                .title(action.label())
+                .build(),
+        );
+    }
+
+    builder.object::<gtk::ShortcutsWindow>("shortcuts").unwrap()
 }
 
 fn convert_server_list_output(output: ServerListOutput) -> AppInput {
@@ -435,6 +552,7 @@ fn convert_account_list_output(output: AccountListOutput) -> AppInput {
 fn convert_video_player_output(output: VideoPlayerOutput) -> AppInput {
     match output {
         VideoPlayerOutput::NavigateBack => AppInput::NavigateBack,
+        VideoPlayerOutput::ShowPlayer => AppInput::ShowVideoPlayer,
     }
 }
 
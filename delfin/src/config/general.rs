@@ -61,6 +61,7 @@ pub struct GeneralConfig {
     pub most_recent_login: Option<MostRecentLogin>,
     pub restore_most_recent_login: bool,
     pub use_episode_image: bool,
+    pub recent_searches: Vec<String>,
 }
 
 impl Default for GeneralConfig {
@@ -71,6 +72,7 @@ impl Default for GeneralConfig {
             most_recent_login: Option::default(),
             restore_most_recent_login: true,
             use_episode_image: false,
+            recent_searches: Vec::default(),
         }
     }
 }
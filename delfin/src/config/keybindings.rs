@@ -0,0 +1,374 @@
+use std::{collections::BTreeMap, fmt::Display, str::FromStr};
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::warn;
+
+use crate::tr;
+
+/// Something the video player can do when a key is pressed.
+///
+/// Stored in the config as a kebab-case name, followed by an amount for actions that take one,
+/// e.g. `seek -5` or `volume +10`.
+#[derive(Clone, Copy, Debug, Serialize, PartialEq, Eq)]
+#[serde(into = "String")]
+pub enum KeybindingAction {
+    TogglePlay,
+    /// Seek backwards by the configured skip backwards amount
+    SkipBackwards,
+    /// Seek forwards by the configured skip forwards amount
+    SkipForwards,
+    /// Seek by a number of seconds
+    Seek(i64),
+    /// Change volume by a percentage
+    Volume(i64),
+    ToggleMute,
+    ToggleNightMode,
+    ToggleFullscreen,
+    ExitFullscreen,
+    ToggleSubtitles,
+    /// Change subtitle delay by a number of milliseconds
+    SubtitleDelay(i64),
+    /// Change audio delay by a number of milliseconds
+    AudioDelay(i64),
+    FrameStepBackwards,
+    FrameStepForwards,
+    SpeedDown,
+    SpeedUp,
+    PreviousChapter,
+    NextChapter,
+    CycleAspectRatio,
+    TogglePanscan,
+    ZoomIn,
+    ZoomOut,
+    ResetVideo,
+    Screenshot,
+    ScreenshotWithoutSubtitles,
+    PictureInPicture,
+    ToggleStats,
+    CycleAbLoop,
+    ClearAbLoop,
+}
+
+impl KeybindingAction {
+    fn name(&self) -> &'static str {
+        match self {
+            KeybindingAction::TogglePlay => "toggle-play",
+            KeybindingAction::SkipBackwards => "skip-backwards",
+            KeybindingAction::SkipForwards => "skip-forwards",
+            KeybindingAction::Seek(_) => "seek",
+            KeybindingAction::Volume(_) => "volume",
+            KeybindingAction::ToggleMute => "toggle-mute",
+            KeybindingAction::ToggleNightMode => "toggle-night-mode",
+            KeybindingAction::ToggleFullscreen => "toggle-fullscreen",
+            KeybindingAction::ExitFullscreen => "exit-fullscreen",
+            KeybindingAction::ToggleSubtitles => "toggle-subtitles",
+            KeybindingAction::SubtitleDelay(_) => "subtitle-delay",
+            KeybindingAction::AudioDelay(_) => "audio-delay",
+            KeybindingAction::FrameStepBackwards => "frame-step-backwards",
+            KeybindingAction::FrameStepForwards => "frame-step-forwards",
+            KeybindingAction::SpeedDown => "speed-down",
+            KeybindingAction::SpeedUp => "speed-up",
+            KeybindingAction::PreviousChapter => "previous-chapter",
+            KeybindingAction::NextChapter => "next-chapter",
+            KeybindingAction::CycleAspectRatio => "cycle-aspect-ratio",
+            KeybindingAction::TogglePanscan => "toggle-panscan",
+            KeybindingAction::ZoomIn => "zoom-in",
+            KeybindingAction::ZoomOut => "zoom-out",
+            KeybindingAction::ResetVideo => "reset-video",
+            KeybindingAction::Screenshot => "screenshot",
+            KeybindingAction::ScreenshotWithoutSubtitles => "screenshot-without-subtitles",
+            KeybindingAction::PictureInPicture => "picture-in-picture",
+            KeybindingAction::ToggleStats => "toggle-stats",
+            KeybindingAction::CycleAbLoop => "cycle-ab-loop",
+            KeybindingAction::ClearAbLoop => "clear-ab-loop",
+        }
+    }
+
+    fn amount(&self) -> Option<i64> {
+        match self {
+            KeybindingAction::Seek(amount)
+            | KeybindingAction::Volume(amount)
+            | KeybindingAction::SubtitleDelay(amount)
+            | KeybindingAction::AudioDelay(amount) => Some(*amount),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let key = format!("kb-action-{}", self.name());
+        match self.amount() {
+            Some(amount) => tr!(&key, { "amount" => format!("{amount:+}") }).to_string(),
+            None => tr!(&key).to_string(),
+        }
+    }
+}
+
+impl Display for KeybindingAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.amount() {
+            Some(amount) => write!(f, "{} {amount:+}", self.name()),
+            None => write!(f, "{}", self.name()),
+        }
+    }
+}
+
+impl FromStr for KeybindingAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, amount) = match s.trim().split_once(' ') {
+            Some((name, amount)) => (name, Some(amount.trim())),
+            None => (s.trim(), None),
+        };
+
+        let action = match name {
+            "toggle-play" => KeybindingAction::TogglePlay,
+            "skip-backwards" => KeybindingAction::SkipBackwards,
+            "skip-forwards" => KeybindingAction::SkipForwards,
+            "seek" => KeybindingAction::Seek(parse_amount(s, amount)?),
+            "volume" => KeybindingAction::Volume(parse_amount(s, amount)?),
+            "toggle-mute" => KeybindingAction::ToggleMute,
+            "toggle-night-mode" => KeybindingAction::ToggleNightMode,
+            "toggle-fullscreen" => KeybindingAction::ToggleFullscreen,
+            "exit-fullscreen" => KeybindingAction::ExitFullscreen,
+            "toggle-subtitles" => KeybindingAction::ToggleSubtitles,
+            "subtitle-delay" => KeybindingAction::SubtitleDelay(parse_amount(s, amount)?),
+            "audio-delay" => KeybindingAction::AudioDelay(parse_amount(s, amount)?),
+            "frame-step-backwards" => KeybindingAction::FrameStepBackwards,
+            "frame-step-forwards" => KeybindingAction::FrameStepForwards,
+            "speed-down" => KeybindingAction::SpeedDown,
+            "speed-up" => KeybindingAction::SpeedUp,
+            "previous-chapter" => KeybindingAction::PreviousChapter,
+            "next-chapter" => KeybindingAction::NextChapter,
+            "cycle-aspect-ratio" => KeybindingAction::CycleAspectRatio,
+            "toggle-panscan" => KeybindingAction::TogglePanscan,
+            "zoom-in" => KeybindingAction::ZoomIn,
+            "zoom-out" => KeybindingAction::ZoomOut,
+            "reset-video" => KeybindingAction::ResetVideo,
+            "screenshot" => KeybindingAction::Screenshot,
+            "screenshot-without-subtitles" => KeybindingAction::ScreenshotWithoutSubtitles,
+            "picture-in-picture" => KeybindingAction::PictureInPicture,
+            "toggle-stats" => KeybindingAction::ToggleStats,
+            "cycle-ab-loop" => KeybindingAction::CycleAbLoop,
+            "clear-ab-loop" => KeybindingAction::ClearAbLoop,
+            _ => bail!("Unknown keybinding action: {s}"),
+        };
+
+        if action.amount().is_none() && amount.is_some() {
+            bail!("Keybinding action {name} does not take an amount: {s}");
+        }
+
+        Ok(action)
+    }
+}
+
+fn parse_amount(s: &str, amount: Option<&str>) -> anyhow::Result<i64> {
+    amount
+        .ok_or_else(|| anyhow!("Keybinding action is missing an amount: {s}"))?
+        .parse()
+        .map_err(|err| anyhow!("Invalid amount for keybinding action {s}: {err}"))
+}
+
+impl From<KeybindingAction> for String {
+    fn from(value: KeybindingAction) -> Self {
+        value.to_string()
+    }
+}
+
+/// Video player keybindings, from GTK accelerator strings (e.g. `<Shift>z`) to actions.
+#[derive(Clone, Debug, Serialize, PartialEq)]
+#[serde(transparent)]
+pub struct Keybindings(BTreeMap<String, KeybindingAction>);
+
+// Skip invalid keybindings instead of failing to load the whole config
+impl<'de> Deserialize<'de> for Keybindings {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let keybindings = BTreeMap::<String, String>::deserialize(deserializer)?;
+        Ok(Self(
+            keybindings
+                .into_iter()
+                .filter_map(|(accelerator, action)| match action.parse() {
+                    Ok(action) => Some((accelerator, action)),
+                    Err(err) => {
+                        warn!("Ignoring keybinding for {accelerator}: {err}");
+                        None
+                    }
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        Self(
+            DEFAULT_KEYBINDINGS
+                .iter()
+                .map(|(accelerator, action)| (accelerator.to_string(), *action))
+                .collect(),
+        )
+    }
+}
+
+// Listed in the order they're shown in the preferences and shortcuts window
+const DEFAULT_KEYBINDINGS: &[(&str, KeybindingAction)] = &[
+    ("space", KeybindingAction::TogglePlay),
+    ("Left", KeybindingAction::SkipBackwards),
+    ("Right", KeybindingAction::SkipForwards),
+    ("Up", KeybindingAction::Volume(10)),
+    ("Down", KeybindingAction::Volume(-10)),
+    ("m", KeybindingAction::ToggleMute),
+    ("n", KeybindingAction::ToggleNightMode),
+    ("c", KeybindingAction::ToggleSubtitles),
+    ("f", KeybindingAction::ToggleFullscreen),
+    ("Escape", KeybindingAction::ExitFullscreen),
+    ("p", KeybindingAction::PictureInPicture),
+    ("i", KeybindingAction::ToggleStats),
+    ("comma", KeybindingAction::FrameStepBackwards),
+    ("period", KeybindingAction::FrameStepForwards),
+    ("less", KeybindingAction::SpeedDown),
+    ("greater", KeybindingAction::SpeedUp),
+    ("Page_Up", KeybindingAction::PreviousChapter),
+    ("Page_Down", KeybindingAction::NextChapter),
+    ("z", KeybindingAction::SubtitleDelay(-100)),
+    ("x", KeybindingAction::SubtitleDelay(100)),
+    ("<Shift>z", KeybindingAction::AudioDelay(-100)),
+    ("<Shift>x", KeybindingAction::AudioDelay(100)),
+    ("a", KeybindingAction::CycleAspectRatio),
+    ("minus", KeybindingAction::ZoomOut),
+    ("equal", KeybindingAction::ZoomIn),
+    ("plus", KeybindingAction::ZoomIn),
+    ("w", KeybindingAction::TogglePanscan),
+    ("0", KeybindingAction::ResetVideo),
+    ("s", KeybindingAction::Screenshot),
+    ("<Shift>s", KeybindingAction::ScreenshotWithoutSubtitles),
+    ("l", KeybindingAction::CycleAbLoop),
+    ("<Shift>l", KeybindingAction::ClearAbLoop),
+];
+
+impl Keybindings {
+    pub fn iter(&self) -> impl Iterator<Item = (&str, KeybindingAction)> {
+        self.0
+            .iter()
+            .map(|(accelerator, action)| (accelerator.as_str(), *action))
+    }
+
+    /// Every action that has a keybinding, or has one by default.
+    pub fn actions(&self) -> Vec<KeybindingAction> {
+        let mut actions: Vec<KeybindingAction> = Vec::new();
+        let default_actions = DEFAULT_KEYBINDINGS.iter().map(|(_, action)| *action);
+        for action in default_actions.chain(self.0.values().copied()) {
+            if !actions.contains(&action) {
+                actions.push(action);
+            }
+        }
+        actions
+    }
+
+    pub fn accelerators(&self, action: KeybindingAction) -> Vec<&str> {
+        self.iter()
+            .filter(|(_, bound_action)| *bound_action == action)
+            .map(|(accelerator, _)| accelerator)
+            .collect()
+    }
+
+    /// The action that the accelerator would be taken from, if it was bound to the given action.
+    pub fn conflict(
+        &self,
+        accelerator: &str,
+        action: KeybindingAction,
+    ) -> Option<KeybindingAction> {
+        self.0
+            .get(accelerator)
+            .copied()
+            .filter(|bound_action| *bound_action != action)
+    }
+
+    /// Replace the keys for an action with a single key, unbinding it from any other action.
+    pub fn bind(&mut self, action: KeybindingAction, accelerator: String) {
+        self.unbind(action);
+        self.0.insert(accelerator, action);
+    }
+
+    pub fn unbind(&mut self, action: KeybindingAction) {
+        self.0.retain(|_, bound_action| *bound_action != action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_round_trip() {
+        for action in Keybindings::default().actions() {
+            assert_eq!(
+                action.to_string().parse::<KeybindingAction>().unwrap(),
+                action
+            );
+        }
+
+        assert_eq!(
+            "seek -5".parse::<KeybindingAction>().unwrap(),
+            KeybindingAction::Seek(-5)
+        );
+        assert_eq!(KeybindingAction::Volume(10).to_string(), "volume +10");
+        assert!("seek".parse::<KeybindingAction>().is_err());
+        assert!("toggle-play 5".parse::<KeybindingAction>().is_err());
+        assert!("dance".parse::<KeybindingAction>().is_err());
+    }
+
+    #[test]
+    fn test_bind() {
+        let mut keybindings = Keybindings::default();
+
+        assert_eq!(
+            keybindings.conflict("m", KeybindingAction::TogglePlay),
+            Some(KeybindingAction::ToggleMute)
+        );
+        assert_eq!(
+            keybindings.conflict("m", KeybindingAction::ToggleMute),
+            None
+        );
+
+        keybindings.bind(KeybindingAction::TogglePlay, "m".into());
+        assert_eq!(
+            keybindings.accelerators(KeybindingAction::TogglePlay),
+            ["m"]
+        );
+        assert!(keybindings
+            .accelerators(KeybindingAction::ToggleMute)
+            .is_empty());
+        // Unbound default actions can still be rebound
+        assert!(keybindings
+            .actions()
+            .contains(&KeybindingAction::ToggleMute));
+
+        keybindings.bind(KeybindingAction::ZoomIn, "k".into());
+        assert_eq!(keybindings.accelerators(KeybindingAction::ZoomIn), ["k"]);
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let keybindings: Keybindings = toml::from_str(
+            r#"
+            "<Shift>Left" = "seek -60"
+            k = "toggle-play"
+            j = "dance"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            keybindings.iter().collect::<Vec<_>>(),
+            [
+                ("<Shift>Left", KeybindingAction::Seek(-60)),
+                ("k", KeybindingAction::TogglePlay),
+            ]
+        );
+    }
+}
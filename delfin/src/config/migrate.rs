@@ -1,6 +1,9 @@
 use anyhow::Result;
 
-use super::{versions::config_v1::ConfigV1, Config};
+use super::{
+    versions::{config_v1::ConfigV1, config_v2::ConfigV2},
+    Config,
+};
 
 pub(crate) trait Migrate<T> {
     fn migrate(self) -> T;
@@ -8,14 +11,16 @@ pub(crate) trait Migrate<T> {
 
 pub(crate) enum ConfigVersions {
     V1(ConfigV1),
-    V2(Config),
+    V2(ConfigV2),
+    V3(Config),
 }
 
 impl From<ConfigVersions> for Config {
     fn from(val: ConfigVersions) -> Self {
         match val {
             ConfigVersions::V1(config) => config.migrate(),
-            ConfigVersions::V2(config) => config,
+            ConfigVersions::V2(config) => config.migrate(),
+            ConfigVersions::V3(config) => config,
         }
     }
 }
@@ -26,7 +31,8 @@ impl ConfigVersions {
         let version = config_table.get("version").and_then(|val| val.as_integer());
         Ok(match version {
             Some(1) => Self::V1(toml::from_str(config)?),
-            Some(2) => Self::V2(toml::from_str(config)?),
+            Some(2) => Self::V2(ConfigV2::new(config)?),
+            Some(3) => Self::V3(toml::from_str(config)?),
             // V1 is missing version field, default to V1
             _ => Self::V1(toml::from_str(config)?),
         })
@@ -40,7 +46,10 @@ mod tests {
 
     use crate::config::{
         general::{GeneralConfig, THEME_DARK},
-        video_player_config::{VideoPlayerConfig, VideoPlayerOnLeftClick},
+        video_player_config::{
+            MediaSegmentBehaviour, VideoPlayerConfig, VideoPlayerMediaSegmentsConfig,
+            VideoPlayerOnLeftClick,
+        },
         Account, Server, Window,
     };
 
@@ -113,7 +122,7 @@ italic = false
         let config: Config = config_version.into();
 
         let expected = Config {
-            version: 2,
+            version: 3,
             window: Window {
                 width: 967,
                 height: 670,
@@ -162,4 +171,44 @@ italic = false
 
         Ok(())
     }
+
+    #[test]
+    fn test_migrate_v2() -> Result<()> {
+        let input = r##"
+version = 2
+servers = []
+
+[video_player]
+volume = 0.5
+intro_skipper = true
+intro_skipper_auto_skip = false
+jellyscrub = true
+        "##;
+
+        let config_version = ConfigVersions::new(input)?;
+
+        assert!(
+            matches!(config_version, ConfigVersions::V2(_)),
+            "original config was not version 2"
+        );
+
+        let config: Config = config_version.into();
+
+        let expected = Config {
+            version: 3,
+            video_player: VideoPlayerConfig {
+                volume: 0.5,
+                media_segments: VideoPlayerMediaSegmentsConfig {
+                    intro: MediaSegmentBehaviour::Ask,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(config, expected);
+
+        Ok(())
+    }
 }
@@ -1,4 +1,5 @@
 pub mod general;
+pub mod keybindings;
 mod migrate;
 mod versions;
 pub mod video_player_config;
@@ -28,7 +29,7 @@ pub struct Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
-            version: 2,
+            version: 3,
             window: Window::default(),
             general: GeneralConfig::default(),
             video_player: VideoPlayerConfig::default(),
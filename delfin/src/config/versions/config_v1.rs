@@ -184,7 +184,7 @@ impl Migrate<Config> for ConfigV1 {
             .collect();
 
         Config {
-            version: 2,
+            version: 3,
             window: Window {
                 width: self.window.width,
                 height: self.window.height,
@@ -236,8 +236,15 @@ impl Migrate<Config> for ConfigV1 {
                     }
                 },
                 hls_playback: self.video_player.hls_playback,
+                media_segments: config::video_player_config::VideoPlayerMediaSegmentsConfig {
+                    intro: if self.video_player.intro_skipper_auto_skip {
+                        config::video_player_config::MediaSegmentBehaviour::AutoSkip
+                    } else {
+                        config::video_player_config::MediaSegmentBehaviour::Ask
+                    },
+                    ..Default::default()
+                },
                 intro_skipper: self.video_player.intro_skipper,
-                intro_skipper_auto_skip: self.video_player.intro_skipper_auto_skip,
                 jellyscrub: self.video_player.jellyscrub,
                 ..Default::default()
             },
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use serde::Deserialize;
+
+use crate::config::{migrate::Migrate, video_player_config::MediaSegmentBehaviour, Config};
+
+/// Version 2 only differs from version 3 in how automatically skipping intros was configured,
+/// so everything else can be read as the current config.
+#[derive(Debug, Clone)]
+pub(crate) struct ConfigV2 {
+    config: Config,
+    intro_skipper_auto_skip: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct VideoPlayerConfigV2 {
+    intro_skipper_auto_skip: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConfigV2Fields {
+    video_player: VideoPlayerConfigV2,
+}
+
+impl ConfigV2 {
+    pub(crate) fn new(config: &str) -> Result<Self> {
+        let fields: ConfigV2Fields = toml::from_str(config)?;
+        Ok(Self {
+            config: toml::from_str(config)?,
+            intro_skipper_auto_skip: fields.video_player.intro_skipper_auto_skip,
+        })
+    }
+}
+
+impl Migrate<Config> for ConfigV2 {
+    fn migrate(self) -> Config {
+        let mut config = self.config;
+        config.version = 3;
+        if let Some(auto_skip) = self.intro_skipper_auto_skip {
+            config.video_player.media_segments.intro = if auto_skip {
+                MediaSegmentBehaviour::AutoSkip
+            } else {
+                MediaSegmentBehaviour::Ask
+            };
+        }
+        config
+    }
+}
@@ -1 +1,2 @@
 pub(crate) mod config_v1;
+pub(crate) mod config_v2;
@@ -1,11 +1,16 @@
-use std::{cell::RefCell, sync::Arc};
+use std::{cell::RefCell, path::PathBuf, sync::Arc};
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::keybindings::Keybindings,
+    jellyfin_api::api::{media_segments::MediaSegmentType, user::SubtitleMode},
     utils::round::round_one_place,
-    video_player::backends::{
-        mpv::VideoPlayerBackendMpv, VideoPlayerBackend, VideoPlayerSubtitleFont,
+    video_player::{
+        backends::{
+            mpv::VideoPlayerBackendMpv, AudioFilters, VideoPlayerBackend, VideoPlayerSubtitleFont,
+        },
+        screenshot::SCREENSHOT_FILENAME_DEFAULT,
     },
 };
 
@@ -15,16 +20,29 @@ pub struct VideoPlayerConfig {
     pub position_update_frequency: usize,
     pub volume: f64,
     pub muted: bool,
+    // Name of the audio output device, or None to pick one automatically
+    pub audio_device: Option<String>,
+    pub audio_passthrough: bool,
+    pub night_mode: bool,
+    pub normalize_loudness: bool,
 
     pub skip_backwards_amount: usize,
     pub skip_forwards_amount: usize,
     pub on_left_click: VideoPlayerOnLeftClick,
+    pub on_scroll: VideoPlayerOnScroll,
     pub duration_display: DurationDisplay,
+    pub resume_behaviour: ResumeBehaviour,
+    pub remember_sync_offsets: bool,
+    pub keybindings: Keybindings,
+
+    // Where screenshots are saved, or None to save them in the user's pictures folder
+    pub screenshot_directory: Option<PathBuf>,
+    pub screenshot_filename: String,
 
     pub subtitles: VideoPlayerSubtitlesConfig,
 
+    pub media_segments: VideoPlayerMediaSegmentsConfig,
     pub intro_skipper: bool,
-    pub intro_skipper_auto_skip: bool,
     pub jellyscrub: bool,
 
     pub backend: VideoPlayerBackendPreference,
@@ -37,23 +55,44 @@ impl Default for VideoPlayerConfig {
             position_update_frequency: 10,
             volume: 1.0,
             muted: false,
+            audio_device: None,
+            audio_passthrough: false,
+            night_mode: false,
+            normalize_loudness: false,
 
             skip_backwards_amount: 10,
             skip_forwards_amount: 30,
             on_left_click: VideoPlayerOnLeftClick::default(),
+            on_scroll: VideoPlayerOnScroll::default(),
             duration_display: DurationDisplay::default(),
+            resume_behaviour: ResumeBehaviour::default(),
+            remember_sync_offsets: false,
+            keybindings: Keybindings::default(),
+
+            screenshot_directory: None,
+            screenshot_filename: SCREENSHOT_FILENAME_DEFAULT.into(),
 
             subtitles: VideoPlayerSubtitlesConfig::default(),
 
+            media_segments: VideoPlayerMediaSegmentsConfig::default(),
+
             backend: VideoPlayerBackendPreference::default(),
             hls_playback: false,
             intro_skipper: true,
-            intro_skipper_auto_skip: true,
             jellyscrub: true,
         }
     }
 }
 
+impl VideoPlayerConfig {
+    pub fn audio_filters(&self) -> AudioFilters {
+        AudioFilters {
+            night_mode: self.night_mode,
+            normalize_loudness: self.normalize_loudness,
+        }
+    }
+}
+
 #[derive(Debug, Default, Deserialize, Serialize, PartialEq, Clone, Copy)]
 pub enum VideoPlayerOnLeftClick {
     #[default]
@@ -61,7 +100,65 @@ pub enum VideoPlayerOnLeftClick {
     ToggleControls,
 }
 
+/// What scrolling over the video does. Touchscreen gestures aren't affected.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum VideoPlayerOnScroll {
+    #[default]
+    Volume,
+    Seek,
+}
+
+impl From<VideoPlayerOnScroll> for u32 {
+    fn from(value: VideoPlayerOnScroll) -> Self {
+        match value {
+            VideoPlayerOnScroll::Volume => 0,
+            VideoPlayerOnScroll::Seek => 1,
+        }
+    }
+}
+
+impl From<u32> for VideoPlayerOnScroll {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => VideoPlayerOnScroll::Volume,
+            1 => VideoPlayerOnScroll::Seek,
+            _ => unreachable!("scroll action index {value} does not exist"),
+        }
+    }
+}
+
+/// What to do when playing media that was partially watched.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum ResumeBehaviour {
+    #[default]
+    Resume,
+    Ask,
+    Restart,
+}
+
+impl From<ResumeBehaviour> for u32 {
+    fn from(value: ResumeBehaviour) -> Self {
+        match value {
+            ResumeBehaviour::Resume => 0,
+            ResumeBehaviour::Ask => 1,
+            ResumeBehaviour::Restart => 2,
+        }
+    }
+}
+
+impl From<u32> for ResumeBehaviour {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => ResumeBehaviour::Resume,
+            1 => ResumeBehaviour::Ask,
+            2 => ResumeBehaviour::Restart,
+            _ => unreachable!("resume behaviour index {value} does not exist"),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
 pub struct VideoPlayerSubtitlesConfig {
     #[serde(serialize_with = "round_one_place")]
     pub scale: f64,
@@ -69,6 +166,15 @@ pub struct VideoPlayerSubtitlesConfig {
     pub background_colour: String,
     pub position: u32,
     pub font: VideoPlayerSubtitleFont,
+
+    // Overrides for the track selection preferences from the user's Jellyfin configuration
+    pub audio_language: Option<String>,
+    pub subtitle_language: Option<String>,
+    pub subtitle_mode: Option<SubtitleMode>,
+
+    // Secondary subtitles, shown at the same time as the main subtitles
+    pub secondary_position: u32,
+    pub secondary_language: Option<String>,
 }
 
 impl Default for VideoPlayerSubtitlesConfig {
@@ -79,6 +185,92 @@ impl Default for VideoPlayerSubtitlesConfig {
             background_colour: "#00000000".into(),
             position: 100,
             font: VideoPlayerSubtitleFont::default(),
+
+            audio_language: None,
+            subtitle_language: None,
+            subtitle_mode: None,
+
+            secondary_position: 0,
+            secondary_language: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum MediaSegmentBehaviour {
+    #[default]
+    Ask,
+    AutoSkip,
+    Ignore,
+}
+
+impl From<MediaSegmentBehaviour> for u32 {
+    fn from(value: MediaSegmentBehaviour) -> Self {
+        match value {
+            MediaSegmentBehaviour::Ask => 0,
+            MediaSegmentBehaviour::AutoSkip => 1,
+            MediaSegmentBehaviour::Ignore => 2,
+        }
+    }
+}
+
+impl From<u32> for MediaSegmentBehaviour {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => MediaSegmentBehaviour::Ask,
+            1 => MediaSegmentBehaviour::AutoSkip,
+            2 => MediaSegmentBehaviour::Ignore,
+            _ => unreachable!("media segment behaviour index {value} does not exist"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct VideoPlayerMediaSegmentsConfig {
+    pub intro: MediaSegmentBehaviour,
+    pub outro: MediaSegmentBehaviour,
+    pub recap: MediaSegmentBehaviour,
+    pub preview: MediaSegmentBehaviour,
+    pub commercial: MediaSegmentBehaviour,
+}
+
+impl Default for VideoPlayerMediaSegmentsConfig {
+    fn default() -> Self {
+        Self {
+            intro: MediaSegmentBehaviour::AutoSkip,
+            outro: MediaSegmentBehaviour::Ask,
+            recap: MediaSegmentBehaviour::Ask,
+            preview: MediaSegmentBehaviour::Ask,
+            commercial: MediaSegmentBehaviour::Ask,
+        }
+    }
+}
+
+impl VideoPlayerMediaSegmentsConfig {
+    pub fn behaviour(&self, segment_type: MediaSegmentType) -> MediaSegmentBehaviour {
+        match segment_type {
+            MediaSegmentType::Intro => self.intro,
+            MediaSegmentType::Outro => self.outro,
+            MediaSegmentType::Recap => self.recap,
+            MediaSegmentType::Preview => self.preview,
+            MediaSegmentType::Commercial => self.commercial,
+            MediaSegmentType::Unknown => MediaSegmentBehaviour::Ignore,
+        }
+    }
+
+    pub fn set_behaviour(
+        &mut self,
+        segment_type: MediaSegmentType,
+        behaviour: MediaSegmentBehaviour,
+    ) {
+        match segment_type {
+            MediaSegmentType::Intro => self.intro = behaviour,
+            MediaSegmentType::Outro => self.outro = behaviour,
+            MediaSegmentType::Recap => self.recap = behaviour,
+            MediaSegmentType::Preview => self.preview = behaviour,
+            MediaSegmentType::Commercial => self.commercial = behaviour,
+            MediaSegmentType::Unknown => {}
         }
     }
 }
@@ -90,6 +282,23 @@ pub enum VideoPlayerBackendPreference {
     Gst,
 }
 
+impl VideoPlayerBackendPreference {
+    // Falls back to MPV when GStreamer isn't available, see the conversion into a backend below
+    fn uses_gst(self) -> bool {
+        cfg!(feature = "gst") && self == VideoPlayerBackendPreference::Gst
+    }
+
+    // GStreamer's player only renders one subtitle track at a time
+    pub fn supports_secondary_subtitles(self) -> bool {
+        !self.uses_gst()
+    }
+
+    // GStreamer's player always decodes audio
+    pub fn supports_audio_passthrough(self) -> bool {
+        !self.uses_gst()
+    }
+}
+
 impl From<VideoPlayerBackendPreference> for Arc<RefCell<dyn VideoPlayerBackend>> {
     fn from(val: VideoPlayerBackendPreference) -> Self {
         match val {
@@ -1,6 +1,6 @@
 use relm4::{once_cell::sync::Lazy, SharedState};
 
-use crate::{config::Config, utils::shift_state::ShiftState};
+use crate::{config::Config, utils::shift_state::ShiftState, video_player::backends::AudioDevice};
 
 pub static CONFIG: Lazy<SharedState<Config>> = Lazy::new(|| {
     let state = SharedState::new();
@@ -13,3 +13,6 @@ pub static SHIFT_STATE: Lazy<SharedState<ShiftState>> = Lazy::new(|| {
     *state.write() = ShiftState::default();
     state
 });
+
+// Audio output devices reported by the video player
+pub static AUDIO_DEVICES: Lazy<SharedState<Vec<AudioDevice>>> = Lazy::new(SharedState::new);
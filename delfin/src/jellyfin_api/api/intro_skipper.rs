@@ -1,5 +1,3 @@
-use std::ops::Range;
-
 use anyhow::Result;
 use reqwest::StatusCode;
 use serde::Deserialize;
@@ -16,29 +14,6 @@ pub struct IntroTimestamps {
     pub hide_skip_prompt_at: f32,
 }
 
-impl IntroTimestamps {
-    pub fn range_intro(&self) -> Range<f32> {
-        Range {
-            start: self.intro_start,
-            end: self.intro_end,
-        }
-    }
-
-    pub fn range_show(&self) -> Range<f32> {
-        Range {
-            start: self.show_skip_prompt_at,
-            end: self.hide_skip_prompt_at,
-        }
-    }
-
-    pub fn range_auto_skip_show(&self) -> Range<f32> {
-        Range {
-            start: self.show_skip_prompt_at,
-            end: self.intro_start,
-        }
-    }
-}
-
 impl ApiClient {
     pub async fn get_intro_timestamps(&self, episode_id: &Uuid) -> Result<Option<IntroTimestamps>> {
         let url = self
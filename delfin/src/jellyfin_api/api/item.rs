@@ -25,6 +25,17 @@ impl ApiClient {
         }
     }
 
+    /// Stream URL that has the server transcode the video, for when it can't be played directly.
+    pub fn get_transcode_stream_url(&self, item_id: &Uuid) -> String {
+        self.root
+            .join(&format!(
+                "Videos/{}/main.m3u8?videoCodec=h264&audioCodec=aac&api_key={}",
+                item_id, self.account.access_token
+            ))
+            .unwrap()
+            .to_string()
+    }
+
     pub async fn get_item(&self, item_id: &Uuid) -> Result<BaseItemDto> {
         let mut url = self
             .root
@@ -20,6 +20,12 @@ pub struct GetItemsOptions {
     start_index: Option<usize>,
     #[builder(default)]
     limit: Option<usize>,
+    #[builder(default)]
+    ids: Option<String>,
+    #[builder(default)]
+    person_ids: Option<String>,
+    #[builder(default)]
+    genre_ids: Option<String>,
 }
 
 impl ApiClient {
@@ -30,12 +36,21 @@ impl ApiClient {
 
         {
             let mut query_pairs = url.query_pairs_mut();
+            if let Some(genre_ids) = &options.genre_ids {
+                query_pairs.append_pair("GenreIds", genre_ids);
+            }
+            if let Some(ids) = &options.ids {
+                query_pairs.append_pair("Ids", ids);
+            }
             if let Some(include_item_types) = &options.include_item_types {
                 query_pairs.append_pair("IncludeItemTypes", include_item_types);
             }
             if let Some(limit) = &options.limit {
                 query_pairs.append_pair("Limit", &limit.to_string());
             }
+            if let Some(person_ids) = &options.person_ids {
+                query_pairs.append_pair("PersonIds", person_ids);
+            }
             query_pairs.append_pair("Recursive", &options.recursive.to_string());
             if let Some(search_term) = &options.search_term {
                 query_pairs.append_pair("SearchTerm", search_term);
@@ -0,0 +1,78 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::jellyfin_api::api_client::ApiClient;
+
+use super::intro_skipper::IntroTimestamps;
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+pub enum MediaSegmentType {
+    #[serde(other)]
+    Unknown,
+    Commercial,
+    Preview,
+    Recap,
+    Outro,
+    Intro,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MediaSegment {
+    #[serde(rename = "Type")]
+    pub type_: MediaSegmentType,
+    pub start_ticks: i64,
+    pub end_ticks: i64,
+}
+
+impl MediaSegment {
+    pub fn start(&self) -> f32 {
+        self.start_ticks as f32 / 10_000_000.0
+    }
+
+    pub fn end(&self) -> f32 {
+        self.end_ticks as f32 / 10_000_000.0
+    }
+
+    pub fn range(&self) -> Range<f32> {
+        Range {
+            start: self.start(),
+            end: self.end(),
+        }
+    }
+}
+
+impl From<IntroTimestamps> for MediaSegment {
+    fn from(value: IntroTimestamps) -> Self {
+        Self {
+            type_: MediaSegmentType::Intro,
+            start_ticks: (value.intro_start * 10_000_000.0) as i64,
+            end_ticks: (value.intro_end * 10_000_000.0) as i64,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct MediaSegmentQueryResult {
+    items: Vec<MediaSegment>,
+}
+
+impl ApiClient {
+    pub async fn get_media_segments(&self, item_id: &Uuid) -> Result<Vec<MediaSegment>> {
+        let url = self.root.join(&format!("MediaSegments/{item_id}"))?;
+
+        let res: MediaSegmentQueryResult = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(res.items)
+    }
+}
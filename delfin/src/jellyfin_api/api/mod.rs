@@ -3,7 +3,10 @@ pub mod intro_skipper;
 pub mod item;
 pub mod items;
 pub mod latest;
+pub mod media_segments;
 pub mod playstate;
+pub mod remote_subtitles;
+pub mod search;
 pub mod shows;
 pub mod system;
 pub mod trickplay;
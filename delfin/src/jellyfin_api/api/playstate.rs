@@ -1,41 +1,106 @@
 use anyhow::Result;
 use jellyfin_api::types::UserItemDataDto;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{jellyfin_api::api_client::ApiClient, utils::ticks::seconds_to_ticks};
 
+/// How the video is being streamed from the server.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub enum PlayMethod {
+    #[default]
+    DirectPlay,
+    DirectStream,
+    Transcode,
+}
+
+/// Identifies what's being played, which stays the same for the whole playback session.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlaybackSession {
+    pub play_session_id: Option<String>,
+    pub media_source_id: Option<String>,
+    pub play_method: PlayMethod,
+    pub can_seek: bool,
+}
+
+/// Player settings that can change during playback.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlaybackStatus {
+    pub is_paused: bool,
+    pub is_muted: bool,
+    /// From 0 to 100
+    pub volume_level: u32,
+    pub playback_rate: f64,
+    pub audio_stream_index: Option<i64>,
+    pub subtitle_stream_index: Option<i64>,
+}
+
+impl Default for PlaybackStatus {
+    fn default() -> Self {
+        Self {
+            is_paused: false,
+            is_muted: false,
+            volume_level: 100,
+            playback_rate: 1.0,
+            audio_stream_index: None,
+            subtitle_stream_index: None,
+        }
+    }
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct ReportPlaybackStartedReq {
+pub struct ReportPlaybackStartedReq<'a> {
     pub item_id: Uuid,
+    #[serde(flatten)]
+    pub session: &'a PlaybackSession,
+    #[serde(flatten)]
+    pub status: &'a PlaybackStatus,
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct ReportPlaybackProgressReq {
+pub struct ReportPlaybackProgressReq<'a> {
     pub event_name: String,
     pub item_id: Uuid,
     pub position_ticks: usize,
-    pub is_paused: bool,
+    #[serde(flatten)]
+    pub session: &'a PlaybackSession,
+    #[serde(flatten)]
+    pub status: &'a PlaybackStatus,
 }
 
 #[derive(Serialize)]
 #[serde(rename_all = "PascalCase")]
-pub struct ReportPlaybackStoppedReq {
+pub struct ReportPlaybackStoppedReq<'a> {
     pub item_id: Uuid,
     pub position_ticks: usize,
+    pub play_session_id: Option<&'a str>,
+    pub media_source_id: Option<&'a str>,
+    pub failed: bool,
 }
 
 impl ApiClient {
-    pub async fn report_playback_started(&self, item_id: Uuid) -> Result<()> {
+    pub async fn report_playback_started(
+        &self,
+        item_id: Uuid,
+        session: &PlaybackSession,
+        status: &PlaybackStatus,
+    ) -> Result<()> {
         let url = self.root.join("Sessions/Playing").unwrap();
 
         self.client
             .post(url)
-            .json(&ReportPlaybackStartedReq { item_id })
+            .json(&ReportPlaybackStartedReq {
+                item_id,
+                session,
+                status,
+            })
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         Ok(())
     }
@@ -45,7 +110,8 @@ impl ApiClient {
         event_name: &str,
         item_id: Uuid,
         position_secs: usize,
-        is_paused: bool,
+        session: &PlaybackSession,
+        status: &PlaybackStatus,
     ) -> Result<()> {
         let url = self.root.join("Sessions/Playing/Progress").unwrap();
 
@@ -55,15 +121,25 @@ impl ApiClient {
                 event_name: event_name.into(),
                 item_id,
                 position_ticks: seconds_to_ticks(position_secs),
-                is_paused,
+                session,
+                status,
             })
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         Ok(())
     }
 
-    pub async fn report_playback_stopped(&self, item_id: Uuid, position_secs: usize) -> Result<()> {
+    /// Reports that playback stopped. If playback failed, the server leaves the item's playback
+    /// position alone.
+    pub async fn report_playback_stopped(
+        &self,
+        item_id: Uuid,
+        position_secs: usize,
+        session: &PlaybackSession,
+        failed: bool,
+    ) -> Result<()> {
         let url = self.root.join("Sessions/Playing/Stopped").unwrap();
 
         self.client
@@ -71,9 +147,13 @@ impl ApiClient {
             .json(&ReportPlaybackStoppedReq {
                 item_id,
                 position_ticks: seconds_to_ticks(position_secs),
+                play_session_id: session.play_session_id.as_deref(),
+                media_source_id: session.media_source_id.as_deref(),
+                failed,
             })
             .send()
-            .await?;
+            .await?
+            .error_for_status()?;
 
         Ok(())
     }
@@ -92,3 +172,52 @@ impl ApiClient {
         Ok(self.client.delete(url).send().await?.json().await?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_report_playback_progress_req() {
+        let session = PlaybackSession {
+            play_session_id: Some("session".into()),
+            media_source_id: Some("source".into()),
+            play_method: PlayMethod::Transcode,
+            can_seek: true,
+        };
+        let status = PlaybackStatus {
+            is_muted: true,
+            volume_level: 50,
+            audio_stream_index: Some(1),
+            ..Default::default()
+        };
+        let req = ReportPlaybackProgressReq {
+            event_name: "volumechange".into(),
+            item_id: Uuid::nil(),
+            position_ticks: seconds_to_ticks(1),
+            session: &session,
+            status: &status,
+        };
+
+        assert_eq!(
+            serde_json::to_value(&req).unwrap(),
+            json!({
+                "EventName": "volumechange",
+                "ItemId": Uuid::nil(),
+                "PositionTicks": 10_000_000,
+                "PlaySessionId": "session",
+                "MediaSourceId": "source",
+                "PlayMethod": "Transcode",
+                "CanSeek": true,
+                "IsPaused": false,
+                "IsMuted": true,
+                "VolumeLevel": 50,
+                "PlaybackRate": 1.0,
+                "AudioStreamIndex": 1,
+                "SubtitleStreamIndex": null,
+            })
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use anyhow::Result;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::jellyfin_api::api_client::ApiClient;
+
+/// A subtitle found by one of the server's subtitle providers.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RemoteSubtitleInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub provider_name: Option<String>,
+    pub format: Option<String>,
+    pub author: Option<String>,
+    pub comment: Option<String>,
+    pub download_count: Option<u32>,
+    pub is_hash_match: Option<bool>,
+    #[serde(rename = "ThreeLetterISOLanguageName")]
+    pub language: Option<String>,
+}
+
+impl ApiClient {
+    /// Search the server's subtitle providers for subtitles in the given language (ISO 639-2).
+    pub async fn search_remote_subtitles(
+        &self,
+        item_id: &Uuid,
+        language: &str,
+    ) -> Result<Vec<RemoteSubtitleInfo>> {
+        let url = self.root.join(&format!(
+            "Items/{item_id}/RemoteSearch/Subtitles/{language}"
+        ))?;
+
+        let res = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(res)
+    }
+
+    /// Have the server download a subtitle and add it to the item.
+    pub async fn download_remote_subtitles(&self, item_id: &Uuid, subtitle_id: &str) -> Result<()> {
+        let url = self.root.join(&format!(
+            "Items/{item_id}/RemoteSearch/Subtitles/{subtitle_id}"
+        ))?;
+
+        self.client.post(url).send().await?.error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        sync::{Arc, RwLock},
+        thread::{self, JoinHandle},
+    };
+
+    use super::*;
+    use crate::config::{Account, Config, Server};
+
+    /// Stand-in for the Jellyfin server, which responds to a single request with the given body.
+    /// The returned handle resolves to the request line the server received.
+    fn serve_once(body: &'static str) -> (String, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            // Skip headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+
+            write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len(),
+            )
+            .unwrap();
+
+            request_line.trim().to_string()
+        });
+
+        (url, handle)
+    }
+
+    fn api_client(url: String) -> ApiClient {
+        let account = Account {
+            id: Uuid::nil(),
+            username: "test".into(),
+            access_token: "token".into(),
+            device_id: Uuid::nil(),
+        };
+        let server = Server {
+            url,
+            accounts: vec![account.clone()],
+            ..Default::default()
+        };
+        ApiClient::new(Arc::new(RwLock::new(Config::default())), &server, &account)
+    }
+
+    #[tokio::test]
+    async fn test_search_remote_subtitles() {
+        let (url, server) = serve_once(
+            r#"[{"Id":"abc123","Name":"Episode.1.srt","ProviderName":"Open Subtitles","Format":"srt","DownloadCount":42,"ThreeLetterISOLanguageName":"eng"}]"#,
+        );
+        let item_id = Uuid::nil();
+
+        let results = api_client(url)
+            .search_remote_subtitles(&item_id, "eng")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            server.join().unwrap(),
+            format!("GET /Items/{item_id}/RemoteSearch/Subtitles/eng HTTP/1.1")
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "abc123");
+        assert_eq!(results[0].download_count, Some(42));
+        assert_eq!(results[0].language.as_deref(), Some("eng"));
+    }
+
+    #[tokio::test]
+    async fn test_download_remote_subtitles() {
+        let (url, server) = serve_once("");
+        let item_id = Uuid::nil();
+
+        api_client(url)
+            .download_remote_subtitles(&item_id, "abc123")
+            .await
+            .unwrap();
+
+        assert_eq!(
+            server.join().unwrap(),
+            format!("POST /Items/{item_id}/RemoteSearch/Subtitles/abc123 HTTP/1.1")
+        );
+    }
+}
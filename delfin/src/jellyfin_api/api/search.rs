@@ -0,0 +1,43 @@
+use anyhow::Result;
+use jellyfin_api::types::BaseItemKind;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::jellyfin_api::api_client::ApiClient;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SearchHint {
+    pub id: Uuid,
+    pub name: String,
+    #[serde(rename = "Type")]
+    pub type_: BaseItemKind,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct SearchHintResult {
+    pub search_hints: Vec<SearchHint>,
+    pub total_record_count: usize,
+}
+
+impl ApiClient {
+    pub async fn get_search_hints(
+        &self,
+        search_term: &str,
+        include_item_types: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchHint>> {
+        let mut url = self.root.join("Search/Hints")?;
+
+        url.query_pairs_mut()
+            .append_pair("userId", &self.account.id.to_string())
+            .append_pair("searchTerm", search_term)
+            .append_pair("includeItemTypes", include_item_types)
+            .append_pair("limit", &limit.to_string());
+
+        let res: SearchHintResult = self.client.get(url).send().await?.json().await?;
+
+        Ok(res.search_hints)
+    }
+}
@@ -42,6 +42,50 @@ pub struct AuthenticateByNameResSessionInfo {
     pub device_id: Uuid,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UserRes {
+    configuration: UserConfiguration,
+}
+
+/// The parts of the user's Jellyfin configuration that affect playback.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct UserConfiguration {
+    pub audio_language_preference: Option<String>,
+    pub play_default_audio_track: bool,
+    pub subtitle_language_preference: Option<String>,
+    pub subtitle_mode: SubtitleMode,
+}
+
+impl Default for UserConfiguration {
+    fn default() -> Self {
+        Self {
+            audio_language_preference: None,
+            play_default_audio_track: true,
+            subtitle_language_preference: None,
+            subtitle_mode: SubtitleMode::default(),
+        }
+    }
+}
+
+/// How subtitle tracks are picked when playback starts.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub enum SubtitleMode {
+    /// Use subtitles marked as default or forced.
+    #[default]
+    Default,
+    /// Always use subtitles in the preferred language.
+    Always,
+    /// Only use subtitles marked as forced.
+    OnlyForced,
+    /// Use subtitles when the audio is in a different language than the preferred subtitle
+    /// language, otherwise only use forced subtitles.
+    Smart,
+    /// Never use subtitles.
+    None,
+}
+
 pub async fn authenticate_by_name(
     url: &str,
     device_id: &Uuid,
@@ -93,6 +137,12 @@ pub async fn get_user_avatar(url: &str, user_id: &Uuid) -> Result<VecDeque<u8>>
 }
 
 impl ApiClient {
+    pub async fn get_user_configuration(&self) -> Result<UserConfiguration> {
+        let url = self.root.join(&format!("Users/{}", self.account.id))?;
+        let res: UserRes = self.client.get(url).send().await?.json().await?;
+        Ok(res.configuration)
+    }
+
     pub async fn sign_out(&self) -> Result<()> {
         let url = self.root.join("/Sessions/Logout")?;
         self.client.post(url).send().await?;
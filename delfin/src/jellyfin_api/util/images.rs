@@ -1,5 +1,6 @@
 use anyhow::{bail, Result};
 use jellyfin_api::types::BaseItemDto;
+use uuid::Uuid;
 
 use crate::jellyfin_api::api_client::ApiClient;
 
@@ -189,4 +190,22 @@ impl ApiClient {
 
         Ok(url.to_string())
     }
+
+    pub fn get_chapter_image_url(
+        &self,
+        item_id: &Uuid,
+        chapter_index: usize,
+        image_tag: &str,
+        width: i32,
+    ) -> Result<String> {
+        let mut url = self
+            .root
+            .join(&format!("Items/{item_id}/Images/Chapter/{chapter_index}"))?;
+        url.query_pairs_mut()
+            .append_pair("fillWidth", &width.to_string())
+            .append_pair("quality", "96")
+            .append_pair("tag", image_tag);
+
+        Ok(url.to_string())
+    }
 }
@@ -9,7 +9,7 @@ use crate::{globals::SHIFT_STATE, jellyfin_api::api_client::ApiClient};
 
 use super::{
     media_button::MediaButton,
-    media_tile::{MediaTile, MediaTileDisplay},
+    media_tile::{MediaTile, MediaTileDisplay, MediaTileOutput},
 };
 
 const MIN_PADDING: i32 = 24;
@@ -58,6 +58,7 @@ pub(crate) enum MediaCarouselInput {
 #[derive(Debug)]
 pub(crate) enum MediaCarouselOutput {
     LabelClicked,
+    MediaActivated,
 }
 
 impl MediaTileDisplay {
@@ -211,7 +212,9 @@ impl Component for MediaCarousel {
                 MediaCarouselType::Tiles => MediaCarouselItem::Tile(
                     MediaTile::builder()
                         .launch((media.clone(), media_tile_display, api_client.clone()))
-                        .detach(),
+                        .forward(sender.output_sender(), |output| match output {
+                            MediaTileOutput::Activated => MediaCarouselOutput::MediaActivated,
+                        }),
                 ),
                 MediaCarouselType::Buttons => MediaCarouselItem::Button(
                     MediaButton::builder()
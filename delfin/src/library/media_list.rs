@@ -142,8 +142,16 @@ impl MediaList {
                     label,
                     label_clickable,
                 })
-                .forward(sender.output_sender(), move |msg| match msg {
-                    MediaCarouselOutput::LabelClicked => MediaListOutput::LabelClicked(view_id),
+                .connect_receiver({
+                    let sender = sender.clone();
+                    move |_, msg| match msg {
+                        MediaCarouselOutput::LabelClicked => {
+                            sender
+                                .output(MediaListOutput::LabelClicked(view_id))
+                                .unwrap();
+                        }
+                        MediaCarouselOutput::MediaActivated => {}
+                    }
                 });
             root.append(carousel.widget());
             MediaListContents::Carousel(carousel)
@@ -3,7 +3,8 @@ use std::{collections::VecDeque, sync::Arc};
 use gdk::Texture;
 use jellyfin_api::types::{BaseItemDto, BaseItemKind};
 use relm4::{
-    gtk::{self, gdk, gdk_pixbuf, glib::markup_escape_text, prelude::*},
+    actions::{RelmAction, RelmActionGroup},
+    gtk::{self, gdk, gdk_pixbuf, gio, glib::markup_escape_text, prelude::*},
     prelude::{AsyncComponent, AsyncComponentParts},
     AsyncComponentSender,
 };
@@ -14,9 +15,18 @@ use crate::{
     globals::CONFIG,
     jellyfin_api::api_client::ApiClient,
     tr,
-    utils::{display_years::DisplayYears, item_name::ItemName, playable::get_next_playable_media},
+    utils::{
+        display_years::DisplayYears,
+        item_name::ItemName,
+        playable::{get_next_playable_media, get_resume_position},
+        timestamp::seconds_to_timestamp,
+    },
 };
 
+relm4::new_action_group!(MediaTileActionGroup, "media_tile");
+relm4::new_stateless_action!(ResumeAction, MediaTileActionGroup, "resume");
+relm4::new_stateless_action!(RestartAction, MediaTileActionGroup, "restart");
+
 #[derive(Clone, Copy)]
 pub enum MediaTileDisplay {
     Cover,
@@ -57,9 +67,16 @@ pub struct MediaTile {
 #[derive(Debug)]
 pub enum MediaTileInput {
     Play,
+    PlayFrom(usize),
     ShowDetails,
 }
 
+#[derive(Debug)]
+pub enum MediaTileOutput {
+    /// The media was played or its details were shown
+    Activated,
+}
+
 #[derive(Debug)]
 pub enum MediaTileCommandOutput {
     ThumbnailLoaded(Option<Texture>),
@@ -69,7 +86,7 @@ pub enum MediaTileCommandOutput {
 impl AsyncComponent for MediaTile {
     type Init = (BaseItemDto, MediaTileDisplay, Arc<ApiClient>);
     type Input = MediaTileInput;
-    type Output = ();
+    type Output = MediaTileOutput;
     type CommandOutput = MediaTileCommandOutput;
 
     view! {
@@ -213,6 +230,10 @@ impl AsyncComponent for MediaTile {
 
         let widgets = view_output!();
 
+        if let Some(resume_position) = get_resume_position(&model.media) {
+            add_resume_menu(&root, &widgets.overlay, &sender, resume_position);
+        }
+
         sender.oneshot_command({
             let media = model.media.clone();
             async move {
@@ -227,7 +248,7 @@ impl AsyncComponent for MediaTile {
     async fn update(
         &mut self,
         message: Self::Input,
-        _sender: AsyncComponentSender<Self>,
+        sender: AsyncComponentSender<Self>,
         _root: &Self::Root,
     ) {
         match message {
@@ -252,6 +273,9 @@ impl AsyncComponent for MediaTile {
                     }
                 };
             }
+            MediaTileInput::PlayFrom(start_position) => {
+                APP_BROKER.send(AppInput::PlayVideoFrom(self.media.clone(), start_position));
+            }
             MediaTileInput::ShowDetails => {
                 match self.media.type_ {
                     Some(BaseItemKind::CollectionFolder) => {
@@ -263,6 +287,9 @@ impl AsyncComponent for MediaTile {
                 };
             }
         }
+
+        // Nothing listens for this when the tile is detached
+        let _ = sender.output(MediaTileOutput::Activated);
     }
 
     async fn update_cmd(
@@ -279,6 +306,53 @@ impl AsyncComponent for MediaTile {
     }
 }
 
+/// Lets the user choose between resuming partially watched media and starting it from the
+/// beginning, instead of following the resume behaviour setting.
+fn add_resume_menu(
+    root: &gtk::Box,
+    overlay: &gtk::Overlay,
+    sender: &AsyncComponentSender<MediaTile>,
+    resume_position: usize,
+) {
+    let menu = gio::Menu::new();
+    menu.append_item(&RelmAction::<ResumeAction>::to_menu_item(tr!(
+        "vp-resume-dialog.response-resume",
+        { "position" => seconds_to_timestamp(resume_position) },
+    )));
+    menu.append_item(&RelmAction::<RestartAction>::to_menu_item(tr!(
+        "vp-resume-dialog.response-restart"
+    )));
+
+    let context_menu = gtk::PopoverMenu::from_model(Some(&menu));
+    root.append(&context_menu);
+
+    let right_click = gtk::GestureClick::builder()
+        .button(gdk::BUTTON_SECONDARY)
+        .build();
+    right_click.connect_pressed(move |_, _, _, _| {
+        context_menu.popup();
+    });
+    overlay.add_controller(right_click);
+
+    let resume_action: RelmAction<ResumeAction> = RelmAction::new_stateless({
+        let sender = sender.clone();
+        move |_| {
+            sender.input(MediaTileInput::PlayFrom(resume_position));
+        }
+    });
+    let restart_action: RelmAction<RestartAction> = RelmAction::new_stateless({
+        let sender = sender.clone();
+        move |_| {
+            sender.input(MediaTileInput::PlayFrom(0));
+        }
+    });
+
+    let mut group = RelmActionGroup::<MediaTileActionGroup>::new();
+    group.add_action(resume_action);
+    group.add_action(restart_action);
+    group.register_for_widget(root);
+}
+
 impl MediaTile {
     pub fn new(media: BaseItemDto, api_client: Arc<ApiClient>) -> Self {
         Self {
@@ -46,7 +46,7 @@ use self::{
     home::{Home, HomeInit},
     search::{
         search_bar::SearchBar,
-        search_results::{SearchResults, SearchResultsInput},
+        search_results::{SearchResults, SearchResultsInput, SearchResultsOutput},
     },
 };
 
@@ -81,6 +81,8 @@ pub enum LibraryInput {
     Hidden,
     ViewStackChildVisible(String),
     SearchChanged(String),
+    SearchSubmitted,
+    SetSearchText(String),
     SearchingChanged(bool),
     ShowSearch,
     ShowCollections,
@@ -154,6 +156,11 @@ impl Component for Library {
                                     None
                                 })
                             ),
+                            connect: ("submit", false, glib::clone!(@strong sender => move |_| {
+                                    sender.input(LibraryInput::SearchSubmitted);
+                                    None
+                                })
+                            ),
                         },
                     },
 
@@ -287,7 +294,14 @@ impl Component for Library {
                 .detach(),
             api_client: Arc::clone(&api_client),
             state: LibraryState::Loading,
-            search_results: SearchResults::builder().launch(api_client).detach(),
+            search_results: SearchResults::builder().launch(api_client).forward(
+                sender.input_sender(),
+                |output| match output {
+                    SearchResultsOutput::SetSearchText(search_text) => {
+                        LibraryInput::SetSearchText(search_text)
+                    }
+                },
+            ),
             home: None,
             collections: None,
             searching: BoolBinding::default(),
@@ -409,6 +423,13 @@ impl Component for Library {
                 self.search_results
                     .emit(SearchResultsInput::SearchChanged(search_text));
             }
+            LibraryInput::SearchSubmitted => {
+                self.search_results
+                    .emit(SearchResultsInput::SearchSubmitted);
+            }
+            LibraryInput::SetSearchText(search_text) => {
+                widgets.search.set_text(search_text);
+            }
             LibraryInput::SearchingChanged(searching) => {
                 if searching {
                     widgets.view_stack.set_visible_child_name("search");
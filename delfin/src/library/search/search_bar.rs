@@ -11,6 +11,7 @@ mod imp {
     use super::*;
 
     static SIGNAL_SEARCH: &str = "search";
+    static SIGNAL_SUBMIT: &str = "submit";
 
     #[derive(Default, Properties)]
     #[properties(wrapper_type = super::SearchBar)]
@@ -71,6 +72,9 @@ mod imp {
                             connect_search_changed[obj] => move |search_entry| {
                                 obj.emit_by_name::<()>(SIGNAL_SEARCH, &[&search_entry.text().to_string()]);
                             },
+                            connect_activate[obj] => move |_| {
+                                obj.emit_by_name::<()>(SIGNAL_SUBMIT, &[]);
+                            },
                         },
                     },
                 }
@@ -79,9 +83,12 @@ mod imp {
 
         fn signals() -> &'static [Signal] {
             static SIGNALS: Lazy<Vec<Signal>> = Lazy::new(|| {
-                vec![Signal::builder(SIGNAL_SEARCH)
-                    .param_types([String::static_type()])
-                    .build()]
+                vec![
+                    Signal::builder(SIGNAL_SEARCH)
+                        .param_types([String::static_type()])
+                        .build(),
+                    Signal::builder(SIGNAL_SUBMIT).build(),
+                ]
             });
             SIGNALS.as_ref()
         }
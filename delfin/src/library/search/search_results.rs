@@ -1,51 +1,298 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use adw::prelude::*;
 use anyhow::Result;
-use jellyfin_api::types::BaseItemDto;
+use jellyfin_api::types::{BaseItemDto, BaseItemKind};
 use relm4::prelude::*;
+use tokio::task::JoinHandle;
 use tracing::warn;
+use uuid::Uuid;
 
 use crate::{
     app::{AppInput, APP_BROKER},
-    jellyfin_api::{api::items::GetItemsOptionsBuilder, api_client::ApiClient},
+    globals::CONFIG,
+    jellyfin_api::{
+        api::{items::GetItemsOptionsBuilder, search::SearchHint},
+        api_client::ApiClient,
+    },
     library::{
+        library_container::LibraryContainer,
+        media_carousel::{
+            MediaCarousel, MediaCarouselInit, MediaCarouselOutput, MediaCarouselType,
+        },
         media_fetcher::Fetcher,
         media_page::{MediaPage, MediaPageInit, MediaPageInput},
         media_tile::MediaTileDisplay,
     },
     tr,
+    utils::{debounce::Debounce, empty_component::EmptyComponent},
 };
 
+const SEARCH_DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(200);
+const SEARCH_HINTS_LIMIT: usize = 100;
+const MAX_RECENT_SEARCHES: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchCategory {
+    Movies,
+    Series,
+    Episodes,
+    People,
+    Collections,
+    Genres,
+}
+
+impl SearchCategory {
+    const ALL: [SearchCategory; 6] = [
+        Self::Movies,
+        Self::Series,
+        Self::Episodes,
+        Self::People,
+        Self::Collections,
+        Self::Genres,
+    ];
+
+    fn from_item_kind(kind: &BaseItemKind) -> Option<Self> {
+        match kind {
+            BaseItemKind::Movie => Some(Self::Movies),
+            BaseItemKind::Series => Some(Self::Series),
+            BaseItemKind::Episode => Some(Self::Episodes),
+            BaseItemKind::Person => Some(Self::People),
+            BaseItemKind::BoxSet => Some(Self::Collections),
+            BaseItemKind::Genre => Some(Self::Genres),
+            _ => None,
+        }
+    }
+
+    fn item_type(&self) -> &'static str {
+        match self {
+            Self::Movies => "Movie",
+            Self::Series => "Series",
+            Self::Episodes => "Episode",
+            Self::People => "Person",
+            Self::Collections => "BoxSet",
+            Self::Genres => "Genre",
+        }
+    }
+
+    fn title(&self) -> String {
+        match self {
+            Self::Movies => tr!("library-search-category.movies").to_string(),
+            Self::Series => tr!("library-search-category.series").to_string(),
+            Self::Episodes => tr!("library-search-category.episodes").to_string(),
+            Self::People => tr!("library-search-category.people").to_string(),
+            Self::Collections => tr!("library-search-category.collections").to_string(),
+            Self::Genres => tr!("library-search-category.genres").to_string(),
+        }
+    }
+
+    // People and genres aren't displayed as media tiles, selecting them shows related items instead
+    fn media_tile_display(&self) -> Option<MediaTileDisplay> {
+        match self {
+            Self::Movies | Self::Series | Self::Collections => Some(MediaTileDisplay::Cover),
+            Self::Episodes => Some(MediaTileDisplay::Wide),
+            Self::People | Self::Genres => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum SearchCategoryResults {
+    Media(Vec<BaseItemDto>),
+    Related(Vec<SearchHint>),
+}
+
+impl SearchCategoryResults {
+    fn is_empty(&self) -> bool {
+        match self {
+            Self::Media(items) => items.is_empty(),
+            Self::Related(hints) => hints.is_empty(),
+        }
+    }
+}
+
+enum SearchResultsSection {
+    Carousel(Controller<MediaCarousel>),
+    Related(gtk::Box),
+}
+
+impl SearchResultsSection {
+    fn widget(&self) -> &gtk::Widget {
+        match self {
+            Self::Carousel(carousel) => carousel.widget().upcast_ref(),
+            Self::Related(related) => related.upcast_ref(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+enum SearchResultsState {
+    Empty,
+    Loading,
+    Results,
+    NoResults,
+    Related,
+    Error,
+}
+
+impl SearchResultsState {
+    fn page_name(&self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::Loading => "loading",
+            Self::Results => "results",
+            Self::NoResults => "no-results",
+            Self::Related => "related",
+            Self::Error => "error",
+        }
+    }
+}
+
 pub struct SearchResults {
     api_client: Arc<ApiClient>,
-    media_page: Option<Controller<MediaPage<SearchResultsFetcher, SearchResultsEmpty>>>,
+    state: SearchResultsState,
+    search_text: String,
+    search_debounce: Debounce,
+    search_handle: Option<JoinHandle<()>>,
+    empty: Controller<SearchResultsEmpty>,
+    sections: Vec<SearchResultsSection>,
+    related_page: Option<Controller<MediaPage<RelatedItemsFetcher, EmptyComponent>>>,
 }
 
 #[derive(Debug)]
 pub enum SearchResultsInput {
     SearchChanged(String),
+    Search,
+    SearchSubmitted,
+    ResultActivated,
+    SearchResultsLoaded(String, Result<Vec<(SearchCategory, SearchCategoryResults)>>),
+    RecentSearchActivated(String),
+    ShowRelated(SearchCategory, SearchHint),
+    BackToResults,
+}
+
+#[derive(Debug)]
+pub enum SearchResultsOutput {
+    SetSearchText(String),
 }
 
 #[relm4::component(pub)]
 impl Component for SearchResults {
     type Init = Arc<ApiClient>;
     type Input = SearchResultsInput;
-    type Output = ();
+    type Output = SearchResultsOutput;
     type CommandOutput = ();
 
     view! {
-        gtk::Box {}
+        gtk::Stack {
+            set_transition_type: gtk::StackTransitionType::Crossfade,
+            #[watch]
+            set_visible_child_name: model.state.page_name(),
+
+            add_named[Some("empty")] = &gtk::ScrolledWindow {
+                set_hexpand: true,
+                set_vexpand: true,
+                set_child: Some(model.empty.widget()),
+            },
+
+            add_named[Some("loading")] = &gtk::Spinner {
+                set_spinning: true,
+                set_halign: gtk::Align::Center,
+                set_valign: gtk::Align::Center,
+                set_width_request: 32,
+                set_height_request: 32,
+            },
+
+            add_named[Some("results")] = &gtk::ScrolledWindow {
+                set_hexpand: true,
+                set_vexpand: true,
+
+                #[template]
+                LibraryContainer {
+                    #[name = "sections_container"]
+                    #[wrap(Some)]
+                    set_child = &gtk::Box {
+                        set_valign: gtk::Align::Start,
+                        set_vexpand: true,
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 20,
+                    },
+                },
+            },
+
+            add_named[Some("no-results")] = &adw::StatusPage {
+                set_icon_name: Some("loupe"),
+                set_title: tr!("library-search-no-results.title"),
+                #[watch]
+                set_description: Some(tr!("library-search-no-results.description", {
+                    "searchText" => model.search_text.clone(),
+                })),
+            },
+
+            add_named[Some("error")] = &adw::StatusPage {
+                set_icon_name: Some("warning"),
+                set_title: tr!("library-search-error.title"),
+                set_description: Some(tr!("library-search-error.description")),
+            },
+
+            #[name = "related_container"]
+            add_named[Some("related")] = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+
+                #[template]
+                LibraryContainer {
+                    set_margin_bottom: 0,
+
+                    gtk::Button {
+                        set_halign: gtk::Align::Start,
+                        add_css_class: "flat",
+                        connect_clicked[sender] => move |_| {
+                            sender.input(SearchResultsInput::BackToResults);
+                        },
+
+                        #[wrap(Some)]
+                        set_child = &adw::ButtonContent {
+                            set_icon_name: "left",
+                            set_label: tr!("library-search-back-to-results"),
+                        },
+                    },
+                },
+            },
+        }
     }
 
     fn init(
         api_client: Self::Init,
         root: Self::Root,
-        _sender: ComponentSender<Self>,
+        sender: ComponentSender<Self>,
     ) -> ComponentParts<Self> {
+        let search_debounce = Debounce::new(
+            SEARCH_DEBOUNCE_TIMEOUT,
+            Box::new({
+                let sender = sender.clone();
+                move || {
+                    sender.input(SearchResultsInput::Search);
+                }
+            }),
+        );
+
+        let empty = SearchResultsEmpty::builder()
+            .launch(api_client.clone())
+            .forward(sender.input_sender(), |output| match output {
+                SearchResultsEmptyOutput::RecentSearchActivated(search_text) => {
+                    SearchResultsInput::RecentSearchActivated(search_text)
+                }
+            });
+
         let model = SearchResults {
             api_client,
-            media_page: None,
+            state: SearchResultsState::Empty,
+            search_text: String::default(),
+            search_debounce,
+            search_handle: None,
+            empty,
+            sections: vec![],
+            related_page: None,
         };
 
         let widgets = view_output!();
@@ -58,39 +305,107 @@ impl Component for SearchResults {
         widgets: &mut Self::Widgets,
         message: Self::Input,
         sender: ComponentSender<Self>,
-        root: &Self::Root,
+        _root: &Self::Root,
     ) {
         match message {
             SearchResultsInput::SearchChanged(search_text) => {
-                if let Some(media_page) = self.media_page.take() {
-                    root.remove(media_page.widget());
+                self.search_text = search_text;
+
+                // Cancel the in-flight search, its results are already outdated
+                if let Some(search_handle) = self.search_handle.take() {
+                    search_handle.abort();
+                }
+
+                if self.search_text.is_empty() {
+                    self.state = SearchResultsState::Empty;
+                } else {
+                    self.state = SearchResultsState::Loading;
+                    self.search_debounce.debounce();
+                }
+            }
+            SearchResultsInput::Search => {
+                if let Some(search_handle) = self.search_handle.take() {
+                    search_handle.abort();
                 }
 
-                let fetcher = SearchResultsFetcher {
-                    api_client: self.api_client.clone(),
-                    search_text: search_text.clone(),
-                };
+                if !self.search_text.is_empty() {
+                    self.search_handle = Some(relm4::spawn({
+                        let api_client = self.api_client.clone();
+                        let search_text = self.search_text.clone();
+                        let sender = sender.clone();
+                        async move {
+                            let results = search(&api_client, &search_text).await;
+                            sender.input(SearchResultsInput::SearchResultsLoaded(
+                                search_text,
+                                results,
+                            ));
+                        }
+                    }));
+                }
+            }
+            SearchResultsInput::SearchResultsLoaded(search_text, results) => {
+                if search_text != self.search_text {
+                    return;
+                }
 
-                let media_page = MediaPage::builder()
+                self.search_handle = None;
+
+                match results {
+                    Ok(results) if results.is_empty() => {
+                        self.clear_sections(&widgets.sections_container);
+                        self.state = SearchResultsState::NoResults;
+                    }
+                    Ok(results) => {
+                        self.display_sections(&widgets.sections_container, &sender, results);
+                        self.state = SearchResultsState::Results;
+                    }
+                    Err(err) => {
+                        warn!("Error searching for {search_text}: {err}");
+                        self.state = SearchResultsState::Error;
+                    }
+                }
+            }
+            // Searches run as the user types, so they are only remembered once the user submits
+            // one or picks a result
+            SearchResultsInput::SearchSubmitted | SearchResultsInput::ResultActivated => {
+                if !self.search_text.is_empty() {
+                    add_recent_search(&self.search_text);
+                }
+            }
+            SearchResultsInput::RecentSearchActivated(search_text) => {
+                sender
+                    .output(SearchResultsOutput::SetSearchText(search_text))
+                    .unwrap();
+            }
+            SearchResultsInput::ShowRelated(category, hint) => {
+                add_recent_search(&self.search_text);
+
+                let related_container = &widgets.related_container;
+
+                if let Some(related_page) = self.related_page.take() {
+                    related_container.remove(related_page.widget());
+                }
+
+                let related_page = MediaPage::builder()
                     .launch(MediaPageInit {
                         api_client: self.api_client.clone(),
-                        fetcher,
-                        empty_component: Some(
-                            SearchResultsEmpty::builder()
-                                .launch(self.api_client.clone())
-                                .detach(),
-                        ),
+                        fetcher: RelatedItemsFetcher {
+                            api_client: self.api_client.clone(),
+                            category,
+                            hint,
+                        },
+                        empty_component: None,
                         media_tile_display: Some(MediaTileDisplay::CoverLarge),
                     })
                     .detach();
+                related_page.emit(MediaPageInput::NextPage);
+                related_container.append(related_page.widget());
 
-                if !search_text.is_empty() {
-                    media_page.emit(MediaPageInput::NextPage);
-                }
-
-                root.append(media_page.widget());
-
-                self.media_page = Some(media_page);
+                self.related_page = Some(related_page);
+                self.state = SearchResultsState::Related;
+            }
+            SearchResultsInput::BackToResults => {
+                self.state = SearchResultsState::Results;
             }
         }
 
@@ -98,38 +413,223 @@ impl Component for SearchResults {
     }
 }
 
-struct SearchResultsFetcher {
-    api_client: Arc<ApiClient>,
-    search_text: String,
+impl SearchResults {
+    fn clear_sections(&mut self, sections_container: &gtk::Box) {
+        for section in self.sections.drain(..) {
+            sections_container.remove(section.widget());
+        }
+    }
+
+    fn display_sections(
+        &mut self,
+        sections_container: &gtk::Box,
+        sender: &ComponentSender<Self>,
+        results: Vec<(SearchCategory, SearchCategoryResults)>,
+    ) {
+        self.clear_sections(sections_container);
+
+        for (category, results) in results {
+            let section = match (results, category.media_tile_display()) {
+                (SearchCategoryResults::Media(media), Some(media_tile_display)) => {
+                    SearchResultsSection::Carousel(
+                        MediaCarousel::builder()
+                            .launch(MediaCarouselInit {
+                                media,
+                                media_tile_display,
+                                carousel_type: MediaCarouselType::Tiles,
+                                api_client: self.api_client.clone(),
+                                label: category.title(),
+                                label_clickable: false,
+                            })
+                            .forward(sender.input_sender(), |output| match output {
+                                MediaCarouselOutput::LabelClicked
+                                | MediaCarouselOutput::MediaActivated => {
+                                    SearchResultsInput::ResultActivated
+                                }
+                            }),
+                    )
+                }
+                (SearchCategoryResults::Related(hints), _) => {
+                    SearchResultsSection::Related(related_section(sender, category, hints))
+                }
+                _ => continue,
+            };
+
+            sections_container.append(section.widget());
+            self.sections.push(section);
+        }
+    }
 }
 
-impl Fetcher for SearchResultsFetcher {
-    async fn fetch(&self, start_index: usize, limit: usize) -> Result<(Vec<BaseItemDto>, usize)> {
-        self.api_client
+fn related_section(
+    sender: &ComponentSender<SearchResults>,
+    category: SearchCategory,
+    hints: Vec<SearchHint>,
+) -> gtk::Box {
+    relm4::view! {
+        section = gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 16,
+
+            gtk::Label {
+                set_label: &category.title(),
+                add_css_class: "title-2",
+                set_halign: gtk::Align::Start,
+            },
+
+            #[name = "flow_box"]
+            gtk::FlowBox {
+                set_column_spacing: 8,
+                set_row_spacing: 8,
+                set_selection_mode: gtk::SelectionMode::None,
+            },
+        }
+    }
+
+    for hint in hints {
+        let button = gtk::Button::builder()
+            .label(&hint.name)
+            .css_classes(["pill"])
+            .build();
+        button.connect_clicked({
+            let sender = sender.clone();
+            move |_| {
+                sender.input(SearchResultsInput::ShowRelated(category, hint.clone()));
+            }
+        });
+        flow_box.append(&button);
+    }
+
+    section
+}
+
+async fn search(
+    api_client: &ApiClient,
+    search_text: &str,
+) -> Result<Vec<(SearchCategory, SearchCategoryResults)>> {
+    let include_item_types = SearchCategory::ALL
+        .iter()
+        .map(SearchCategory::item_type)
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let hints = api_client
+        .get_search_hints(search_text, &include_item_types, SEARCH_HINTS_LIMIT)
+        .await?;
+
+    // Search hints don't include everything needed to display media tiles, so the full items
+    // are fetched in a single request
+    let media_ids: Vec<String> = hints
+        .iter()
+        .filter(|hint| {
+            SearchCategory::from_item_kind(&hint.type_)
+                .and_then(|category| category.media_tile_display())
+                .is_some()
+        })
+        .map(|hint| hint.id.to_string())
+        .collect();
+
+    let mut media: HashMap<Uuid, BaseItemDto> = if media_ids.is_empty() {
+        HashMap::new()
+    } else {
+        let (items, _) = api_client
             .get_items(
                 &GetItemsOptionsBuilder::default()
-                    .search_term(Some(self.search_text.clone()))
-                    .include_item_types(Some("Series,Movie".to_string()))
-                    .sort_by(Some("SortName,ProductionYear".to_string()))
-                    .sort_order(Some("Ascending".to_string()))
-                    .start_index(Some(start_index))
-                    .limit(Some(limit))
+                    .ids(Some(media_ids.join(",")))
                     .build()
                     .unwrap(),
             )
-            .await
+            .await?;
+        items
+            .into_iter()
+            .filter_map(|item| item.id.map(|id| (id, item)))
+            .collect()
+    };
+
+    Ok(SearchCategory::ALL
+        .into_iter()
+        .filter_map(|category| {
+            // Keep the order of the search hints, they're sorted by relevance
+            let hints = hints
+                .iter()
+                .filter(|hint| SearchCategory::from_item_kind(&hint.type_) == Some(category));
+
+            let results = match category.media_tile_display() {
+                Some(_) => SearchCategoryResults::Media(
+                    hints.filter_map(|hint| media.remove(&hint.id)).collect(),
+                ),
+                None => SearchCategoryResults::Related(hints.cloned().collect()),
+            };
+
+            (!results.is_empty()).then_some((category, results))
+        })
+        .collect())
+}
+
+fn add_recent_search(search_text: &str) {
+    let mut config = CONFIG.write();
+    let recent_searches = &mut config.general.recent_searches;
+
+    recent_searches.retain(|recent_search| recent_search != search_text);
+    recent_searches.insert(0, search_text.to_string());
+    recent_searches.truncate(MAX_RECENT_SEARCHES);
+
+    if let Err(err) = config.save() {
+        warn!("Error saving recent searches: {err}");
+    }
+}
+
+struct RelatedItemsFetcher {
+    api_client: Arc<ApiClient>,
+    category: SearchCategory,
+    hint: SearchHint,
+}
+
+impl Fetcher for RelatedItemsFetcher {
+    async fn fetch(&self, start_index: usize, limit: usize) -> Result<(Vec<BaseItemDto>, usize)> {
+        let mut options = GetItemsOptionsBuilder::default();
+        options
+            .include_item_types(Some("Series,Movie".to_string()))
+            .sort_by(Some("SortName,ProductionYear".to_string()))
+            .sort_order(Some("Ascending".to_string()))
+            .start_index(Some(start_index))
+            .limit(Some(limit));
+
+        match self.category {
+            SearchCategory::Genres => options.genre_ids(Some(self.hint.id.to_string())),
+            _ => options.person_ids(Some(self.hint.id.to_string())),
+        };
+
+        self.api_client.get_items(&options.build().unwrap()).await
     }
 
     fn title(&self) -> String {
-        tr!("library-search-title", {
-             "searchText" => self.search_text.clone(),
-        })
-        .to_string()
+        match self.category {
+            SearchCategory::Genres => tr!("library-search-related.genre", {
+                "name" => self.hint.name.clone(),
+            })
+            .to_string(),
+            _ => tr!("library-search-related.person", {
+                "name" => self.hint.name.clone(),
+            })
+            .to_string(),
+        }
     }
 }
 
 struct SearchResultsEmpty;
 
+#[derive(Debug)]
+enum SearchResultsEmptyInput {
+    RecentSearchesUpdated(Vec<String>),
+    ClearRecentSearches,
+}
+
+#[derive(Debug)]
+enum SearchResultsEmptyOutput {
+    RecentSearchActivated(String),
+}
+
 #[derive(Debug)]
 enum SearchResultsEmptyCommandOutput {
     Suggestions(Vec<BaseItemDto>),
@@ -138,8 +638,8 @@ enum SearchResultsEmptyCommandOutput {
 #[relm4::component]
 impl Component for SearchResultsEmpty {
     type Init = Arc<ApiClient>;
-    type Input = ();
-    type Output = ();
+    type Input = SearchResultsEmptyInput;
+    type Output = SearchResultsEmptyOutput;
     type CommandOutput = SearchResultsEmptyCommandOutput;
 
     view! {
@@ -151,14 +651,45 @@ impl Component for SearchResultsEmpty {
                 adw::StatusPage {
                     set_icon_name: Some("loupe"),
                     set_title: tr!("library-search-empty.title"),
-                    set_description: Some(tr!("library-search-empty.description")),
 
-                    #[name = "suggestions"]
                     #[wrap(Some)]
-                    set_child = &gtk::ListBox {
-                        set_visible: false,
-                        add_css_class: "boxed-list",
-                        set_selection_mode: gtk::SelectionMode::None,
+                    set_child = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_spacing: 24,
+
+                        #[name = "recent_searches_group"]
+                        adw::PreferencesGroup {
+                            set_title: tr!("library-search-recent.title"),
+                            set_visible: false,
+
+                            #[wrap(Some)]
+                            set_header_suffix = &gtk::Button {
+                                set_icon_name: "user-trash-symbolic",
+                                set_tooltip: tr!("library-search-recent.clear"),
+                                add_css_class: "flat",
+                                connect_clicked[sender] => move |_| {
+                                    sender.input(SearchResultsEmptyInput::ClearRecentSearches);
+                                },
+                            },
+
+                            #[name = "recent_searches"]
+                            gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None,
+                            },
+                        },
+
+                        #[name = "suggestions_group"]
+                        adw::PreferencesGroup {
+                            set_title: tr!("library-search-suggestions"),
+                            set_visible: false,
+
+                            #[name = "suggestions"]
+                            gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None,
+                            },
+                        },
                     },
                 },
 
@@ -195,9 +726,65 @@ impl Component for SearchResultsEmpty {
 
         let widgets = view_output!();
 
+        sender.input(SearchResultsEmptyInput::RecentSearchesUpdated(
+            CONFIG.read().general.recent_searches.clone(),
+        ));
+        CONFIG.subscribe(sender.input_sender(), |config| {
+            SearchResultsEmptyInput::RecentSearchesUpdated(config.general.recent_searches.clone())
+        });
+
         ComponentParts { model, widgets }
     }
 
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            SearchResultsEmptyInput::RecentSearchesUpdated(recent_searches) => {
+                let list = &widgets.recent_searches;
+                list.remove_all();
+
+                for search_text in &recent_searches {
+                    let prefix = gtk::Image::from_icon_name("document-open-recent-symbolic");
+                    let row = adw::ActionRow::builder()
+                        .title(search_text)
+                        .activatable(true)
+                        .build();
+                    row.add_prefix(&prefix);
+                    row.connect_activated({
+                        let sender = sender.clone();
+                        let search_text = search_text.clone();
+                        move |_| {
+                            sender
+                                .output(SearchResultsEmptyOutput::RecentSearchActivated(
+                                    search_text.clone(),
+                                ))
+                                .unwrap();
+                        }
+                    });
+                    list.append(&row);
+                }
+
+                widgets
+                    .recent_searches_group
+                    .set_visible(!recent_searches.is_empty());
+            }
+            SearchResultsEmptyInput::ClearRecentSearches => {
+                let mut config = CONFIG.write();
+                config.general.recent_searches.clear();
+                if let Err(err) = config.save() {
+                    warn!("Error saving recent searches: {err}");
+                }
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
     fn update_cmd_with_view(
         &mut self,
         widgets: &mut Self::Widgets,
@@ -224,7 +811,7 @@ impl Component for SearchResultsEmpty {
                     }
                 }
 
-                suggestions.set_visible(true);
+                widgets.suggestions_group.set_visible(true);
             }
             _ => {}
         }
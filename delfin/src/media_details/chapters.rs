@@ -0,0 +1,244 @@
+use std::{collections::VecDeque, sync::Arc};
+
+use adw::prelude::*;
+use gdk::Texture;
+use gtk::{gdk, gdk_pixbuf::Pixbuf};
+use jellyfin_api::types::{BaseItemDto, ChapterInfo};
+use relm4::{factory::FactoryVecDeque, prelude::*, FactorySender};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    app::{AppInput, APP_BROKER},
+    jellyfin_api::api_client::ApiClient,
+    tr,
+    utils::{ticks::ticks_to_seconds, timestamp::seconds_to_timestamp},
+};
+
+const CHAPTER_THUMBNAIL_WIDTH: i32 = 96;
+const CHAPTER_THUMBNAIL_HEIGHT: i32 = 54;
+
+/// List of an item's chapters, which can be used to start playback from a given chapter.
+pub(crate) struct Chapters {
+    item: BaseItemDto,
+    chapters: FactoryVecDeque<ChapterListItem>,
+}
+
+#[derive(Debug)]
+pub(crate) enum ChaptersInput {
+    PlayFrom(usize),
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for Chapters {
+    type Init = (Arc<ApiClient>, BaseItemDto);
+    type Input = ChaptersInput;
+    type Output = ();
+
+    view! {
+        gtk::Box {
+            set_orientation: gtk::Orientation::Vertical,
+            set_spacing: 8,
+
+            gtk::Label {
+                set_label: tr!("media-details-chapters"),
+                set_halign: gtk::Align::Start,
+                add_css_class: "heading",
+            },
+
+            #[local_ref]
+            chapter_list -> gtk::ListBox {
+                add_css_class: "boxed-list",
+                set_selection_mode: gtk::SelectionMode::None,
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let (api_client, item) = init;
+
+        let mut chapters = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::default())
+            .forward(sender.input_sender(), |output| match output {
+                ChapterListItemOutput::PlayFrom(start) => ChaptersInput::PlayFrom(start),
+            });
+
+        if let (Some(item_id), Some(item_chapters)) = (item.id, &item.chapters) {
+            let mut chapters = chapters.guard();
+            for (index, chapter) in item_chapters.iter().enumerate() {
+                chapters.push_back(ChapterListItemInit {
+                    api_client: api_client.clone(),
+                    item_id,
+                    index,
+                    chapter: chapter.clone(),
+                });
+            }
+        }
+
+        let model = Chapters { item, chapters };
+
+        let chapter_list = model.chapters.widget();
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+        match message {
+            ChaptersInput::PlayFrom(start) => {
+                APP_BROKER.send(AppInput::PlayVideoFrom(self.item.clone(), start));
+            }
+        }
+    }
+}
+
+/// A single chapter, showing its image, name, and start time.
+#[derive(Debug)]
+pub(crate) struct ChapterListItem {
+    name: String,
+    start: usize,
+    image: Option<Texture>,
+    active: bool,
+}
+
+pub(crate) struct ChapterListItemInit {
+    pub(crate) api_client: Arc<ApiClient>,
+    pub(crate) item_id: Uuid,
+    /// Index of the chapter in the item's chapter list, used to fetch the chapter image
+    pub(crate) index: usize,
+    pub(crate) chapter: ChapterInfo,
+}
+
+#[derive(Debug)]
+pub(crate) enum ChapterListItemInput {
+    Activated,
+    SetActive(bool),
+}
+
+#[derive(Debug)]
+pub(crate) enum ChapterListItemOutput {
+    PlayFrom(usize),
+}
+
+#[derive(Debug)]
+pub(crate) enum ChapterListItemCommandOutput {
+    ImageLoaded(Option<VecDeque<u8>>),
+}
+
+#[relm4::factory(pub(crate))]
+impl FactoryComponent for ChapterListItem {
+    type Init = ChapterListItemInit;
+    type Input = ChapterListItemInput;
+    type Output = ChapterListItemOutput;
+    type CommandOutput = ChapterListItemCommandOutput;
+    type ParentWidget = gtk::ListBox;
+
+    view! {
+        adw::ActionRow {
+            set_title: &self.name,
+            set_title_lines: 1,
+            set_use_markup: false,
+            set_subtitle: &seconds_to_timestamp(self.start),
+
+            add_prefix = &gtk::Picture {
+                #[watch]
+                set_paintable: self.image.as_ref(),
+
+                add_css_class: "chapter-thumbnail",
+                set_valign: gtk::Align::Center,
+                set_margin_top: 6,
+                set_margin_bottom: 6,
+                set_width_request: CHAPTER_THUMBNAIL_WIDTH,
+                set_height_request: CHAPTER_THUMBNAIL_HEIGHT,
+                set_content_fit: gtk::ContentFit::Cover,
+            },
+
+            add_suffix = &gtk::Image {
+                set_icon_name: Some("media-playback-start-symbolic"),
+                set_tooltip_text: Some(tr!("media-details-chapters.current")),
+                #[watch]
+                set_visible: self.active,
+            },
+
+            set_activatable: true,
+            connect_activated[sender] => move |_| {
+                sender.input(ChapterListItemInput::Activated);
+            },
+        }
+    }
+
+    fn init_model(init: Self::Init, _index: &Self::Index, sender: FactorySender<Self>) -> Self {
+        let ChapterListItemInit {
+            api_client,
+            item_id,
+            index,
+            chapter,
+        } = init;
+
+        if let Some(image_tag) = &chapter.image_tag {
+            match api_client.get_chapter_image_url(
+                &item_id,
+                index,
+                image_tag,
+                CHAPTER_THUMBNAIL_WIDTH,
+            ) {
+                Ok(img_url) => {
+                    sender.oneshot_command(async move {
+                        let img_bytes = match reqwest::get(&img_url).await {
+                            Ok(res) => res.bytes().await,
+                            Err(err) => Err(err),
+                        };
+                        ChapterListItemCommandOutput::ImageLoaded(match img_bytes {
+                            Ok(img_bytes) => Some(img_bytes.into_iter().collect()),
+                            Err(err) => {
+                                warn!("Error getting chapter image {img_url}: {err}");
+                                None
+                            }
+                        })
+                    });
+                }
+                Err(err) => warn!("Error getting chapter image URL: {err}"),
+            }
+        }
+
+        Self {
+            name: chapter.name.unwrap_or_else(|| {
+                tr!("media-details-chapters.unnamed", { "number" => index + 1 }).to_string()
+            }),
+            start: chapter
+                .start_position_ticks
+                .map_or(0, |ticks| ticks_to_seconds(ticks).max(0) as usize),
+            image: None,
+            active: false,
+        }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: FactorySender<Self>) {
+        match message {
+            ChapterListItemInput::Activated => {
+                sender
+                    .output(ChapterListItemOutput::PlayFrom(self.start))
+                    .unwrap();
+            }
+            ChapterListItemInput::SetActive(active) => {
+                self.active = active;
+            }
+        }
+    }
+
+    fn update_cmd(&mut self, message: Self::CommandOutput, _sender: FactorySender<Self>) {
+        match message {
+            ChapterListItemCommandOutput::ImageLoaded(Some(img_bytes)) => {
+                match Pixbuf::from_read(img_bytes) {
+                    Ok(pixbuf) => self.image = Some(gdk::Texture::for_pixbuf(&pixbuf)),
+                    Err(err) => warn!("Error creating chapter image pixbuf: {err}"),
+                }
+            }
+            ChapterListItemCommandOutput::ImageLoaded(None) => {}
+        }
+    }
+}
@@ -17,6 +17,7 @@ use crate::{
 };
 
 use super::{
+    chapters::Chapters,
     media_details_header::{MediaDetailsHeader, MediaDetailsHeaderInput, MediaDetailsHeaderOutput},
     run_time::RunTime,
     seasons::{Seasons, SeasonsOutput},
@@ -29,6 +30,7 @@ pub struct MediaDetailsContents {
     header: OnceCell<AsyncController<MediaDetailsHeader>>,
     seasons: Option<AsyncController<Seasons>>,
     selected_season_index: Option<usize>,
+    chapters: Option<Controller<Chapters>>,
 }
 
 #[derive(Debug)]
@@ -129,6 +131,7 @@ impl AsyncComponent for MediaDetailsContents {
             header: OnceCell::new(),
             seasons: None,
             selected_season_index: None,
+            chapters: None,
         };
 
         let widgets = view_output!();
@@ -149,6 +152,7 @@ impl AsyncComponent for MediaDetailsContents {
         model.add_info(info_box, genre_label);
 
         model.load_seasons(&sender, container);
+        model.load_chapters(container);
 
         AsyncComponentParts { model, widgets }
     }
@@ -196,6 +200,25 @@ impl MediaDetailsContents {
         }
     }
 
+    fn load_chapters(&mut self, container: &gtk::Box) {
+        // Only standalone items like movies have chapters, series show their seasons instead
+        if self.series_id.is_some()
+            || self
+                .item
+                .chapters
+                .as_ref()
+                .map_or(true, |chapters| chapters.is_empty())
+        {
+            return;
+        }
+
+        let chapters = Chapters::builder()
+            .launch((self.api_client.clone(), self.item.clone()))
+            .detach();
+        container.append(chapters.widget());
+        self.chapters = Some(chapters);
+    }
+
     fn add_info(&self, info_box: &gtk::Box, genre_label: &gtk::Label) {
         let item = &self.item;
 
@@ -9,11 +9,16 @@ use relm4::{
 
 use crate::{
     app::{AppInput, APP_BROKER},
+    config::video_player_config::ResumeBehaviour,
+    globals::CONFIG,
     jellyfin_api::api_client::ApiClient,
     library::LIBRARY_REFRESH_QUEUED,
     media_details::watched_state::{watched_label, Played},
     tr,
-    utils::{constants::MAX_LIBRARY_WIDTH, playable::get_next_playable_media},
+    utils::{
+        constants::MAX_LIBRARY_WIDTH,
+        playable::{get_next_playable_media, get_resume_position},
+    },
 };
 
 use super::watched_state::toggle_watched;
@@ -39,6 +44,7 @@ pub(crate) struct MediaDetailsHeaderInit {
 #[derive(Debug)]
 pub enum MediaDetailsHeaderInput {
     PlayNext,
+    PlayFromBeginning,
     ToggleWatched(bool),
     UpdatePlayNext,
 }
@@ -181,34 +187,57 @@ impl AsyncComponent for MediaDetailsHeader {
                                         } @toggle_handler,
                                     },
 
-                                    #[name = "btn_play_next"]
-                                    gtk::Button {
-                                        add_css_class: "pill",
-                                        add_css_class: "suggested-action",
+                                    #[name = "play_buttons"]
+                                    gtk::Box {
                                         set_valign: gtk::Align::Center,
-                                        set_hexpand: false,
-                                        set_vexpand: false,
-
-                                        #[watch]
-                                        set_visible: model.play_next_label.is_some() && model.play_next_media.is_some(),
-
-                                        connect_clicked[sender] => move |_| {
-                                            sender.input(MediaDetailsHeaderInput::PlayNext);
+                                        set_spacing: 8,
+
+                                        gtk::Button {
+                                            add_css_class: "pill",
+                                            add_css_class: "suggested-action",
+                                            set_valign: gtk::Align::Center,
+                                            set_hexpand: false,
+                                            set_vexpand: false,
+
+                                            #[watch]
+                                            set_visible: model.play_next_label.is_some() && model.play_next_media.is_some(),
+
+                                            connect_clicked[sender] => move |_| {
+                                                sender.input(MediaDetailsHeaderInput::PlayNext);
+                                            },
+
+                                            #[wrap(Some)]
+                                            set_child = &gtk::Box {
+                                                set_orientation: gtk::Orientation::Horizontal,
+                                                set_spacing: 8,
+
+                                                gtk::Image::from_icon_name("play-filled"),
+
+                                                if model.play_next_label.is_some() {
+                                                    gtk::Label {
+                                                        #[watch]
+                                                        set_label: model.play_next_label.as_ref().unwrap(),
+                                                    }
+                                                } else { gtk::Spinner { set_spinning: true } },
+                                            },
                                         },
 
-                                        #[wrap(Some)]
-                                        set_child = &gtk::Box {
-                                            set_orientation: gtk::Orientation::Horizontal,
-                                            set_spacing: 8,
-
-                                            gtk::Image::from_icon_name("play-filled"),
-
-                                            if model.play_next_label.is_some() {
-                                                gtk::Label {
-                                                    #[watch]
-                                                    set_label: model.play_next_label.as_ref().unwrap(),
-                                                }
-                                            } else { gtk::Spinner { set_spinning: true } },
+                                        // Only shown when the play button resumes playback
+                                        gtk::Button {
+                                            set_icon_name: "refresh",
+                                            add_css_class: "circular",
+                                            set_valign: gtk::Align::Center,
+                                            set_tooltip_text: Some(tr!("media-details-play-from-beginning")),
+
+                                            #[watch]
+                                            set_visible: model.play_next_media
+                                                .as_ref()
+                                                .and_then(get_resume_position)
+                                                .is_some(),
+
+                                            connect_clicked[sender] => move |_| {
+                                                sender.input(MediaDetailsHeaderInput::PlayFromBeginning);
+                                            },
                                         },
                                     },
                                 },
@@ -235,11 +264,11 @@ impl AsyncComponent for MediaDetailsHeader {
                 add_setter: (&details, "margin_start", &24.0.into()),
                 add_setter: (&details, "margin_end", &24.0.into()),
                 add_setter: (&header_buttons, "halign", &gtk::Align::Start.into()),
-                connect_apply: glib::clone!(@weak header_buttons, @weak btn_play_next, @weak btn_watched => move |_| {
-                    header_buttons.reorder_child_after(&btn_watched, Some(&btn_play_next));
+                connect_apply: glib::clone!(@weak header_buttons, @weak play_buttons, @weak btn_watched => move |_| {
+                    header_buttons.reorder_child_after(&btn_watched, Some(&play_buttons));
                 }),
-                connect_unapply: glib::clone!(@weak header_buttons, @weak btn_play_next, @weak btn_watched => move |_| {
-                    header_buttons.reorder_child_after(&btn_play_next, Some(&btn_watched));
+                connect_unapply: glib::clone!(@weak header_buttons, @weak play_buttons, @weak btn_watched => move |_| {
+                    header_buttons.reorder_child_after(&play_buttons, Some(&btn_watched));
                 })
             }
 
@@ -303,7 +332,12 @@ impl AsyncComponent for MediaDetailsHeader {
         match message {
             MediaDetailsHeaderInput::PlayNext => {
                 if let Some(play_next_media) = &self.play_next_media {
-                    APP_BROKER.send(crate::app::AppInput::PlayVideo(play_next_media.clone()));
+                    APP_BROKER.send(AppInput::PlayVideo(play_next_media.clone()));
+                }
+            }
+            MediaDetailsHeaderInput::PlayFromBeginning => {
+                if let Some(play_next_media) = &self.play_next_media {
+                    APP_BROKER.send(AppInput::PlayVideoFrom(play_next_media.clone(), 0));
                 }
             }
             MediaDetailsHeaderInput::ToggleWatched(watched) => {
@@ -392,12 +426,9 @@ async fn get_play_next(
         );
     };
 
-    let resume = next_playable
-        .user_data
-        .clone()
-        .and_then(|user_data| user_data.playback_position_ticks)
-        .unwrap_or(0)
-        != 0;
+    // Partially watched media always starts over when set to restart
+    let resume = get_resume_position(&next_playable).is_some()
+        && CONFIG.read().video_player.resume_behaviour != ResumeBehaviour::Restart;
 
     if !(matches!(
         next_playable.type_,
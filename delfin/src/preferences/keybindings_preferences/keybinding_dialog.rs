@@ -0,0 +1,191 @@
+use adw::{prelude::*, ResponseAppearance};
+use gtk::{gdk, glib};
+use relm4::prelude::*;
+
+use crate::{
+    config::keybindings::{KeybindingAction, Keybindings},
+    tr,
+    video_player::keybindings::shortcut_accelerator,
+};
+
+enum Responses {
+    Cancel,
+    Disable,
+    Set,
+}
+
+impl From<Responses> for &str {
+    fn from(val: Responses) -> Self {
+        match val {
+            Responses::Cancel => "cancel",
+            Responses::Disable => "disable",
+            Responses::Set => "set",
+        }
+    }
+}
+
+/// Waits for a key to be pressed, to use as the keybinding for an action.
+pub(crate) struct KeybindingDialog {
+    action: KeybindingAction,
+    keybindings: Keybindings,
+    accelerator: Option<String>,
+}
+
+pub(crate) struct KeybindingDialogInit {
+    pub action: KeybindingAction,
+    pub keybindings: Keybindings,
+}
+
+#[derive(Debug)]
+pub(crate) enum KeybindingDialogInput {
+    KeyPressed(String),
+    Set,
+    Disable,
+}
+
+#[derive(Debug)]
+pub(crate) enum KeybindingDialogOutput {
+    Bind(KeybindingAction, String),
+    Unbind(KeybindingAction),
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for KeybindingDialog {
+    type Init = KeybindingDialogInit;
+    type Input = KeybindingDialogInput;
+    type Output = KeybindingDialogOutput;
+
+    view! {
+        adw::MessageDialog {
+            set_visible: true,
+            set_modal: true,
+            set_heading: Some(tr!("prefs-kb-dialog.heading")),
+            set_body: tr!("prefs-kb-dialog.body", { "action" => model.action.label() }),
+            add_responses: &[
+                (Responses::Cancel.into(), tr!("prefs-kb-dialog.response-cancel")),
+                (Responses::Disable.into(), tr!("prefs-kb-dialog.response-disable")),
+                (Responses::Set.into(), tr!("prefs-kb-dialog.response-set")),
+            ],
+            set_close_response: Responses::Cancel.into(),
+            #[watch]
+            set_response_enabled: (Responses::Set.into(), model.accelerator.is_some()),
+            #[watch]
+            set_response_appearance: (
+                Responses::Set.into(),
+                if model.conflict().is_some() {
+                    ResponseAppearance::Destructive
+                } else {
+                    ResponseAppearance::Suggested
+                },
+            ),
+            connect_response: (Some(Responses::Disable.into()), {
+                let sender = sender.clone();
+                move |_, _| {
+                    sender.input(KeybindingDialogInput::Disable);
+                }
+            }),
+            connect_response: (Some(Responses::Set.into()), {
+                let sender = sender.clone();
+                move |_, _| {
+                    sender.input(KeybindingDialogInput::Set);
+                }
+            }),
+
+            // Capture keys before the dialog uses them to pick a response
+            add_controller = gtk::EventControllerKey {
+                set_propagation_phase: gtk::PropagationPhase::Capture,
+                connect_key_pressed[sender] => move |_, key, _, modifiers| {
+                    let modifiers = modifiers & gtk::accelerator_get_default_mod_mask();
+                    // Escape still closes the dialog
+                    if key == gdk::Key::Escape && modifiers.is_empty() {
+                        return glib::Propagation::Proceed;
+                    }
+                    // Wait for a key to be pressed along with modifiers like Shift
+                    if !gtk::accelerator_valid(key, modifiers) {
+                        return glib::Propagation::Stop;
+                    }
+                    sender.input(KeybindingDialogInput::KeyPressed(shortcut_accelerator(key, modifiers)));
+                    glib::Propagation::Stop
+                },
+            },
+
+            #[wrap(Some)]
+            set_extra_child = &gtk::Box {
+                set_orientation: gtk::Orientation::Vertical,
+                set_spacing: 12,
+
+                gtk::ShortcutLabel {
+                    set_halign: gtk::Align::Center,
+                    set_disabled_text: tr!("prefs-kb-dialog.waiting"),
+                    #[watch]
+                    set_accelerator: model.accelerator.as_deref().unwrap_or_default(),
+                },
+
+                gtk::Label {
+                    #[watch]
+                    set_visible: model.conflict().is_some(),
+                    #[watch]
+                    set_label: &model.conflict().map(|conflict| {
+                        tr!("prefs-kb-dialog.conflict", {
+                            "action" => conflict.label(),
+                        })
+                        .to_string()
+                    }).unwrap_or_default(),
+                    set_wrap: true,
+                    set_justify: gtk::Justification::Center,
+                    add_css_class: "warning",
+                },
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let KeybindingDialogInit {
+            action,
+            keybindings,
+        } = init;
+
+        let model = KeybindingDialog {
+            action,
+            keybindings,
+            accelerator: None,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            KeybindingDialogInput::KeyPressed(accelerator) => {
+                self.accelerator = Some(accelerator);
+            }
+            KeybindingDialogInput::Set => {
+                if let Some(accelerator) = self.accelerator.take() {
+                    sender
+                        .output(KeybindingDialogOutput::Bind(self.action, accelerator))
+                        .unwrap();
+                }
+            }
+            KeybindingDialogInput::Disable => {
+                sender
+                    .output(KeybindingDialogOutput::Unbind(self.action))
+                    .unwrap();
+            }
+        }
+    }
+}
+
+impl KeybindingDialog {
+    /// Action that will lose its keybinding to this one.
+    fn conflict(&self) -> Option<KeybindingAction> {
+        self.accelerator
+            .as_ref()
+            .and_then(|accelerator| self.keybindings.conflict(accelerator, self.action))
+    }
+}
@@ -0,0 +1,180 @@
+mod keybinding_dialog;
+
+use adw::prelude::*;
+use relm4::prelude::*;
+
+use crate::{
+    config::keybindings::{KeybindingAction, Keybindings},
+    globals::CONFIG,
+    tr,
+};
+
+use self::keybinding_dialog::{KeybindingDialog, KeybindingDialogInit, KeybindingDialogOutput};
+
+pub struct KeybindingsPreferences {
+    keybindings: Keybindings,
+    rows: Vec<adw::ActionRow>,
+    keybinding_dialog: Option<Controller<KeybindingDialog>>,
+}
+
+#[derive(Debug)]
+pub enum KeybindingsPreferencesInput {
+    UpdateConfig(Keybindings),
+    Edit(KeybindingAction),
+    Bind(KeybindingAction, String),
+    Unbind(KeybindingAction),
+    Reset,
+}
+
+#[relm4::component(pub)]
+impl Component for KeybindingsPreferences {
+    type Init = ();
+    type Input = KeybindingsPreferencesInput;
+    type Output = ();
+    type CommandOutput = ();
+
+    view! {
+        adw::PreferencesPage {
+            set_title: tr!("prefs-kb-page"),
+            set_icon_name: Some("input-keyboard-symbolic"),
+
+            #[name = "group"]
+            add = &adw::PreferencesGroup {
+                set_title: tr!("prefs-kb-video-player.title"),
+                set_description: Some(tr!("prefs-kb-video-player.description")),
+
+                #[wrap(Some)]
+                set_header_suffix = &gtk::Button {
+                    adw::ButtonContent {
+                        set_icon_name: "edit-undo",
+                        set_label: tr!("prefs-kb-reset.label"),
+                    },
+                    set_tooltip: tr!("prefs-kb-reset.tooltip"),
+                    add_css_class: "flat",
+                    #[watch]
+                    set_sensitive: model.keybindings != Keybindings::default(),
+                    connect_clicked[sender] => move |_| {
+                        sender.input(KeybindingsPreferencesInput::Reset);
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let mut model = KeybindingsPreferences {
+            keybindings: CONFIG.read().video_player.keybindings.clone(),
+            rows: Vec::new(),
+            keybinding_dialog: None,
+        };
+        CONFIG.subscribe(sender.input_sender(), |config| {
+            KeybindingsPreferencesInput::UpdateConfig(config.video_player.keybindings.clone())
+        });
+
+        let widgets = view_output!();
+
+        model.update_rows(&widgets.group, &sender);
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            KeybindingsPreferencesInput::UpdateConfig(keybindings) => {
+                if keybindings != self.keybindings {
+                    self.keybindings = keybindings;
+                    self.update_rows(&widgets.group, &sender);
+                }
+                self.update_view(widgets, sender);
+                return;
+            }
+            KeybindingsPreferencesInput::Edit(action) => {
+                let mut builder = KeybindingDialog::builder();
+                if let Some(window) = root.root().and_downcast::<gtk::Window>() {
+                    builder = builder.transient_for(window);
+                }
+                self.keybinding_dialog = Some(
+                    builder
+                        .launch(KeybindingDialogInit {
+                            action,
+                            keybindings: self.keybindings.clone(),
+                        })
+                        .forward(sender.input_sender(), |output| match output {
+                            KeybindingDialogOutput::Bind(action, accelerator) => {
+                                KeybindingsPreferencesInput::Bind(action, accelerator)
+                            }
+                            KeybindingDialogOutput::Unbind(action) => {
+                                KeybindingsPreferencesInput::Unbind(action)
+                            }
+                        }),
+                );
+                return;
+            }
+            _ => {}
+        }
+
+        let mut config = CONFIG.write();
+        let keybindings = &mut config.video_player.keybindings;
+
+        match message {
+            KeybindingsPreferencesInput::UpdateConfig(_) | KeybindingsPreferencesInput::Edit(_) => {
+                // Already handled above
+                unreachable!();
+            }
+            KeybindingsPreferencesInput::Bind(action, accelerator) => {
+                keybindings.bind(action, accelerator);
+            }
+            KeybindingsPreferencesInput::Unbind(action) => {
+                keybindings.unbind(action);
+            }
+            KeybindingsPreferencesInput::Reset => {
+                *keybindings = Keybindings::default();
+            }
+        }
+
+        config.save().expect("Error saving config");
+    }
+}
+
+impl KeybindingsPreferences {
+    // Rows depend on which actions are bound, so they're rebuilt whenever the keybindings change
+    fn update_rows(&mut self, group: &adw::PreferencesGroup, sender: &ComponentSender<Self>) {
+        for row in self.rows.drain(..) {
+            group.remove(&row);
+        }
+
+        for action in self.keybindings.actions() {
+            let row = adw::ActionRow::builder()
+                .title(action.label())
+                .activatable(true)
+                .build();
+            row.add_suffix(
+                &gtk::ShortcutLabel::builder()
+                    .accelerator(self.keybindings.accelerators(action).join(" "))
+                    .disabled_text(tr!("prefs-kb-disabled"))
+                    .valign(gtk::Align::Center)
+                    .build(),
+            );
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            row.connect_activated({
+                let sender = sender.clone();
+                move |_| {
+                    sender.input(KeybindingsPreferencesInput::Edit(action));
+                }
+            });
+
+            group.add(&row);
+            self.rows.push(row);
+        }
+    }
+}
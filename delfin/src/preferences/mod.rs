@@ -1,4 +1,5 @@
 mod general_preferences;
+mod keybindings_preferences;
 mod video_player_preferences;
 
 use adw::prelude::*;
@@ -7,12 +8,14 @@ use relm4::prelude::*;
 use crate::tr;
 
 use self::{
-    general_preferences::GeneralPreferences, video_player_preferences::VideoPlayerPreferences,
+    general_preferences::GeneralPreferences, keybindings_preferences::KeybindingsPreferences,
+    video_player_preferences::VideoPlayerPreferences,
 };
 
 pub struct Preferences {
     general_preferences: Controller<GeneralPreferences>,
     video_player_preferences: Controller<VideoPlayerPreferences>,
+    keybindings_preferences: Controller<KeybindingsPreferences>,
 }
 
 #[relm4::component(pub)]
@@ -30,6 +33,7 @@ impl SimpleComponent for Preferences {
 
             add = model.general_preferences.widget(),
             add = model.video_player_preferences.widget(),
+            add = model.keybindings_preferences.widget(),
         }
     }
 
@@ -41,6 +45,7 @@ impl SimpleComponent for Preferences {
         let model = Preferences {
             general_preferences: GeneralPreferences::builder().launch(()).detach(),
             video_player_preferences: VideoPlayerPreferences::builder().launch(()).detach(),
+            keybindings_preferences: KeybindingsPreferences::builder().launch(()).detach(),
         };
         let widgets = view_output!();
         ComponentParts { model, widgets }
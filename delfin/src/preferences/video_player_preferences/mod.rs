@@ -1,14 +1,21 @@
 mod subtitles_preferences;
 
+use std::path::PathBuf;
+
 use adw::prelude::*;
+use gtk::gio;
 use relm4::prelude::*;
+use tracing::warn;
 
 use crate::{
     config::video_player_config::{
-        VideoPlayerBackendPreference, VideoPlayerConfig, VideoPlayerOnLeftClick,
+        MediaSegmentBehaviour, VideoPlayerBackendPreference, VideoPlayerConfig,
+        VideoPlayerOnLeftClick,
     },
-    globals::CONFIG,
+    globals::{AUDIO_DEVICES, CONFIG},
+    jellyfin_api::api::media_segments::MediaSegmentType,
     tr,
+    video_player::{backends::AudioDevice, screenshot::screenshot_directory},
 };
 
 use self::subtitles_preferences::SubtitlesPreferences;
@@ -16,18 +23,34 @@ use self::subtitles_preferences::SubtitlesPreferences;
 pub struct VideoPlayerPreferences {
     video_player_config: VideoPlayerConfig,
     subtitles_preferences: Controller<SubtitlesPreferences>,
+    audio_devices: Vec<AudioDevice>,
+    audio_device_model: gtk::StringList,
 }
 
 #[derive(Debug)]
 pub enum VideoPlayerPreferencesInput {
     UpdateConfig(VideoPlayerConfig),
+    AudioDevicesUpdated(Vec<AudioDevice>),
 
     SkipBackwardsAmount(usize),
     SkipForwardsAmount(usize),
     OnLeftClick(u32),
+    OnScroll(u32),
+    ResumeBehaviour(u32),
+    RememberSyncOffsets(bool),
+
+    AudioDevice(u32),
+    AudioPassthrough(bool),
+    NightMode(bool),
+    NormalizeLoudness(bool),
+
+    ChooseScreenshotDirectory,
+    ScreenshotDirectory(PathBuf),
+    ScreenshotFilename(String),
+
+    MediaSegmentBehaviour(MediaSegmentType, u32),
 
     IntroSkipper(bool),
-    IntroSkipperAutoSkip(bool),
     Jellyscrub(bool),
 
     Backend(u32),
@@ -96,42 +119,209 @@ impl Component for VideoPlayerPreferences {
                         sender.input(VideoPlayerPreferencesInput::OnLeftClick(cb.selected()));
                     },
                 },
-            },
 
-            add = model.subtitles_preferences.widget(),
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-on-scroll.title"),
+                    set_subtitle: tr!("prefs-vp-on-scroll.subtitle"),
+                    #[wrap(Some)]
+                    set_model = &gtk::StringList::new(&[
+                        tr!("prefs-vp-on-scroll-options.volume"),
+                        tr!("prefs-vp-on-scroll-options.seek"),
+                    ]),
+                    set_selected: video_player_config.on_scroll.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::OnScroll(cb.selected()));
+                    },
+                },
+
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-resume-behaviour.title"),
+                    set_subtitle: tr!("prefs-vp-resume-behaviour.subtitle"),
+                    #[wrap(Some)]
+                    set_model = &gtk::StringList::new(&[
+                        tr!("prefs-vp-resume-behaviour-options.resume"),
+                        tr!("prefs-vp-resume-behaviour-options.ask"),
+                        tr!("prefs-vp-resume-behaviour-options.restart"),
+                    ]),
+                    set_selected: video_player_config.resume_behaviour.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::ResumeBehaviour(cb.selected()));
+                    },
+                },
+
+                add = &adw::SwitchRow {
+                    set_title: tr!("prefs-vp-remember-sync-offsets.title"),
+                    set_subtitle: tr!("prefs-vp-remember-sync-offsets.subtitle"),
+                    set_active: video_player_config.remember_sync_offsets,
+                    connect_active_notify[sender] => move |sr| {
+                        sender.input(VideoPlayerPreferencesInput::RememberSyncOffsets(sr.is_active()));
+                    },
+                },
+            },
 
             add = &adw::PreferencesGroup {
-                set_title: tr!("prefs-vp-plugins"),
+                set_title: tr!("prefs-vp-audio"),
 
-                #[name = "intro_skipper_expander"]
-                add = &adw::ExpanderRow {
-                    set_title: tr!("prefs-vp-intro-skipper.title"),
-                    set_subtitle: tr!("prefs-vp-intro-skipper.subtitle", {
-                        "introSkipperUrl" => "https://github.com/ConfusedPolarBear/intro-skipper/",
-                    }),
-                    set_show_enable_switch: false,
+                #[name = "audio_device_row"]
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-audio-device.title"),
+                    set_subtitle: tr!("prefs-vp-audio-device.subtitle"),
+                    set_model: Some(&model.audio_device_model),
+                    #[watch]
+                    #[block_signal(audio_device_changed_handler)]
+                    set_selected: model.audio_device_index(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::AudioDevice(cb.selected()));
+                    } @audio_device_changed_handler,
+                },
+
+                add = &adw::SwitchRow {
+                    #[watch]
+                    set_visible: model.video_player_config.backend.supports_audio_passthrough(),
+                    set_title: tr!("prefs-vp-audio-passthrough.title"),
+                    set_subtitle: tr!("prefs-vp-audio-passthrough.subtitle"),
+                    set_active: video_player_config.audio_passthrough,
+                    connect_active_notify[sender] => move |sr| {
+                        sender.input(VideoPlayerPreferencesInput::AudioPassthrough(sr.is_active()));
+                    },
+                },
+
+                add = &adw::SwitchRow {
+                    set_title: tr!("prefs-vp-night-mode.title"),
+                    set_subtitle: tr!("prefs-vp-night-mode.subtitle"),
                     #[watch]
-                    set_enable_expansion: model.video_player_config.intro_skipper,
+                    #[block_signal(night_mode_changed_handler)]
+                    set_active: model.video_player_config.night_mode,
+                    connect_active_notify[sender] => move |sr| {
+                        sender.input(VideoPlayerPreferencesInput::NightMode(sr.is_active()));
+                    } @night_mode_changed_handler,
+                },
+
+                add = &adw::SwitchRow {
+                    set_title: tr!("prefs-vp-normalize-loudness.title"),
+                    set_subtitle: tr!("prefs-vp-normalize-loudness.subtitle"),
+                    #[watch]
+                    #[block_signal(normalize_loudness_changed_handler)]
+                    set_active: model.video_player_config.normalize_loudness,
+                    connect_active_notify[sender] => move |sr| {
+                        sender.input(VideoPlayerPreferencesInput::NormalizeLoudness(sr.is_active()));
+                    } @normalize_loudness_changed_handler,
+                },
+            },
+
+            add = &adw::PreferencesGroup {
+                set_title: tr!("prefs-vp-screenshots"),
+                set_description: Some(tr!("prefs-vp-screenshots.description")),
+
+                add = &adw::ActionRow {
+                    set_title: tr!("prefs-vp-screenshot-directory"),
                     #[watch]
-                    set_expanded: model.video_player_config.intro_skipper,
+                    set_subtitle: &model.screenshot_directory_label(),
 
-                    add_suffix = &gtk::Switch {
+                    add_suffix = &gtk::Button {
+                        set_icon_name: "folder-open-symbolic",
+                        set_tooltip_text: Some(tr!("prefs-vp-screenshot-directory.choose")),
                         set_valign: gtk::Align::Center,
-                        set_active: video_player_config.intro_skipper,
-                        connect_active_notify[sender] => move |switch| {
-                            sender.input(VideoPlayerPreferencesInput::IntroSkipper(switch.is_active()));
+                        add_css_class: "flat",
+                        connect_clicked[sender] => move |_| {
+                            sender.input(VideoPlayerPreferencesInput::ChooseScreenshotDirectory);
                         },
                     },
+                },
 
-                    add_row = &adw::SwitchRow {
-                        set_title: tr!("prefs-vp-intro-skipper-auto-skip.title"),
-                        set_subtitle: tr!("prefs-vp-intro-skipper-auto-skip.subtitle"),
-                        set_active: video_player_config.intro_skipper_auto_skip,
-                        connect_active_notify[sender] => move |sr| {
-                            sender.input(VideoPlayerPreferencesInput::IntroSkipperAutoSkip(sr.is_active()));
-                        },
-                        #[watch]
-                        set_sensitive: model.video_player_config.intro_skipper,
+                add = &adw::EntryRow {
+                    set_title: tr!("prefs-vp-screenshot-filename"),
+                    set_text: &video_player_config.screenshot_filename,
+                    set_show_apply_button: true,
+                    connect_apply[sender] => move |entry| {
+                        sender.input(VideoPlayerPreferencesInput::ScreenshotFilename(entry.text().into()));
+                    },
+                },
+            },
+
+            add = model.subtitles_preferences.widget(),
+
+            add = &adw::PreferencesGroup {
+                set_title: tr!("prefs-vp-media-segments"),
+                set_description: Some(tr!("prefs-vp-media-segments-description")),
+
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-media-segments-intro.title"),
+                    set_subtitle: tr!("prefs-vp-media-segments-intro.subtitle"),
+                    set_model: Some(&media_segment_behaviour_model()),
+                    set_selected: video_player_config.media_segments.intro.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::MediaSegmentBehaviour(
+                            MediaSegmentType::Intro,
+                            cb.selected(),
+                        ));
+                    },
+                },
+
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-media-segments-outro.title"),
+                    set_subtitle: tr!("prefs-vp-media-segments-outro.subtitle"),
+                    set_model: Some(&media_segment_behaviour_model()),
+                    set_selected: video_player_config.media_segments.outro.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::MediaSegmentBehaviour(
+                            MediaSegmentType::Outro,
+                            cb.selected(),
+                        ));
+                    },
+                },
+
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-media-segments-recap.title"),
+                    set_subtitle: tr!("prefs-vp-media-segments-recap.subtitle"),
+                    set_model: Some(&media_segment_behaviour_model()),
+                    set_selected: video_player_config.media_segments.recap.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::MediaSegmentBehaviour(
+                            MediaSegmentType::Recap,
+                            cb.selected(),
+                        ));
+                    },
+                },
+
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-media-segments-preview.title"),
+                    set_subtitle: tr!("prefs-vp-media-segments-preview.subtitle"),
+                    set_model: Some(&media_segment_behaviour_model()),
+                    set_selected: video_player_config.media_segments.preview.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::MediaSegmentBehaviour(
+                            MediaSegmentType::Preview,
+                            cb.selected(),
+                        ));
+                    },
+                },
+
+                add = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-media-segments-commercial.title"),
+                    set_subtitle: tr!("prefs-vp-media-segments-commercial.subtitle"),
+                    set_model: Some(&media_segment_behaviour_model()),
+                    set_selected: video_player_config.media_segments.commercial.into(),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(VideoPlayerPreferencesInput::MediaSegmentBehaviour(
+                            MediaSegmentType::Commercial,
+                            cb.selected(),
+                        ));
+                    },
+                },
+            },
+
+            add = &adw::PreferencesGroup {
+                set_title: tr!("prefs-vp-plugins"),
+
+                add = &adw::SwitchRow {
+                    set_title: tr!("prefs-vp-intro-skipper.title"),
+                    set_subtitle: tr!("prefs-vp-intro-skipper.subtitle", {
+                        "introSkipperUrl" => "https://github.com/ConfusedPolarBear/intro-skipper/",
+                    }),
+                    set_active: video_player_config.intro_skipper,
+                    connect_active_notify[sender] => move |sr| {
+                        sender.input(VideoPlayerPreferencesInput::IntroSkipper(sr.is_active()));
                     },
                 },
 
@@ -193,7 +383,10 @@ impl Component for VideoPlayerPreferences {
         let model = VideoPlayerPreferences {
             video_player_config: video_player_config.clone(),
             subtitles_preferences: SubtitlesPreferences::builder().launch(()).detach(),
+            audio_devices: AUDIO_DEVICES.read().clone(),
+            audio_device_model: gtk::StringList::default(),
         };
+        model.update_audio_device_model();
         VideoPlayerPreferences::subscribe_to_config(&sender);
 
         let widgets = view_output!();
@@ -206,18 +399,34 @@ impl Component for VideoPlayerPreferences {
         widgets: &mut Self::Widgets,
         message: Self::Input,
         sender: ComponentSender<Self>,
-        _root: &Self::Root,
+        root: &Self::Root,
     ) {
-        if let VideoPlayerPreferencesInput::UpdateConfig(video_player_config) = message {
-            self.video_player_config = video_player_config;
-            self.update_view(widgets, sender);
-            return;
+        match message {
+            VideoPlayerPreferencesInput::UpdateConfig(video_player_config) => {
+                self.video_player_config = video_player_config;
+                self.refresh_audio_devices(widgets);
+                self.update_view(widgets, sender);
+                return;
+            }
+            VideoPlayerPreferencesInput::AudioDevicesUpdated(audio_devices) => {
+                self.audio_devices = audio_devices;
+                self.refresh_audio_devices(widgets);
+                self.update_view(widgets, sender);
+                return;
+            }
+            VideoPlayerPreferencesInput::ChooseScreenshotDirectory => {
+                self.choose_screenshot_directory(root, &sender);
+                return;
+            }
+            _ => {}
         }
 
         let mut config = CONFIG.write();
 
         match message {
-            VideoPlayerPreferencesInput::UpdateConfig(_) => {
+            VideoPlayerPreferencesInput::UpdateConfig(_)
+            | VideoPlayerPreferencesInput::AudioDevicesUpdated(_)
+            | VideoPlayerPreferencesInput::ChooseScreenshotDirectory => {
                 // Already handled above
                 unreachable!();
             }
@@ -233,13 +442,50 @@ impl Component for VideoPlayerPreferences {
                     _ => VideoPlayerOnLeftClick::ToggleControls,
                 };
             }
+            VideoPlayerPreferencesInput::OnScroll(index) => {
+                config.video_player.on_scroll = index.into();
+            }
+            VideoPlayerPreferencesInput::ResumeBehaviour(index) => {
+                config.video_player.resume_behaviour = index.into();
+            }
+            VideoPlayerPreferencesInput::RememberSyncOffsets(remember_sync_offsets) => {
+                config.video_player.remember_sync_offsets = remember_sync_offsets;
+            }
+
+            VideoPlayerPreferencesInput::AudioDevice(index) => {
+                config.video_player.audio_device = self
+                    .audio_device_options()
+                    .into_iter()
+                    .nth(index as usize)
+                    .and_then(|(name, _)| name);
+            }
+            VideoPlayerPreferencesInput::AudioPassthrough(audio_passthrough) => {
+                config.video_player.audio_passthrough = audio_passthrough;
+            }
+            VideoPlayerPreferencesInput::NightMode(night_mode) => {
+                config.video_player.night_mode = night_mode;
+            }
+            VideoPlayerPreferencesInput::NormalizeLoudness(normalize_loudness) => {
+                config.video_player.normalize_loudness = normalize_loudness;
+            }
+
+            VideoPlayerPreferencesInput::ScreenshotDirectory(directory) => {
+                config.video_player.screenshot_directory = Some(directory);
+            }
+            VideoPlayerPreferencesInput::ScreenshotFilename(screenshot_filename) => {
+                config.video_player.screenshot_filename = screenshot_filename;
+            }
+
+            VideoPlayerPreferencesInput::MediaSegmentBehaviour(segment_type, index) => {
+                config
+                    .video_player
+                    .media_segments
+                    .set_behaviour(segment_type, index.into());
+            }
 
             VideoPlayerPreferencesInput::IntroSkipper(intro_skipper) => {
                 config.video_player.intro_skipper = intro_skipper;
             }
-            VideoPlayerPreferencesInput::IntroSkipperAutoSkip(intro_skipper_auto_skip) => {
-                config.video_player.intro_skipper_auto_skip = intro_skipper_auto_skip;
-            }
             VideoPlayerPreferencesInput::Jellyscrub(jellyscrub) => {
                 config.video_player.jellyscrub = jellyscrub;
             }
@@ -266,5 +512,102 @@ impl VideoPlayerPreferences {
         CONFIG.subscribe(sender.input_sender(), |config| {
             VideoPlayerPreferencesInput::UpdateConfig(config.video_player.clone())
         });
+        AUDIO_DEVICES.subscribe(sender.input_sender(), |audio_devices| {
+            VideoPlayerPreferencesInput::AudioDevicesUpdated(audio_devices.clone())
+        });
+    }
+
+    // Device names paired with their labels, where None picks the device automatically
+    fn audio_device_options(&self) -> Vec<(Option<String>, String)> {
+        let mut options = vec![(None, tr!("prefs-vp-audio-device-auto").to_string())];
+        options.extend(
+            self.audio_devices
+                .iter()
+                .map(|device| (Some(device.name.clone()), device.description.clone())),
+        );
+
+        // Keep showing the configured device when it isn't currently available
+        if let Some(audio_device) = &self.video_player_config.audio_device {
+            if !self
+                .audio_devices
+                .iter()
+                .any(|device| &device.name == audio_device)
+            {
+                options.push((Some(audio_device.clone()), audio_device.clone()));
+            }
+        }
+
+        options
+    }
+
+    fn audio_device_index(&self) -> u32 {
+        self.audio_device_options()
+            .iter()
+            .position(|(name, _)| *name == self.video_player_config.audio_device)
+            .unwrap_or(0) as u32
+    }
+
+    fn update_audio_device_model(&self) {
+        let labels: Vec<String> = self
+            .audio_device_options()
+            .into_iter()
+            .map(|(_, label)| label)
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        self.audio_device_model
+            .splice(0, self.audio_device_model.n_items(), &labels);
+    }
+
+    fn refresh_audio_devices(&self, widgets: &VideoPlayerPreferencesWidgets) {
+        // Replacing the options changes the selection, which shouldn't change the config
+        widgets
+            .audio_device_row
+            .block_signal(&widgets.audio_device_changed_handler);
+        self.update_audio_device_model();
+        widgets
+            .audio_device_row
+            .unblock_signal(&widgets.audio_device_changed_handler);
+    }
+
+    fn screenshot_directory_label(&self) -> String {
+        screenshot_directory(&self.video_player_config)
+            .map(|directory| directory.display().to_string())
+            .unwrap_or_default()
+    }
+
+    fn choose_screenshot_directory(
+        &self,
+        root: &adw::PreferencesPage,
+        sender: &ComponentSender<Self>,
+    ) {
+        let dialog = gtk::FileDialog::builder()
+            .title(tr!("prefs-vp-screenshot-directory.choose"))
+            .modal(true)
+            .build();
+        if let Ok(directory) = screenshot_directory(&self.video_player_config) {
+            dialog.set_initial_folder(Some(&gio::File::for_path(directory)));
+        }
+
+        let window = root.root().and_downcast::<gtk::Window>();
+        dialog.select_folder(window.as_ref(), None::<&gio::Cancellable>, {
+            let sender = sender.clone();
+            move |res| match res {
+                Ok(folder) => {
+                    if let Some(directory) = folder.path() {
+                        sender.input(VideoPlayerPreferencesInput::ScreenshotDirectory(directory));
+                    }
+                }
+                Err(err) if err.matches(gtk::DialogError::Dismissed) => {}
+                Err(err) => warn!("Error choosing screenshot folder: {err}"),
+            }
+        });
     }
 }
+
+fn media_segment_behaviour_model() -> gtk::StringList {
+    gtk::StringList::new(&[
+        tr!("prefs-vp-media-segments-options.ask"),
+        tr!("prefs-vp-media-segments-options.auto-skip"),
+        tr!("prefs-vp-media-segments-options.ignore"),
+    ])
+}
@@ -4,6 +4,7 @@ use relm4::prelude::*;
 
 use crate::config::video_player_config::VideoPlayerConfig;
 use crate::globals::CONFIG;
+use crate::jellyfin_api::api::user::SubtitleMode;
 use crate::tr;
 use crate::utils::rgba::RGBA;
 use crate::video_player::backends::VideoPlayerSubtitleFont;
@@ -20,7 +21,11 @@ pub(crate) enum SubtitlesPreferencesInput {
     SubtitleColour(RGBA),
     SubtitleBackgroundColour(RGBA),
     SubtitlePosition(f64),
+    SecondarySubtitlePosition(f64),
     SubtitleFont(VideoPlayerSubtitleFont),
+    AudioLanguage(String),
+    SubtitleLanguage(String),
+    SubtitleMode(u32),
 }
 
 #[relm4::component(pub(crate))]
@@ -48,6 +53,45 @@ impl SimpleComponent for SubtitlesPreferences {
                 },
             },
 
+            add = &adw::ExpanderRow {
+                set_title: tr!("prefs-vp-subs-track-selection.title"),
+                set_subtitle: tr!("prefs-vp-subs-track-selection.subtitle"),
+
+                add_row = &adw::EntryRow {
+                    set_title: tr!("prefs-vp-subs-audio-language"),
+                    set_text: model.video_player_config.subtitles.audio_language.as_deref().unwrap_or_default(),
+                    set_show_apply_button: true,
+                    connect_apply[sender] => move |entry| {
+                        sender.input(SubtitlesPreferencesInput::AudioLanguage(entry.text().into()));
+                    },
+                },
+
+                add_row = &adw::EntryRow {
+                    set_title: tr!("prefs-vp-subs-subtitle-language"),
+                    set_text: model.video_player_config.subtitles.subtitle_language.as_deref().unwrap_or_default(),
+                    set_show_apply_button: true,
+                    connect_apply[sender] => move |entry| {
+                        sender.input(SubtitlesPreferencesInput::SubtitleLanguage(entry.text().into()));
+                    },
+                },
+
+                add_row = &adw::ComboRow {
+                    set_title: tr!("prefs-vp-subs-mode.title"),
+                    set_model: Some(&gtk::StringList::new(&[
+                        tr!("prefs-vp-subs-mode-options.server"),
+                        tr!("prefs-vp-subs-mode-options.default"),
+                        tr!("prefs-vp-subs-mode-options.always"),
+                        tr!("prefs-vp-subs-mode-options.only-forced"),
+                        tr!("prefs-vp-subs-mode-options.smart"),
+                        tr!("prefs-vp-subs-mode-options.none"),
+                    ])),
+                    set_selected: subtitle_mode_to_index(model.video_player_config.subtitles.subtitle_mode),
+                    connect_selected_notify[sender] => move |cb| {
+                        sender.input(SubtitlesPreferencesInput::SubtitleMode(cb.selected()));
+                    },
+                },
+            },
+
             add = &adw::ExpanderRow {
                 set_title: tr!("prefs-vp-subs-style"),
 
@@ -184,6 +228,29 @@ impl SimpleComponent for SubtitlesPreferences {
                         sender.input(SubtitlesPreferencesInput::SubtitlePosition(spinrow.value()));
                     } @subtitle_position_change_handler,
                 },
+
+                add_row = &adw::SpinRow::new(
+                    Some(&gtk::Adjustment::new(
+                        model.video_player_config.subtitles.secondary_position as f64,
+                        0.0, 150.0, 1.0, 1.0, 0.0,
+                    )),
+                    // Climb rate
+                    1.0,
+                    // Digits
+                    0,
+                ) {
+                    #[watch]
+                    set_visible: model.video_player_config.backend.supports_secondary_subtitles(),
+                    set_title: tr!("prefs-vp-subs-secondary-position.title"),
+                    set_subtitle: tr!("prefs-vp-subs-secondary-position.subtitle"),
+
+                    #[watch]
+                    #[block_signal(secondary_subtitle_position_change_handler)]
+                    set_value: model.video_player_config.subtitles.secondary_position as f64,
+                    connect_changed[sender] => move |spinrow| {
+                        sender.input(SubtitlesPreferencesInput::SecondarySubtitlePosition(spinrow.value()));
+                    } @secondary_subtitle_position_change_handler,
+                },
             },
         }
     }
@@ -225,6 +292,8 @@ impl SimpleComponent for SubtitlesPreferences {
                 config.video_player.subtitles.background_colour =
                     default.subtitles.background_colour;
                 config.video_player.subtitles.position = default.subtitles.position;
+                config.video_player.subtitles.secondary_position =
+                    default.subtitles.secondary_position;
                 config.video_player.subtitles.font = default.subtitles.font;
             }
             SubtitlesPreferencesInput::SubtitleScale(scale) => {
@@ -239,9 +308,25 @@ impl SimpleComponent for SubtitlesPreferences {
             SubtitlesPreferencesInput::SubtitlePosition(position) => {
                 config.video_player.subtitles.position = position as u32;
             }
+            SubtitlesPreferencesInput::SecondarySubtitlePosition(position) => {
+                config.video_player.subtitles.secondary_position = position as u32;
+            }
             SubtitlesPreferencesInput::SubtitleFont(font) => {
                 config.video_player.subtitles.font = font;
             }
+            SubtitlesPreferencesInput::AudioLanguage(language) => {
+                let language = language.trim();
+                config.video_player.subtitles.audio_language =
+                    (!language.is_empty()).then(|| language.to_owned());
+            }
+            SubtitlesPreferencesInput::SubtitleLanguage(language) => {
+                let language = language.trim();
+                config.video_player.subtitles.subtitle_language =
+                    (!language.is_empty()).then(|| language.to_owned());
+            }
+            SubtitlesPreferencesInput::SubtitleMode(index) => {
+                config.video_player.subtitles.subtitle_mode = subtitle_mode_from_index(index);
+            }
         }
 
         config.save().expect("Error saving config");
@@ -258,6 +343,8 @@ impl SubtitlesPreferences {
             || (video_player_config.subtitles.background_colour
                 != default.subtitles.background_colour)
             || (video_player_config.subtitles.position != default.subtitles.position)
+            || (video_player_config.subtitles.secondary_position
+                != default.subtitles.secondary_position)
             || (video_player_config.subtitles.font != default.subtitles.font)
     }
 
@@ -285,3 +372,27 @@ impl SubtitlesPreferences {
         })
     }
 }
+
+// Index 0 uses the subtitle mode from the user's Jellyfin configuration
+fn subtitle_mode_to_index(subtitle_mode: Option<SubtitleMode>) -> u32 {
+    match subtitle_mode {
+        None => 0,
+        Some(SubtitleMode::Default) => 1,
+        Some(SubtitleMode::Always) => 2,
+        Some(SubtitleMode::OnlyForced) => 3,
+        Some(SubtitleMode::Smart) => 4,
+        Some(SubtitleMode::None) => 5,
+    }
+}
+
+fn subtitle_mode_from_index(index: u32) -> Option<SubtitleMode> {
+    match index {
+        0 => None,
+        1 => Some(SubtitleMode::Default),
+        2 => Some(SubtitleMode::Always),
+        3 => Some(SubtitleMode::OnlyForced),
+        4 => Some(SubtitleMode::Smart),
+        5 => Some(SubtitleMode::None),
+        _ => unreachable!("subtitle mode index {index} does not exist"),
+    }
+}
@@ -0,0 +1,34 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{de::DeserializeOwned, Serialize};
+use tracing::warn;
+
+/// Read a JSON file from the data directory, falling back to the default if it doesn't exist or
+/// can't be parsed.
+pub fn read_data_file<T: DeserializeOwned + Default>(name: &str) -> T {
+    let Some(path) = data_file_path(name) else {
+        return T::default();
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|err| {
+            warn!("Error parsing data file {}: {err}", path.display());
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+pub fn write_data_file<T: Serialize>(name: &str, contents: &T) -> Result<()> {
+    let path = data_file_path(name).context("Could not find data directory")?;
+    let dir = path.parent().context("Data file has no parent directory")?;
+    fs::create_dir_all(dir)?;
+    fs::write(path, serde_json::to_string(contents)?)?;
+    Ok(())
+}
+
+fn data_file_path(name: &str) -> Option<PathBuf> {
+    let data_dir = dirs::data_dir()?;
+    Some(data_dir.join("delfin").join(name))
+}
@@ -1,5 +1,6 @@
 pub mod bif;
 pub mod constants;
+pub mod data_file;
 pub mod debounce;
 pub mod device_profile;
 pub mod display_years;
@@ -13,3 +14,4 @@ pub mod rgba;
 pub mod round;
 pub mod shift_state;
 pub mod ticks;
+pub mod timestamp;
@@ -3,11 +3,23 @@ use std::sync::Arc;
 use jellyfin_api::types::{BaseItemDto, BaseItemKind};
 use uuid::Uuid;
 
-use crate::jellyfin_api::{
-    api::{latest::GetNextUpOptionsBuilder, shows::GetEpisodesOptionsBuilder},
-    api_client::ApiClient,
+use crate::{
+    jellyfin_api::{
+        api::{latest::GetNextUpOptionsBuilder, shows::GetEpisodesOptionsBuilder},
+        api_client::ApiClient,
+    },
+    utils::ticks::ticks_to_seconds,
 };
 
+// Returns the position (in seconds) that playback of the given media would resume from, if it was
+// partially watched.
+pub fn get_resume_position(media: &BaseItemDto) -> Option<usize> {
+    let playback_position_ticks = media.user_data.as_ref()?.playback_position_ticks?;
+    usize::try_from(ticks_to_seconds(playback_position_ticks))
+        .ok()
+        .filter(|position| *position > 0)
+}
+
 // Gets the next playable media for the given media item.
 // For episodes and movies, this just returns the passed in media.
 // For TV shows, this looks for the first episode that the user can resume, continue the series
@@ -41,4 +41,13 @@ impl RGBA {
             (self.0.blue() * 255.0) as usize,
         )
     }
+
+    /// Converts to a number in the format 0xAARRGGBB (used by GStreamer)
+    #[must_use]
+    pub fn to_argb(&self) -> u32 {
+        (((self.0.alpha() * 255.0) as u32) << 24)
+            | (((self.0.red() * 255.0) as u32) << 16)
+            | (((self.0.green() * 255.0) as u32) << 8)
+            | ((self.0.blue() * 255.0) as u32)
+    }
 }
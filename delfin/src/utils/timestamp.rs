@@ -0,0 +1,31 @@
+use chrono::TimeDelta;
+use tracing::warn;
+
+#[must_use]
+pub fn seconds_to_timestamp(seconds: usize) -> String {
+    let Some(time) = TimeDelta::try_seconds(seconds as i64) else {
+        warn!("Could not convert seconds to TimeDelta");
+        return String::new();
+    };
+
+    let hours = time.num_hours();
+    let minutes = time.num_minutes() - (60 * hours);
+    let seconds = time.num_seconds() % 60;
+
+    if hours > 0 {
+        format!("{hours}:{minutes:0>2}:{seconds:0>2}")
+    } else {
+        format!("{minutes:0>2}:{seconds:0>2}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seconds_to_timestamp() {
+        assert_eq!(seconds_to_timestamp(8624), "2:23:44");
+        assert_eq!(seconds_to_timestamp(345), "05:45");
+    }
+}
@@ -0,0 +1,118 @@
+use crate::{tr, utils::timestamp::seconds_to_timestamp};
+
+/// Section of the video that playback repeats, set one point at a time.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AbLoop {
+    pub start: Option<usize>,
+    pub end: Option<usize>,
+}
+
+impl AbLoop {
+    fn new(start: usize, end: Option<usize>) -> Self {
+        match end {
+            Some(end) if end > start => Self {
+                start: Some(start),
+                end: Some(end),
+            },
+            // Allow the points to be set in either order
+            Some(end) if end < start => Self {
+                start: Some(end),
+                end: Some(start),
+            },
+            _ => Self {
+                start: Some(start),
+                end: None,
+            },
+        }
+    }
+
+    pub fn with_start(self, start: usize) -> Self {
+        Self::new(start, self.end)
+    }
+
+    /// Setting the end without a start loops from the beginning of the video.
+    pub fn with_end(self, end: usize) -> Self {
+        Self::new(self.start.unwrap_or(0), Some(end))
+    }
+
+    /// Set the start, then the end, then clear the loop, like mpv's `l` key.
+    pub fn cycle(self, position: usize) -> Self {
+        match (self.start, self.end) {
+            (None, _) => self.with_start(position),
+            (Some(_), None) => self.with_end(position),
+            (Some(_), Some(_)) => Self::default(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start.is_none() && self.end.is_none()
+    }
+
+    pub fn indicator_label(&self) -> String {
+        match (self.start, self.end) {
+            (Some(start), Some(end)) => tr!("vp-ab-loop-indicator.active", {
+                "start" => seconds_to_timestamp(start),
+                "end" => seconds_to_timestamp(end),
+            })
+            .to_string(),
+            (Some(start), None) => tr!("vp-ab-loop-indicator.start", {
+                "start" => seconds_to_timestamp(start),
+            })
+            .to_string(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ab_loop_cycle() {
+        let ab_loop = AbLoop::default().cycle(30);
+        assert_eq!(
+            ab_loop,
+            AbLoop {
+                start: Some(30),
+                end: None
+            }
+        );
+
+        let ab_loop = ab_loop.cycle(45);
+        assert_eq!(
+            ab_loop,
+            AbLoop {
+                start: Some(30),
+                end: Some(45)
+            }
+        );
+
+        assert!(ab_loop.cycle(60).is_empty());
+    }
+
+    #[test]
+    fn test_ab_loop_order() {
+        assert_eq!(
+            AbLoop::default().with_start(30).with_end(10),
+            AbLoop {
+                start: Some(10),
+                end: Some(30)
+            }
+        );
+        assert_eq!(
+            AbLoop::default().with_end(10),
+            AbLoop {
+                start: Some(0),
+                end: Some(10)
+            }
+        );
+        assert_eq!(
+            AbLoop::default().with_start(30).with_start(30),
+            AbLoop {
+                start: Some(30),
+                end: None
+            }
+        );
+    }
+}
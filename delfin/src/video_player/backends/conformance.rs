@@ -0,0 +1,303 @@
+// Checks that every backend behaves the same way, by playing a local video with each of them.
+//
+// Real backends need a display and a video to play, so they're ignored by default. Run them with:
+//
+//     DELFIN_TEST_MEDIA=/path/to/video.mkv cargo test --all-features -- --ignored conformance
+//
+// The video should be at least 10 seconds long. The same checks always run against the fake
+// backend's simulated playback, so they keep working without a display.
+//
+// CI's test step (.woodpecker/test.yml) runs plain `cargo test` without a display, so it only runs
+// the fake backend's checks. The real backends are expected to be checked under a virtual display
+// instead, with the same command wrapped in `xvfb-run -a`, before backend changes are merged.
+
+use std::{
+    cell::{Ref, RefCell},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use relm4::gtk::{self, glib, prelude::*};
+
+use super::{
+    fake::{Inputs, VideoPlayerBackendFake},
+    mpv::VideoPlayerBackendMpv,
+    PlayerState, SubtitleTrack, VideoPlayerBackend,
+};
+
+// How long to wait for the player to do something before failing
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const TEST_SUBTITLES: &str = "1
+00:00:00,000 --> 00:00:30,000
+Conformance test subtitles
+";
+
+fn test_media_uri() -> String {
+    let path = std::env::var("DELFIN_TEST_MEDIA")
+        .expect("DELFIN_TEST_MEDIA should be set to the path of a video to play");
+    let path = PathBuf::from(path)
+        .canonicalize()
+        .expect("Test media not found");
+    glib::filename_to_uri(path, None).unwrap().to_string()
+}
+
+// Runs the main loop until the condition is met, so the player can handle events
+fn wait_for(what: &str, condition: impl Fn() -> bool) {
+    let deadline = Instant::now() + TIMEOUT;
+    let context = glib::MainContext::default();
+    while !condition() {
+        assert!(Instant::now() < deadline, "Timed out waiting for {what}");
+        if !context.iteration(false) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+// What a backend reported through its callbacks
+#[derive(Debug)]
+enum Event {
+    PlayerStateChanged(PlayerState),
+    PositionUpdated(usize),
+    DurationUpdated(usize),
+    EndOfStream,
+    Error(String),
+    SubtitleTracksUpdated(Vec<SubtitleTrack>),
+}
+
+#[derive(Debug)]
+struct Reported {
+    state: PlayerState,
+    position: usize,
+    duration: usize,
+    ended: bool,
+    error: Option<String>,
+    subtitle_tracks: Vec<SubtitleTrack>,
+}
+
+struct Harness<B: VideoPlayerBackend> {
+    backend: B,
+    window: Option<gtk::Window>,
+    events: Inputs<Event>,
+    reported: RefCell<Reported>,
+}
+
+impl<B: VideoPlayerBackend> Harness<B> {
+    fn new(mut backend: B) -> Self {
+        let events = Inputs::default();
+
+        backend.connect_player_state_changed({
+            let send = events.sender();
+            Box::new(move |state| send(Event::PlayerStateChanged(state)))
+        });
+        backend.connect_position_updated({
+            let send = events.sender();
+            Box::new(move |position| send(Event::PositionUpdated(position)))
+        });
+        backend.connect_duration_updated({
+            let send = events.sender();
+            Box::new(move |duration| send(Event::DurationUpdated(duration)))
+        });
+        backend.connect_end_of_stream({
+            let send = events.sender();
+            Box::new(move || send(Event::EndOfStream))
+        });
+        backend.connect_error({
+            let send = events.sender();
+            Box::new(move |message| send(Event::Error(message)))
+        });
+        backend.connect_subtitle_tracks_updated({
+            let send = events.sender();
+            Box::new(move |tracks| send(Event::SubtitleTracksUpdated(tracks)))
+        });
+
+        Self {
+            backend,
+            window: None,
+            events,
+            reported: RefCell::new(Reported {
+                state: PlayerState::Loading,
+                position: 0,
+                duration: 0,
+                ended: false,
+                error: None,
+                subtitle_tracks: vec![],
+            }),
+        }
+    }
+
+    // Catches up on everything the backend reported so far
+    fn reported(&self) -> Ref<'_, Reported> {
+        {
+            let mut reported = self.reported.borrow_mut();
+            for event in self.events.take() {
+                match event {
+                    Event::PlayerStateChanged(state) => reported.state = state,
+                    Event::PositionUpdated(position) => reported.position = position,
+                    Event::DurationUpdated(duration) => reported.duration = duration,
+                    Event::EndOfStream => reported.ended = true,
+                    Event::Error(message) => reported.error = Some(message),
+                    Event::SubtitleTracksUpdated(tracks) => reported.subtitle_tracks = tracks,
+                }
+            }
+        }
+        self.reported.borrow()
+    }
+
+    // Some backends only play once their widget is shown
+    fn show(mut self) -> Self {
+        let window = gtk::Window::builder()
+            .child(self.backend.widget())
+            .default_width(640)
+            .default_height(360)
+            .build();
+        window.present();
+        self.window = Some(window);
+        self
+    }
+
+    fn wait_for_state(&self, what: &str, condition: impl Fn(PlayerState) -> bool) {
+        wait_for(what, || condition(self.reported().state));
+    }
+
+    fn check_plays(&mut self, uri: &str) {
+        self.backend.play_uri(uri);
+        self.wait_for_state("playback to start", |state| {
+            matches!(state, PlayerState::Playing { paused: false })
+        });
+        wait_for("duration", || self.reported().duration > 0);
+        wait_for("position to update", || self.reported().position >= 1);
+    }
+
+    fn check_pause_and_resume(&self) {
+        self.backend.pause();
+        self.wait_for_state("pause", |state| {
+            matches!(state, PlayerState::Playing { paused: true })
+        });
+
+        self.backend.play();
+        self.wait_for_state("resume", |state| {
+            matches!(state, PlayerState::Playing { paused: false })
+        });
+    }
+
+    fn check_seek(&self) {
+        self.backend.seek_to(5);
+        wait_for("seek to 5 seconds", || {
+            (5..=6).contains(&self.backend.position())
+        });
+
+        self.backend.seek_by(-3);
+        wait_for("seek back 3 seconds", || {
+            (2..=3).contains(&self.backend.position())
+        });
+    }
+
+    fn check_volume(&self) {
+        self.backend.set_volume(0.5);
+        wait_for("volume to change", || {
+            (self.backend.volume() - 0.5).abs() < 0.01
+        });
+
+        self.backend.set_muted(true);
+        wait_for("mute", || self.backend.muted());
+        self.backend.set_muted(false);
+        wait_for("unmute", || !self.backend.muted());
+    }
+
+    fn check_playback_speed(&self) {
+        self.backend.set_playback_speed(1.5);
+        wait_for("playback speed to change", || {
+            (self.backend.playback_speed() - 1.5).abs() < 0.01
+        });
+        self.backend.set_playback_speed(1.0);
+    }
+
+    fn check_frame_step(&self) {
+        self.backend.frame_step_forwards();
+        self.wait_for_state("frame step forwards to pause", |state| {
+            matches!(state, PlayerState::Playing { paused: true })
+        });
+        self.backend.frame_step_backwards();
+        self.wait_for_state("frame step backwards to stay paused", |state| {
+            matches!(state, PlayerState::Playing { paused: true })
+        });
+
+        self.backend.play();
+        self.wait_for_state("resume after frame stepping", |state| {
+            matches!(state, PlayerState::Playing { paused: false })
+        });
+    }
+
+    fn check_external_subtitles(&self) {
+        let path = std::env::temp_dir().join("delfin-conformance-test.srt");
+        std::fs::write(&path, TEST_SUBTITLES).unwrap();
+        let uri = glib::filename_to_uri(&path, None).unwrap();
+
+        let tracks_before = self.reported().subtitle_tracks.len();
+        self.backend.add_subtitle_track(&uri, "Conformance Test");
+        wait_for("external subtitles to load", || {
+            self.reported().subtitle_tracks.len() > tracks_before
+        });
+    }
+
+    fn check_end_of_stream(&self) {
+        let duration = self.reported().duration;
+        self.backend.seek_to(duration.saturating_sub(1));
+        wait_for("end of stream", || self.reported().ended);
+    }
+
+    fn check_error(&mut self) {
+        self.backend
+            .play_uri("file:///nonexistent/delfin-conformance-test.mkv");
+        self.wait_for_state("error state", |state| matches!(state, PlayerState::Error));
+        wait_for("error message", || self.reported().error.is_some());
+    }
+}
+
+fn run_conformance(mut harness: Harness<impl VideoPlayerBackend>, uri: &str) {
+    harness.check_plays(uri);
+    harness.check_pause_and_resume();
+    harness.check_seek();
+    harness.check_volume();
+    harness.check_playback_speed();
+    harness.check_frame_step();
+    harness.check_external_subtitles();
+    harness.check_end_of_stream();
+    harness.check_error();
+
+    harness.backend.stop();
+    if let Some(window) = harness.window {
+        window.destroy();
+    }
+}
+
+// Backends are checked one after the other in a single test, since GTK can only be used from the
+// thread it was initialized on
+#[test]
+#[ignore = "needs a display and DELFIN_TEST_MEDIA"]
+fn conformance() {
+    gtk::init().unwrap();
+    let uri = test_media_uri();
+
+    run_conformance(Harness::new(VideoPlayerBackendMpv::default()).show(), &uri);
+
+    #[cfg(feature = "gst")]
+    {
+        video_player_gst::init_gst();
+        run_conformance(
+            Harness::new(super::gst::VideoPlayerBackendGst::default()).show(),
+            &uri,
+        );
+    }
+}
+
+#[test]
+fn conformance_fake() {
+    // The fake backend only checks that the file exists
+    let path = std::env::temp_dir().join("delfin-conformance-test.mkv");
+    std::fs::write(&path, b"").unwrap();
+    let uri = glib::filename_to_uri(&path, None).unwrap();
+
+    run_conformance(Harness::new(VideoPlayerBackendFake::simulated(30)), &uri);
+}
@@ -0,0 +1,443 @@
+use std::{
+    cell::{Cell, OnceCell, RefCell},
+    collections::HashMap,
+    path::Path,
+    sync::mpsc,
+};
+
+use relm4::gtk::{self, glib, prelude::*};
+use uuid::Uuid;
+
+use crate::utils::rgba::RGBA;
+
+use super::{
+    AudioDevice, AudioFilters, AudioTrack, PlaybackStats, PlayerState, PlayerStateChangedCallback,
+    SubtitleTrack, VideoAspectRatio, VideoPlayerBackend, VideoPlayerSubtitleFont,
+};
+
+type Callbacks<F> = HashMap<Uuid, Box<F>>;
+
+/// In-memory backend for testing player logic without a display. Nothing is played: tests emit
+/// position, duration, state and end of stream changes themselves, and check what the player was
+/// asked to do.
+pub(crate) struct VideoPlayerBackendFake {
+    // Length of the video when simulating playback, see VideoPlayerBackendFake::simulated()
+    simulated_duration: Option<usize>,
+    // Created the first time it's needed, since widgets can only be created once GTK is running
+    widget: OnceCell<gtk::Widget>,
+    state: Cell<PlayerState>,
+    uri: RefCell<Option<String>>,
+    position: Cell<usize>,
+    duration: Cell<usize>,
+    // Every position seek_to() and seek_by() went to, in order
+    seeks: RefCell<Vec<usize>>,
+    muted: Cell<bool>,
+    volume: Cell<f64>,
+    playback_speed: Cell<f64>,
+    subtitle_track: Cell<Option<usize>>,
+    secondary_subtitle_track: Cell<Option<usize>>,
+    audio_track: Cell<Option<usize>>,
+    subtitle_tracks: RefCell<Vec<SubtitleTrack>>,
+
+    player_state_changed_callbacks: Callbacks<dyn Fn(PlayerState)>,
+    end_of_stream_callbacks: Callbacks<dyn Fn() + Send>,
+    error_callbacks: Callbacks<dyn Fn(String) + Send>,
+    position_updated_callbacks: Callbacks<dyn Fn(usize) + Send + Sync>,
+    duration_updated_callbacks: Callbacks<dyn Fn(usize) + Send + Sync>,
+    mute_updated_callbacks: Callbacks<dyn Fn(bool) + Send + Sync>,
+    volume_updated_callbacks: Callbacks<dyn Fn(f64) + Send + Sync>,
+    subtitle_tracks_updated_callbacks: Callbacks<dyn Fn(Vec<SubtitleTrack>) + Send + Sync>,
+    audio_tracks_updated_callbacks: Callbacks<dyn Fn(Vec<AudioTrack>) + Send + Sync>,
+    audio_devices_updated_callbacks: Callbacks<dyn Fn(Vec<AudioDevice>) + Send + Sync>,
+}
+
+impl std::fmt::Debug for VideoPlayerBackendFake {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VideoPlayerBackendFake")
+            .field("state", &self.state)
+            .field("uri", &self.uri)
+            .field("position", &self.position)
+            .field("duration", &self.duration)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for VideoPlayerBackendFake {
+    fn default() -> Self {
+        Self {
+            simulated_duration: None,
+            widget: OnceCell::new(),
+            state: Cell::new(PlayerState::Loading),
+            uri: RefCell::default(),
+            position: Cell::default(),
+            duration: Cell::default(),
+            seeks: RefCell::default(),
+            muted: Cell::new(false),
+            volume: Cell::new(1.0),
+            playback_speed: Cell::new(1.0),
+            subtitle_track: Cell::default(),
+            secondary_subtitle_track: Cell::default(),
+            audio_track: Cell::default(),
+            subtitle_tracks: RefCell::default(),
+
+            player_state_changed_callbacks: HashMap::default(),
+            end_of_stream_callbacks: HashMap::default(),
+            error_callbacks: HashMap::default(),
+            position_updated_callbacks: HashMap::default(),
+            duration_updated_callbacks: HashMap::default(),
+            mute_updated_callbacks: HashMap::default(),
+            volume_updated_callbacks: HashMap::default(),
+            subtitle_tracks_updated_callbacks: HashMap::default(),
+            audio_tracks_updated_callbacks: HashMap::default(),
+            audio_devices_updated_callbacks: HashMap::default(),
+        }
+    }
+}
+
+/// Collects what backend callbacks send, the way components pass them on as inputs, so tests can
+/// handle them in order once the fake has emitted something.
+pub(crate) struct Inputs<T> {
+    tx: mpsc::Sender<T>,
+    rx: mpsc::Receiver<T>,
+}
+
+impl<T> Default for Inputs<T> {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx }
+    }
+}
+
+impl<T: Send + 'static> Inputs<T> {
+    pub(crate) fn sender(&self) -> impl Fn(T) + Clone + Send + Sync + 'static {
+        let tx = self.tx.clone();
+        move |input| tx.send(input).unwrap()
+    }
+
+    pub(crate) fn take(&self) -> Vec<T> {
+        self.rx.try_iter().collect()
+    }
+}
+
+fn insert<F: ?Sized>(callbacks: &mut Callbacks<F>, callback: Box<F>) -> Uuid {
+    let id = Uuid::new_v4();
+    callbacks.insert(id, callback);
+    id
+}
+
+impl VideoPlayerBackendFake {
+    /// Responds to the player like a real backend would, so it can be checked by the same tests.
+    /// Playing a file that exists starts straight away, a second into a video that's `duration`
+    /// seconds long, and seeking to the last second ends the video.
+    pub(crate) fn simulated(duration: usize) -> Self {
+        Self {
+            simulated_duration: Some(duration),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn emit_player_state(&self, state: PlayerState) {
+        self.state.set(state);
+        for callback in self.player_state_changed_callbacks.values() {
+            callback(state);
+        }
+    }
+
+    pub(crate) fn emit_position(&self, position: usize) {
+        self.position.set(position);
+        for callback in self.position_updated_callbacks.values() {
+            callback(position);
+        }
+    }
+
+    pub(crate) fn emit_duration(&self, duration: usize) {
+        self.duration.set(duration);
+        for callback in self.duration_updated_callbacks.values() {
+            callback(duration);
+        }
+    }
+
+    pub(crate) fn emit_end_of_stream(&self) {
+        for callback in self.end_of_stream_callbacks.values() {
+            callback();
+        }
+    }
+
+    /// Fails playback, like a backend that couldn't load the video.
+    pub(crate) fn emit_error(&self, message: &str) {
+        self.emit_player_state(PlayerState::Error);
+        for callback in self.error_callbacks.values() {
+            callback(message.to_string());
+        }
+    }
+
+    pub(crate) fn seeks(&self) -> Vec<usize> {
+        self.seeks.borrow().clone()
+    }
+
+    fn set_paused(&self, paused: bool) {
+        if let PlayerState::Playing { paused: cur_paused } = self.state.get() {
+            if paused != cur_paused {
+                self.emit_player_state(PlayerState::Playing { paused });
+            }
+        }
+    }
+}
+
+impl VideoPlayerBackend for VideoPlayerBackendFake {
+    // Nothing is rendered, but the player still needs something to show
+    fn widget(&self) -> &gtk::Widget {
+        self.widget.get_or_init(|| gtk::Box::default().upcast())
+    }
+
+    fn connect_player_state_changed(&mut self, callback: PlayerStateChangedCallback) -> Uuid {
+        insert(&mut self.player_state_changed_callbacks, callback)
+    }
+
+    fn disconnect_player_state_changed(&mut self, id: Uuid) {
+        self.player_state_changed_callbacks.remove(&id);
+    }
+
+    fn play_uri(&mut self, uri: &str) {
+        self.uri.replace(Some(uri.to_string()));
+        self.position.set(0);
+        self.subtitle_tracks.borrow_mut().clear();
+        self.emit_player_state(PlayerState::Loading);
+
+        if let Some(duration) = self.simulated_duration {
+            let exists = glib::filename_from_uri(uri).is_ok_and(|(path, _)| path.exists());
+            if !exists {
+                self.emit_error("File not found");
+                return;
+            }
+            self.emit_duration(duration);
+            self.emit_player_state(PlayerState::Playing { paused: false });
+            self.emit_position(1);
+        }
+    }
+
+    fn play(&self) {
+        self.set_paused(false);
+    }
+
+    fn pause(&self) {
+        self.set_paused(true);
+    }
+
+    fn stop(&mut self) {
+        self.uri.replace(None);
+    }
+
+    fn seek_to(&self, seconds: usize) {
+        self.seeks.borrow_mut().push(seconds);
+        self.emit_position(seconds);
+
+        if let Some(duration) = self.simulated_duration {
+            if seconds + 1 >= duration {
+                self.emit_end_of_stream();
+            }
+        }
+    }
+
+    fn seek_by(&self, seconds: isize) {
+        self.seek_to(self.position.get().saturating_add_signed(seconds));
+    }
+
+    fn frame_step_forwards(&self) {
+        self.set_paused(true);
+    }
+
+    fn frame_step_backwards(&self) {
+        self.set_paused(true);
+    }
+
+    fn set_ab_loop_a(&self, _seconds: Option<usize>) {}
+
+    fn set_ab_loop_b(&self, _seconds: Option<usize>) {}
+
+    fn screenshot(&self, _path: &Path, _include_subtitles: bool) -> anyhow::Result<()> {
+        anyhow::bail!("The fake backend doesn't render video");
+    }
+
+    fn muted(&self) -> bool {
+        self.muted.get()
+    }
+
+    fn set_muted(&self, muted: bool) {
+        self.muted.set(muted);
+        for callback in self.mute_updated_callbacks.values() {
+            callback(muted);
+        }
+    }
+
+    fn volume(&self) -> f64 {
+        self.volume.get()
+    }
+
+    fn set_volume(&self, volume: f64) {
+        self.volume.set(volume);
+        for callback in self.volume_updated_callbacks.values() {
+            callback(volume);
+        }
+    }
+
+    fn position(&self) -> usize {
+        self.position.get()
+    }
+
+    fn playback_stats(&self) -> PlaybackStats {
+        PlaybackStats::default()
+    }
+
+    fn playback_speed(&self) -> f64 {
+        self.playback_speed.get()
+    }
+
+    fn set_playback_speed(&self, playback_speed: f64) {
+        self.playback_speed.set(playback_speed);
+    }
+
+    fn set_aspect_ratio(&self, _aspect_ratio: VideoAspectRatio) {}
+
+    fn set_zoom(&self, _zoom: f64) {}
+
+    fn set_panscan(&self, _panscan: f64) {}
+
+    fn current_subtitle_track(&self) -> Option<usize> {
+        self.subtitle_track.get()
+    }
+
+    fn set_subtitle_track(&self, subtitle_track_id: Option<usize>) {
+        self.subtitle_track.set(subtitle_track_id);
+    }
+
+    fn add_subtitle_track(&self, _url: &str, title: &str) {
+        let tracks = {
+            let mut tracks = self.subtitle_tracks.borrow_mut();
+            tracks.push(SubtitleTrack {
+                id: tracks.len() + 1,
+                name: title.to_string(),
+                language: None,
+            });
+            tracks.clone()
+        };
+        for callback in self.subtitle_tracks_updated_callbacks.values() {
+            callback(tracks.clone());
+        }
+    }
+
+    fn set_subtitle_delay(&self, _delay: i64) {}
+
+    fn supports_secondary_subtitles(&self) -> bool {
+        true
+    }
+
+    fn current_secondary_subtitle_track(&self) -> Option<usize> {
+        self.secondary_subtitle_track.get()
+    }
+
+    fn set_secondary_subtitle_track(&self, subtitle_track_id: Option<usize>) {
+        self.secondary_subtitle_track.set(subtitle_track_id);
+    }
+
+    fn current_audio_track(&self) -> Option<usize> {
+        self.audio_track.get()
+    }
+
+    fn set_audio_track(&self, audio_track_id: Option<usize>) {
+        self.audio_track.set(audio_track_id);
+    }
+
+    fn set_audio_delay(&self, _delay: i64) {}
+
+    fn audio_devices(&self) -> Vec<AudioDevice> {
+        vec![]
+    }
+
+    fn set_audio_device(&self, _name: Option<&str>) {}
+
+    fn set_audio_passthrough(&self, _passthrough: bool) {}
+
+    fn set_audio_filters(&self, _audio_filters: AudioFilters) {}
+
+    fn set_subtitle_scale(&self, _subtitle_scale: f64) {}
+
+    fn set_subtitle_colour(&self, _colour: RGBA) {}
+
+    fn set_subtitle_background_colour(&self, _colour: RGBA) {}
+
+    fn set_subtitle_position(&self, _position: u32) {}
+
+    fn set_secondary_subtitle_position(&self, _position: u32) {}
+
+    fn set_subtitle_font(&self, _font: &VideoPlayerSubtitleFont) {}
+
+    fn connect_end_of_stream(&mut self, callback: Box<dyn Fn() + Send + 'static>) -> Uuid {
+        insert(&mut self.end_of_stream_callbacks, callback)
+    }
+
+    fn connect_error(&mut self, callback: Box<dyn Fn(String) + Send + 'static>) -> Uuid {
+        insert(&mut self.error_callbacks, callback)
+    }
+
+    fn connect_position_updated(
+        &mut self,
+        callback: Box<dyn Fn(usize) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.position_updated_callbacks, callback)
+    }
+
+    fn connect_duration_updated(
+        &mut self,
+        callback: Box<dyn Fn(usize) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.duration_updated_callbacks, callback)
+    }
+
+    fn connect_mute_updated(
+        &mut self,
+        callback: Box<dyn Fn(bool) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.mute_updated_callbacks, callback)
+    }
+
+    fn connect_volume_updated(
+        &mut self,
+        callback: Box<dyn Fn(f64) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.volume_updated_callbacks, callback)
+    }
+
+    fn connect_subtitle_tracks_updated(
+        &mut self,
+        callback: Box<dyn Fn(Vec<SubtitleTrack>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.subtitle_tracks_updated_callbacks, callback)
+    }
+
+    fn connect_audio_tracks_updated(
+        &mut self,
+        callback: Box<dyn Fn(Vec<AudioTrack>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.audio_tracks_updated_callbacks, callback)
+    }
+
+    fn connect_audio_devices_updated(
+        &mut self,
+        callback: Box<dyn Fn(Vec<AudioDevice>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        insert(&mut self.audio_devices_updated_callbacks, callback)
+    }
+
+    fn disconnect_signal_handler(&mut self, id: &Uuid) {
+        self.end_of_stream_callbacks.remove(id);
+        self.error_callbacks.remove(id);
+        self.position_updated_callbacks.remove(id);
+        self.duration_updated_callbacks.remove(id);
+        self.mute_updated_callbacks.remove(id);
+        self.volume_updated_callbacks.remove(id);
+        self.subtitle_tracks_updated_callbacks.remove(id);
+        self.audio_tracks_updated_callbacks.remove(id);
+        self.audio_devices_updated_callbacks.remove(id);
+    }
+}
@@ -1,50 +1,200 @@
-use gst::{glib::SignalHandlerId, prelude::Cast};
-use gstplay::{traits::PlayStreamInfoExt, PlayAudioInfo, PlaySubtitleInfo};
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    path::Path,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use anyhow::Context;
+use gst::{
+    glib::SignalHandlerId,
+    prelude::{Cast, DeviceExt, DeviceMonitorExt, DeviceMonitorExtManual, GstBinExt, ObjectExt},
+};
+use gstplay::{traits::PlayStreamInfoExt, PlayAudioInfo, PlayState, PlaySubtitleInfo};
+use relm4::gtk::pango;
+use tracing::{debug, warn};
 use uuid::Uuid;
 use video_player_gst::{gst, gstplay, GstVideoPlayer};
 
-use crate::tr;
+use crate::{tr, utils::rgba::RGBA};
 
-use super::{AudioTrack, PlayerStateChangedCallback, SubtitleTrack, VideoPlayerBackend};
+use super::{
+    AudioDevice, AudioFilters, AudioTrack, PlaybackStats, PlayerState, PlayerStateChangedCallback,
+    SubtitleTrack, VideoAspectRatio, VideoPlayerBackend, VideoPlayerSubtitleFont,
+};
+
+// Start and end of the A-B loop, in seconds
+type AbLoopPoints = (Option<usize>, Option<usize>);
+
+// Tracks that were last passed on to a callback, and the video they belong to
+type LastTracks<T> = Mutex<Option<(usize, Vec<T>)>>;
+
+// playbin only picks up its audio filter when the pipeline is built, so every filter is always in
+// place, and turned off by setting it to leave the audio unchanged
+const AUDIO_FILTERS: &str = "audioconvert \
+    ! audiodynamic name=normalize_loudness mode=compressor characteristics=soft-knee threshold=0.3 ratio=1.0 \
+    ! audiodynamic name=night_mode_compressor mode=compressor characteristics=soft-knee threshold=0.1 ratio=1.0 \
+    ! audioamplify name=night_mode_boost amplification=1.0 clipping-method=clip \
+    ! audioconvert";
 
-#[derive(Debug)]
 pub struct VideoPlayerBackendGst {
     player: GstVideoPlayer,
-    signal_handler_ids: Vec<SignalHandlerId>,
+    state: Rc<Cell<PlayerState>>,
+    player_state_changed_callbacks: Rc<RefCell<HashMap<Uuid, PlayerStateChangedCallback>>>,
+    signal_handler_ids: HashMap<Uuid, SignalHandlerId>,
+    // Signals are blocked while stopped, and GLib complains if they're unblocked twice
+    signals_blocked: Cell<bool>,
+    // Audio devices are watched through the device monitor's bus instead of the player
+    device_monitor: gst::DeviceMonitor,
+    device_signal_handler_ids: HashMap<Uuid, SignalHandlerId>,
+    audio_filters: Option<gst::Bin>,
+    ab_loop: Arc<Mutex<AbLoopPoints>>,
+    // Counts how many videos have been played, so track callbacks know when a new one starts
+    media_count: Arc<AtomicUsize>,
+}
+
+impl std::fmt::Debug for VideoPlayerBackendGst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VideoPlayerBackendGst")
+            .field("player", &self.player)
+            .field("state", &self.state)
+            .field("signal_handler_ids", &self.signal_handler_ids)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Default for VideoPlayerBackendGst {
     fn default() -> Self {
         let player = GstVideoPlayer::new();
-        Self {
+        let state = Rc::new(Cell::new(PlayerState::Loading));
+        let player_state_changed_callbacks = Rc::default();
+
+        let device_monitor = gst::DeviceMonitor::new();
+        device_monitor.add_filter(Some("Audio/Sink"), None);
+        if let Err(err) = device_monitor.start() {
+            warn!("Error watching audio devices: {err}");
+        }
+        device_monitor.bus().add_signal_watch();
+
+        let audio_filters = match gst::parse::bin_from_description(AUDIO_FILTERS, true) {
+            Ok(audio_filters) => {
+                player.set_audio_filter(Some(audio_filters.upcast_ref()));
+                Some(audio_filters)
+            }
+            Err(err) => {
+                warn!("Error creating audio filters: {err}");
+                None
+            }
+        };
+
+        let gst = Self {
             player,
-            signal_handler_ids: vec![],
+            state,
+            player_state_changed_callbacks,
+            signal_handler_ids: HashMap::default(),
+            signals_blocked: Cell::new(false),
+            device_monitor,
+            device_signal_handler_ids: HashMap::default(),
+            audio_filters,
+            ab_loop: Arc::default(),
+            media_count: Arc::default(),
+        };
+
+        gst.player.connect_state_changed({
+            let state = gst.state.clone();
+            let player_state_changed_callbacks = gst.player_state_changed_callbacks.clone();
+            move |play_state| {
+                if let Some(new_state) = next_player_state(state.get(), play_state) {
+                    set_player_state!(state, player_state_changed_callbacks, new_state);
+                }
+            }
+        });
+
+        gst.player.connect_error({
+            let state = gst.state.clone();
+            let player_state_changed_callbacks = gst.player_state_changed_callbacks.clone();
+            move |_, _| {
+                set_player_state!(state, player_state_changed_callbacks, PlayerState::Error);
+            }
+        });
+
+        // GStreamer doesn't support A-B loops, so seek back to the start once playback passes the
+        // end
+        gst.player.connect_position_updated({
+            let ab_loop = gst.ab_loop.clone();
+            let play = gst.player.player().get().unwrap().clone();
+            move |position| {
+                if let (start, Some(end)) = *ab_loop.lock().unwrap() {
+                    if position.seconds() as usize >= end {
+                        play.seek(gst::ClockTime::from_seconds(start.unwrap_or(0) as u64));
+                    }
+                }
+            }
+        });
+
+        gst
+    }
+}
+
+impl VideoPlayerBackendGst {
+    fn set_player_state(&mut self, state: PlayerState) {
+        set_player_state!(self.state, self.player_state_changed_callbacks, state);
+    }
+
+    fn unblock_signals(&self) {
+        if !self.signals_blocked.replace(false) {
+            return;
+        }
+        for signal_handler in self.signal_handler_ids.values() {
+            self.player.signal_adapter_unblock_signal(signal_handler);
         }
     }
 }
 
+// Maps GStreamer's player state to ours, or None if it doesn't change
+fn next_player_state(state: PlayerState, play_state: PlayState) -> Option<PlayerState> {
+    match (state, play_state) {
+        (_, PlayState::Playing) => Some(PlayerState::Playing { paused: false }),
+        // The player pauses and buffers before it starts playing, and stops after an error
+        (PlayerState::Loading | PlayerState::Error, _) => None,
+        (_, PlayState::Paused) => Some(PlayerState::Playing { paused: true }),
+        (PlayerState::Playing { .. }, PlayState::Buffering) => Some(PlayerState::Buffering),
+        _ => None,
+    }
+}
+
 impl VideoPlayerBackend for VideoPlayerBackendGst {
     fn widget(&self) -> &libadwaita::gtk::Widget {
         self.player.upcast_ref()
     }
 
-    fn connect_player_state_changed(&mut self, _callback: PlayerStateChangedCallback) -> Uuid {
-        todo!();
+    fn connect_player_state_changed(&mut self, callback: PlayerStateChangedCallback) -> Uuid {
+        let id = Uuid::new_v4();
+        self.player_state_changed_callbacks
+            .borrow_mut()
+            .insert(id, callback);
+        id
     }
 
-    fn disconnect_player_state_changed(&mut self, _id: Uuid) {
-        todo!();
+    fn disconnect_player_state_changed(&mut self, id: Uuid) {
+        self.player_state_changed_callbacks.borrow_mut().remove(&id);
     }
 
     fn play_uri(&mut self, uri: &str) {
+        // Subtitles loaded for the previous video don't belong to this one
+        self.player.set_subtitle_uri(None);
+        self.unblock_signals();
+        self.set_player_state(PlayerState::Loading);
+        self.media_count.fetch_add(1, Ordering::Relaxed);
         self.player.play_uri(uri);
     }
 
     fn play(&self) {
-        for signal_handler in &self.signal_handler_ids {
-            self.player.signal_adapter_unblock_signal(signal_handler);
-        }
-
+        self.unblock_signals();
         self.player.play();
     }
 
@@ -55,7 +205,10 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
     fn stop(&mut self) {
         self.player.stop();
 
-        for signal_handler in &self.signal_handler_ids {
+        if self.signals_blocked.replace(true) {
+            return;
+        }
+        for signal_handler in self.signal_handler_ids.values() {
             self.player.signal_adapter_block_signal(signal_handler);
         }
     }
@@ -76,8 +229,38 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
         }
     }
 
+    fn frame_step_forwards(&self) {
+        self.player.frame_step_forwards();
+    }
+
+    fn frame_step_backwards(&self) {
+        self.player.frame_step_backwards();
+    }
+
+    fn set_ab_loop_a(&self, seconds: Option<usize>) {
+        self.ab_loop.lock().unwrap().0 = seconds;
+    }
+
+    fn set_ab_loop_b(&self, seconds: Option<usize>) {
+        self.ab_loop.lock().unwrap().1 = seconds;
+    }
+
+    fn screenshot(&self, path: &Path, _include_subtitles: bool) -> anyhow::Result<()> {
+        // Subtitles are rendered separately from the video, so they're never included
+        let sample = self
+            .player
+            .png_snapshot()
+            .context("Error getting video snapshot")?;
+        let buffer = sample.buffer().context("Video snapshot is empty")?;
+        let map = buffer
+            .map_readable()
+            .context("Error reading video snapshot")?;
+        std::fs::write(path, map.as_slice())?;
+        Ok(())
+    }
+
     fn muted(&self) -> bool {
-        todo!();
+        self.player.is_muted()
     }
 
     fn set_muted(&self, muted: bool) {
@@ -85,7 +268,7 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
     }
 
     fn volume(&self) -> f64 {
-        todo!();
+        self.player.volume()
     }
 
     fn set_volume(&self, volume: f64) {
@@ -99,8 +282,60 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
             .unwrap_or(0)
     }
 
-    fn disconnect_signal_handler(&mut self, _id: &Uuid) {
-        // TODO
+    fn playback_stats(&self) -> PlaybackStats {
+        let video = self.player.current_video_track();
+        let audio = self.player.current_audio_track();
+        // Unknown bitrates are reported as 0
+        let bitrate = |bitrate: i32| (bitrate > 0).then_some(bitrate as u64);
+
+        PlaybackStats {
+            container: self
+                .player
+                .media_info()
+                .and_then(|media_info| media_info.container_format())
+                .map(|container| container.to_string()),
+            video_codec: video
+                .as_ref()
+                .and_then(|video| video.codec())
+                .map(|codec| codec.to_string()),
+            audio_codec: audio
+                .as_ref()
+                .and_then(|audio| audio.codec())
+                .map(|codec| codec.to_string()),
+            resolution: video
+                .as_ref()
+                .filter(|video| video.width() > 0 && video.height() > 0)
+                .map(|video| (video.width() as u32, video.height() as u32)),
+            fps: video
+                .as_ref()
+                .map(|video| video.framerate())
+                .filter(|framerate| framerate.numer() > 0 && framerate.denom() > 0)
+                .map(|framerate| framerate.numer() as f64 / framerate.denom() as f64),
+            video_bitrate: video.as_ref().and_then(|video| bitrate(video.bitrate())),
+            audio_bitrate: audio.as_ref().and_then(|audio| bitrate(audio.bitrate())),
+            // GStreamer's player doesn't expose decoder or buffer details
+            ..Default::default()
+        }
+    }
+
+    fn playback_speed(&self) -> f64 {
+        self.player.rate()
+    }
+
+    fn set_playback_speed(&self, playback_speed: f64) {
+        self.player.set_rate(playback_speed);
+    }
+
+    fn set_aspect_ratio(&self, aspect_ratio: VideoAspectRatio) {
+        self.player.set_aspect_ratio(aspect_ratio.ratio());
+    }
+
+    fn set_zoom(&self, zoom: f64) {
+        self.player.set_zoom(zoom);
+    }
+
+    fn set_panscan(&self, panscan: f64) {
+        self.player.set_panscan(panscan);
     }
 
     fn current_subtitle_track(&self) -> Option<usize> {
@@ -115,7 +350,7 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
                 self.player
                     .set_subtitle_track(id as i32)
                     .expect("Failed to set subtitle track on Gst player.");
-                self.player.set_audio_track_enabled(true);
+                self.player.set_subtitle_track_enabled(true);
             }
             None => {
                 self.player.set_subtitle_track_enabled(false);
@@ -123,6 +358,28 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
         };
     }
 
+    fn add_subtitle_track(&self, url: &str, _title: &str) {
+        // Only one external subtitle file can be loaded at a time, and its title comes from the
+        // file itself
+        self.player.set_subtitle_uri(Some(url));
+    }
+
+    fn set_subtitle_delay(&self, delay: i64) {
+        // Offsets are in nanoseconds
+        self.player.set_subtitle_offset(delay * 1_000_000);
+    }
+
+    // GStreamer's player only renders one subtitle track at a time
+    fn supports_secondary_subtitles(&self) -> bool {
+        false
+    }
+
+    fn current_secondary_subtitle_track(&self) -> Option<usize> {
+        None
+    }
+
+    fn set_secondary_subtitle_track(&self, _subtitle_track_id: Option<usize>) {}
+
     fn current_audio_track(&self) -> Option<usize> {
         self.player
             .current_audio_track()
@@ -143,51 +400,281 @@ impl VideoPlayerBackend for VideoPlayerBackendGst {
         };
     }
 
-    fn connect_end_of_stream(&mut self, callback: Box<dyn Fn() + Send + 'static>) {
-        self.signal_handler_ids
-            .push(self.player.connect_end_of_stream(callback));
+    fn set_audio_delay(&self, delay: i64) {
+        // Offsets are in nanoseconds
+        self.player.set_audio_offset(delay * 1_000_000);
+    }
+
+    fn audio_devices(&self) -> Vec<AudioDevice> {
+        get_audio_devices(&self.device_monitor)
+    }
+
+    fn set_audio_device(&self, name: Option<&str>) {
+        let sink = name.and_then(|name| {
+            let device = self
+                .device_monitor
+                .devices()
+                .into_iter()
+                .find(|device| device.display_name().as_str() == name)?;
+            device
+                .create_element(None)
+                .inspect_err(|err| warn!("Error using audio device {name}: {err}"))
+                .ok()
+        });
+        self.player.set_audio_sink(sink.as_ref());
+    }
+
+    // GStreamer's player always decodes audio
+    fn set_audio_passthrough(&self, _passthrough: bool) {}
+
+    fn set_audio_filters(&self, audio_filters: AudioFilters) {
+        let Some(filters) = &self.audio_filters else {
+            return;
+        };
+        let set_filter_property =
+            |name: &str, property: &str, value: f32| match filters.by_name(name) {
+                Some(filter) => filter.set_property(property, value),
+                None => warn!("Audio filter {name} is missing"),
+            };
+
+        // Gentle compression to even out the overall loudness
+        set_filter_property(
+            "normalize_loudness",
+            "ratio",
+            if audio_filters.normalize_loudness {
+                0.5
+            } else {
+                1.0
+            },
+        );
+
+        // Heavy compression, then boost everything so quiet dialogue is louder
+        let (ratio, amplification) = if audio_filters.night_mode {
+            (0.25, 2.0)
+        } else {
+            (1.0, 1.0)
+        };
+        set_filter_property("night_mode_compressor", "ratio", ratio);
+        set_filter_property("night_mode_boost", "amplification", amplification);
+    }
+
+    fn set_subtitle_scale(&self, subtitle_scale: f64) {
+        self.player
+            .update_subtitle_style(|style| style.scale = subtitle_scale);
+    }
+
+    fn set_subtitle_colour(&self, colour: RGBA) {
+        self.player
+            .update_subtitle_style(|style| style.colour = colour.to_argb());
+    }
+
+    fn set_subtitle_background_colour(&self, colour: RGBA) {
+        self.player
+            .update_subtitle_style(|style| style.background_colour = colour.to_argb());
+    }
+
+    fn set_subtitle_position(&self, position: u32) {
+        assert!((0..=150).contains(&position));
+        self.player
+            .update_subtitle_style(|style| style.position = position);
+    }
+
+    // Secondary subtitles aren't supported, see supports_secondary_subtitles()
+    fn set_secondary_subtitle_position(&self, _position: u32) {}
+
+    fn set_subtitle_font(&self, font: &VideoPlayerSubtitleFont) {
+        let font = pango::FontDescription::from(font.clone()).to_str();
+        self.player
+            .update_subtitle_style(|style| style.font = font.to_string());
+    }
+
+    fn connect_end_of_stream(&mut self, callback: Box<dyn Fn() + Send + 'static>) -> Uuid {
+        let ab_loop = self.ab_loop.clone();
+        let play = self.player.player().get().unwrap().clone();
+        let id = Uuid::new_v4();
+        self.signal_handler_ids.insert(
+            id,
+            self.player.connect_end_of_stream(move || {
+                // Keep looping from the start of the A-B loop when it has no end
+                if let (Some(start), None) = *ab_loop.lock().unwrap() {
+                    play.seek(gst::ClockTime::from_seconds(start as u64));
+                    play.play();
+                    return;
+                }
+                callback();
+            }),
+        );
+        id
+    }
+
+    fn connect_error(&mut self, callback: Box<dyn Fn(String) + Send + 'static>) -> Uuid {
+        let id = Uuid::new_v4();
+        self.signal_handler_ids.insert(
+            id,
+            self.player.connect_error(move |error, _| {
+                callback(error.message().to_string());
+            }),
+        );
+        id
     }
 
     fn connect_position_updated(
         &mut self,
         callback: Box<dyn Fn(usize) + Send + Sync + 'static>,
     ) -> Uuid {
-        self.signal_handler_ids
-            .push(self.player.connect_position_updated(move |position| {
+        let id = Uuid::new_v4();
+        self.signal_handler_ids.insert(
+            id,
+            self.player.connect_position_updated(move |position| {
                 callback(position.seconds() as usize);
-            }));
-        // TODO
-        Uuid::new_v4()
+            }),
+        );
+        id
     }
 
-    fn connect_duration_updated(&mut self, callback: Box<dyn Fn(usize) + Send + Sync + 'static>) {
-        self.signal_handler_ids
-            .push(self.player.connect_duration_changed(move |duration| {
+    fn connect_duration_updated(
+        &mut self,
+        callback: Box<dyn Fn(usize) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        self.signal_handler_ids.insert(
+            id,
+            self.player.connect_duration_changed(move |duration| {
                 callback(duration.seconds() as usize);
-            }));
+            }),
+        );
+        id
     }
 
-    fn connect_mute_updated(&mut self, callback: Box<dyn Fn(bool) + Send + Sync + 'static>) {
-        self.player.connect_mute_changed(callback);
+    fn connect_mute_updated(
+        &mut self,
+        callback: Box<dyn Fn(bool) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        self.signal_handler_ids
+            .insert(id, self.player.connect_mute_changed(callback));
+        id
     }
 
-    fn connect_volume_updated(&mut self, callback: Box<dyn Fn(f64) + Send + Sync + 'static>) {
-        self.player.connect_volume_changed(callback);
+    fn connect_volume_updated(
+        &mut self,
+        callback: Box<dyn Fn(f64) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        self.signal_handler_ids
+            .insert(id, self.player.connect_volume_changed(callback));
+        id
     }
 
     fn connect_subtitle_tracks_updated(
         &mut self,
-        _callback: Box<dyn Fn(Vec<SubtitleTrack>) + Send + Sync + 'static>,
-    ) {
-        todo!();
+        callback: Box<dyn Fn(Vec<SubtitleTrack>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        let media_count = self.media_count.clone();
+        let last_tracks = LastTracks::default();
+        self.signal_handler_ids.insert(
+            id,
+            self.player.connect_media_info_updated(move |media_info| {
+                let tracks: Vec<SubtitleTrack> = media_info
+                    .subtitle_streams()
+                    .into_iter()
+                    .map(SubtitleTrack::from)
+                    .collect();
+                if let Some(tracks) = changed_tracks(&last_tracks, &media_count, tracks) {
+                    callback(tracks);
+                }
+            }),
+        );
+        id
     }
 
     fn connect_audio_tracks_updated(
         &mut self,
-        _callback: Box<dyn Fn(Vec<AudioTrack>) + Send + Sync + 'static>,
-    ) {
-        todo!();
+        callback: Box<dyn Fn(Vec<AudioTrack>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let id = Uuid::new_v4();
+        let media_count = self.media_count.clone();
+        let last_tracks = LastTracks::default();
+        self.signal_handler_ids.insert(
+            id,
+            self.player.connect_media_info_updated(move |media_info| {
+                let tracks: Vec<AudioTrack> = media_info
+                    .audio_streams()
+                    .into_iter()
+                    .map(AudioTrack::from)
+                    .collect();
+                if let Some(tracks) = changed_tracks(&last_tracks, &media_count, tracks) {
+                    callback(tracks);
+                }
+            }),
+        );
+        id
+    }
+
+    fn connect_audio_devices_updated(
+        &mut self,
+        callback: Box<dyn Fn(Vec<AudioDevice>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let device_monitor = self.device_monitor.clone();
+        let id = Uuid::new_v4();
+        self.device_signal_handler_ids.insert(
+            id,
+            self.device_monitor
+                .bus()
+                .connect_message(None, move |_, message| {
+                    if matches!(
+                        message.view(),
+                        gst::MessageView::DeviceAdded(_) | gst::MessageView::DeviceRemoved(_)
+                    ) {
+                        callback(get_audio_devices(&device_monitor));
+                    }
+                }),
+        );
+        id
     }
+
+    fn disconnect_signal_handler(&mut self, id: &Uuid) {
+        if let Some(signal_handler_id) = self.signal_handler_ids.remove(id) {
+            self.player.disconnect(signal_handler_id);
+        } else if let Some(signal_handler_id) = self.device_signal_handler_ids.remove(id) {
+            self.device_monitor.bus().disconnect(signal_handler_id);
+        } else {
+            debug!("Signal handler not found when trying to disconnect: {id}");
+        }
+    }
+}
+
+// media-info-updated fires many times while playing, so only pass on tracks when they're different
+// from the last ones, or belong to a new video
+fn changed_tracks<T: Clone + PartialEq>(
+    last_tracks: &LastTracks<T>,
+    media_count: &AtomicUsize,
+    tracks: Vec<T>,
+) -> Option<Vec<T>> {
+    let media = media_count.load(Ordering::Relaxed);
+    let mut last_tracks = last_tracks.lock().unwrap();
+    let unchanged = last_tracks
+        .as_ref()
+        .is_some_and(|(last_media, last)| *last_media == media && *last == tracks);
+    if unchanged {
+        return None;
+    }
+    *last_tracks = Some((media, tracks.clone()));
+    Some(tracks)
+}
+
+fn get_audio_devices(device_monitor: &gst::DeviceMonitor) -> Vec<AudioDevice> {
+    device_monitor
+        .devices()
+        .into_iter()
+        .map(|device| AudioDevice {
+            // Device names aren't stable between runs, so devices are identified by what they're
+            // called instead
+            name: device.display_name().to_string(),
+            description: device.display_name().to_string(),
+        })
+        .collect()
 }
 
 impl From<PlaySubtitleInfo> for SubtitleTrack {
@@ -195,6 +682,7 @@ impl From<PlaySubtitleInfo> for SubtitleTrack {
         Self {
             name: value.display_name(),
             id: value.index() as usize,
+            language: value.language().map(|l| l.to_string()),
         }
     }
 }
@@ -231,6 +719,7 @@ impl From<PlayAudioInfo> for AudioTrack {
         Self {
             id: value.index() as usize,
             name: value.display_name(),
+            language: value.language().map(|l| l.to_string()),
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::fmt;
+use std::{fmt, path::Path};
 
 use relm4::gtk;
 use serde::{Deserialize, Serialize};
@@ -6,21 +6,109 @@ use uuid::Uuid;
 
 use crate::utils::rgba::RGBA;
 
+// Shared by the backends to update their state and notify callbacks
+macro_rules! set_player_state {
+    ($state:expr, $player_state_changed_callbacks:expr, $new_state:expr$(,)?) => {
+        $state.replace($new_state);
+        for callback in $player_state_changed_callbacks.borrow().values() {
+            callback($new_state);
+        }
+    };
+}
+
+#[cfg(test)]
+mod conformance;
+#[cfg(test)]
+pub(crate) mod fake;
 #[cfg(feature = "gst")]
 pub mod gst;
 
 pub mod mpv;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct SubtitleTrack {
     pub id: usize,
     pub name: String,
+    pub language: Option<String>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub struct AudioTrack {
     pub id: usize,
     pub name: String,
+    pub language: Option<String>,
+}
+
+/// An output device that audio can be played through.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioDevice {
+    /// Identifies the device to the backend.
+    pub name: String,
+    /// Human readable name of the device.
+    pub description: String,
+}
+
+/// Filters applied to the audio before it's played.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AudioFilters {
+    /// Compress the dynamic range, so loud scenes are quieter and dialogue is louder.
+    pub night_mode: bool,
+    /// Normalize the overall loudness, so different videos play at a similar volume.
+    pub normalize_loudness: bool,
+}
+
+/// Technical details about what's currently playing, for troubleshooting playback. Anything the
+/// backend can't tell is None.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaybackStats {
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// Width and height of the video, in pixels.
+    pub resolution: Option<(u32, u32)>,
+    pub fps: Option<f64>,
+    /// Hardware decoding API in use, or None when decoding in software.
+    pub hardware_decoding: Option<String>,
+    /// In bits per second.
+    pub video_bitrate: Option<u64>,
+    /// In bits per second.
+    pub audio_bitrate: Option<u64>,
+    /// How many seconds are buffered ahead of the playback position.
+    pub cache_duration: Option<f64>,
+    pub dropped_frames: Option<u64>,
+}
+
+/// Aspect ratio to display the video with.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum VideoAspectRatio {
+    /// Use the video's own aspect ratio.
+    #[default]
+    Auto,
+    /// 4:3
+    Standard,
+    /// 16:9
+    Widescreen,
+    /// 2.35:1
+    Cinemascope,
+}
+
+impl VideoAspectRatio {
+    pub const ALL: [VideoAspectRatio; 4] = [
+        VideoAspectRatio::Auto,
+        VideoAspectRatio::Standard,
+        VideoAspectRatio::Widescreen,
+        VideoAspectRatio::Cinemascope,
+    ];
+
+    /// Width divided by height, or None to use the video's own aspect ratio.
+    pub fn ratio(&self) -> Option<f64> {
+        match self {
+            VideoAspectRatio::Auto => None,
+            VideoAspectRatio::Standard => Some(4.0 / 3.0),
+            VideoAspectRatio::Widescreen => Some(16.0 / 9.0),
+            VideoAspectRatio::Cinemascope => Some(2.35),
+        }
+    }
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -28,6 +116,7 @@ pub enum PlayerState {
     Loading,
     Playing { paused: bool },
     Buffering,
+    Error,
 }
 
 type PlayerStateChangedCallback = Box<dyn Fn(PlayerState)>;
@@ -66,6 +155,16 @@ pub trait VideoPlayerBackend: fmt::Debug {
     /// Step backwards by one frame.
     fn frame_step_backwards(&self);
 
+    /// Set the point playback loops back to once it reaches the end of the A-B loop, or clear it if
+    /// None.
+    fn set_ab_loop_a(&self, seconds: Option<usize>);
+
+    /// Set the point where playback loops back to the start of the A-B loop, or clear it if None.
+    fn set_ab_loop_b(&self, seconds: Option<usize>);
+
+    /// Save the current frame to a PNG file, optionally including the subtitles shown on it.
+    fn screenshot(&self, path: &Path, include_subtitles: bool) -> anyhow::Result<()>;
+
     /// Check if the player is currently muted.
     fn muted(&self) -> bool;
 
@@ -81,12 +180,24 @@ pub trait VideoPlayerBackend: fmt::Debug {
     /// Get current video position in seconds.
     fn position(&self) -> usize;
 
+    /// Get technical details about what's currently playing.
+    fn playback_stats(&self) -> PlaybackStats;
+
     /// Get the current playback speed.
     fn playback_speed(&self) -> f64;
 
     /// Set the playback speed.
     fn set_playback_speed(&self, playback_speed: f64);
 
+    /// Override the video's aspect ratio.
+    fn set_aspect_ratio(&self, aspect_ratio: VideoAspectRatio);
+
+    /// Set video zoom, where 0 is the original size, 1 is twice the size, and -1 is half the size.
+    fn set_zoom(&self, zoom: f64);
+
+    /// Crop the video to fill the player, from 0 (no cropping) to 1 (fills the player).
+    fn set_panscan(&self, panscan: f64);
+
     /// Get the current subtitle track ID.
     fn current_subtitle_track(&self) -> Option<usize>;
 
@@ -96,12 +207,40 @@ pub trait VideoPlayerBackend: fmt::Debug {
     /// Add a subtitle track.
     fn add_subtitle_track(&self, url: &str, title: &str);
 
+    /// Delay subtitles relative to the video, in milliseconds.
+    fn set_subtitle_delay(&self, delay: i64);
+
+    /// Check if a secondary subtitle track can be shown alongside the current subtitle track.
+    fn supports_secondary_subtitles(&self) -> bool;
+
+    /// Get the current secondary subtitle track ID.
+    fn current_secondary_subtitle_track(&self) -> Option<usize>;
+
+    /// Set the secondary subtitle track, shown at the same time as the current subtitle track.
+    fn set_secondary_subtitle_track(&self, subtitle_track_id: Option<usize>);
+
     /// Get the current audio track ID.
     fn current_audio_track(&self) -> Option<usize>;
 
     /// Set the current audio track.
     fn set_audio_track(&self, audio_track_id: Option<usize>);
 
+    /// Delay audio relative to the video, in milliseconds.
+    fn set_audio_delay(&self, delay: i64);
+
+    /// Get the available audio output devices.
+    fn audio_devices(&self) -> Vec<AudioDevice>;
+
+    /// Set the audio output device by name, or None to pick one automatically.
+    fn set_audio_device(&self, name: Option<&str>);
+
+    /// Pass compressed surround audio (AC3, E-AC3, DTS, TrueHD) through to the audio device
+    /// over S/PDIF or HDMI, instead of decoding it.
+    fn set_audio_passthrough(&self, passthrough: bool);
+
+    /// Set the filters applied to the audio.
+    fn set_audio_filters(&self, audio_filters: AudioFilters);
+
     /// Set Subtitle scale.
     fn set_subtitle_scale(&self, subtitle_scale: f64);
 
@@ -114,12 +253,19 @@ pub trait VideoPlayerBackend: fmt::Debug {
     /// Set subtitle position from 0 - 150, where 0 is the top of the screen and 100 is the bottom.
     fn set_subtitle_position(&self, position: u32);
 
+    /// Set secondary subtitle position from 0 - 150, where 0 is the top of the screen and 100 is
+    /// the bottom.
+    fn set_secondary_subtitle_position(&self, position: u32);
+
     /// Set subtitle font.
     fn set_subtitle_font(&self, font: &VideoPlayerSubtitleFont);
 
     /// Get notified when video player reaches the end of the current video.
     fn connect_end_of_stream(&mut self, callback: Box<dyn Fn() + Send + 'static>) -> Uuid;
 
+    /// Get notified when the current video fails to load or play.
+    fn connect_error(&mut self, callback: Box<dyn Fn(String) + Send + 'static>) -> Uuid;
+
     /// Get notified when the playback position changes.
     fn connect_position_updated(
         &mut self,
@@ -154,6 +300,12 @@ pub trait VideoPlayerBackend: fmt::Debug {
         callback: Box<dyn Fn(Vec<AudioTrack>) + Send + Sync + 'static>,
     ) -> Uuid;
 
+    /// Get notified when the list of available audio output devices changes.
+    fn connect_audio_devices_updated(
+        &mut self,
+        callback: Box<dyn Fn(Vec<AudioDevice>) + Send + Sync + 'static>,
+    ) -> Uuid;
+
     // Disconnects the signal handler for the given ID.
     fn disconnect_signal_handler(&mut self, id: &Uuid);
 }
@@ -1,9 +1,12 @@
 use std::{
     cell::{Cell, RefCell},
     collections::HashMap,
+    path::Path,
     rc::Rc,
+    str::FromStr,
 };
 
+use anyhow::{anyhow, bail};
 use glib::SignalHandlerId;
 use relm4::gtk::{self, glib, prelude::*};
 use tracing::debug;
@@ -13,28 +16,28 @@ use video_player_mpv::{Track, TrackType, VideoPlayerMpv};
 use crate::{tr, utils::rgba::RGBA};
 
 use super::{
-    AudioTrack, PlayerState, PlayerStateChangedCallback, SubtitleTrack, VideoPlayerBackend,
-    VideoPlayerSubtitleFont,
+    AudioDevice, AudioFilters, AudioTrack, PlaybackStats, PlayerState, PlayerStateChangedCallback,
+    SubtitleTrack, VideoAspectRatio, VideoPlayerBackend, VideoPlayerSubtitleFont,
 };
 
+// Codecs passed through to the audio device when passthrough is enabled
+const AUDIO_PASSTHROUGH_CODECS: &str = "ac3,eac3,dts,truehd";
+
+// FFmpeg filters used for the audio filters
+const LOUDNORM_FILTER: &str = "lavfi=[loudnorm=I=-16:TP=-1.5:LRA=11]";
+const DYNAUDNORM_FILTER: &str = "lavfi=[dynaudnorm=f=250:g=31:p=0.9:m=10]";
+
 fn uuid() -> Uuid {
     Uuid::new_v4()
 }
 
-macro_rules! set_player_state {
-    ($state:expr, $player_state_changed_callbacks:expr, $new_state:expr$(,)?) => {
-        $state.replace($new_state);
-        for callback in $player_state_changed_callbacks.borrow().values() {
-            callback($new_state);
-        }
-    };
-}
-
 pub struct VideoPlayerBackendMpv {
     widget: VideoPlayerMpv,
     state: Rc<Cell<PlayerState>>,
     player_state_changed_callbacks: Rc<RefCell<HashMap<Uuid, PlayerStateChangedCallback>>>,
     signal_handler_ids: HashMap<Uuid, SignalHandlerId>,
+    // mpv restarts audio whenever the filters are set, so we only set them when they change
+    audio_filters: Cell<Option<AudioFilters>>,
 }
 
 impl std::fmt::Debug for VideoPlayerBackendMpv {
@@ -57,6 +60,7 @@ impl Default for VideoPlayerBackendMpv {
             state,
             player_state_changed_callbacks,
             signal_handler_ids: HashMap::default(),
+            audio_filters: Cell::default(),
         };
 
         mpv.widget.connect_core_idle({
@@ -105,6 +109,14 @@ impl Default for VideoPlayerBackendMpv {
             }
         });
 
+        mpv.widget.connect_error({
+            let state = mpv.state.clone();
+            let player_state_changed_callbacks = mpv.player_state_changed_callbacks.clone();
+            move |_, _| {
+                set_player_state!(state, player_state_changed_callbacks, PlayerState::Error);
+            }
+        });
+
         mpv
     }
 }
@@ -113,6 +125,10 @@ impl VideoPlayerBackendMpv {
     fn set_player_state(&mut self, state: PlayerState) {
         set_player_state!(self.state, self.player_state_changed_callbacks, state);
     }
+
+    fn property<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.widget.property_string(name)?.parse().ok()
+    }
 }
 
 impl VideoPlayerBackend for VideoPlayerBackendMpv {
@@ -133,9 +149,10 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
     }
 
     fn play_uri(&mut self, uri: &str) {
+        // Loading errors are emitted while starting playback, so set the state first
+        self.set_player_state(PlayerState::Loading);
         self.widget.play_uri(uri);
         self.widget.play();
-        self.set_player_state(PlayerState::Loading);
     }
 
     fn play(&self) {
@@ -166,6 +183,26 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
         self.widget.frame_step_backwards();
     }
 
+    fn set_ab_loop_a(&self, seconds: Option<usize>) {
+        self.widget
+            .set_ab_loop_a(seconds.map_or(-1.0, |seconds| seconds as f64));
+    }
+
+    fn set_ab_loop_b(&self, seconds: Option<usize>) {
+        self.widget
+            .set_ab_loop_b(seconds.map_or(-1.0, |seconds| seconds as f64));
+    }
+
+    fn screenshot(&self, path: &Path, include_subtitles: bool) -> anyhow::Result<()> {
+        let path = path
+            .to_str()
+            .ok_or_else(|| anyhow!("Invalid screenshot path: {}", path.display()))?;
+        if !self.widget.screenshot_to_file(path, include_subtitles) {
+            bail!("Error saving screenshot to {path}");
+        }
+        Ok(())
+    }
+
     fn muted(&self) -> bool {
         self.widget.mute()
     }
@@ -186,6 +223,36 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
         self.widget.position() as usize
     }
 
+    fn playback_stats(&self) -> PlaybackStats {
+        // Frames can be dropped by both the decoder and the video output
+        let dropped_frames = match (
+            self.property::<u64>("decoder-frame-drop-count"),
+            self.property::<u64>("frame-drop-count"),
+        ) {
+            (None, None) => None,
+            (decoder, output) => Some(decoder.unwrap_or(0) + output.unwrap_or(0)),
+        };
+
+        PlaybackStats {
+            container: self.property("file-format"),
+            video_codec: self.property("video-format"),
+            audio_codec: self.property("audio-codec-name"),
+            resolution: self
+                .property("video-params/w")
+                .zip(self.property("video-params/h")),
+            fps: self
+                .property("estimated-vf-fps")
+                .or_else(|| self.property("container-fps")),
+            hardware_decoding: self
+                .property::<String>("hwdec-current")
+                .filter(|hwdec| !hwdec.is_empty() && hwdec != "no"),
+            video_bitrate: self.property::<f64>("video-bitrate").map(|b| b as u64),
+            audio_bitrate: self.property::<f64>("audio-bitrate").map(|b| b as u64),
+            cache_duration: self.property("demuxer-cache-duration"),
+            dropped_frames,
+        }
+    }
+
     fn playback_speed(&self) -> f64 {
         self.widget.playback_speed()
     }
@@ -194,6 +261,19 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
         self.widget.set_playback_speed(playback_speed);
     }
 
+    fn set_aspect_ratio(&self, aspect_ratio: VideoAspectRatio) {
+        self.widget
+            .set_aspect_ratio(aspect_ratio.ratio().unwrap_or(-1.0));
+    }
+
+    fn set_zoom(&self, zoom: f64) {
+        self.widget.set_zoom(zoom);
+    }
+
+    fn set_panscan(&self, panscan: f64) {
+        self.widget.set_panscan(panscan);
+    }
+
     fn current_subtitle_track(&self) -> Option<usize> {
         match self.widget.current_subtitle_track() {
             id @ 0.. => Some(id as usize),
@@ -210,6 +290,26 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
         self.widget.add_subtitle_track(url, title);
     }
 
+    fn set_subtitle_delay(&self, delay: i64) {
+        self.widget.set_subtitle_delay(delay as f64 / 1000.0);
+    }
+
+    fn supports_secondary_subtitles(&self) -> bool {
+        true
+    }
+
+    fn current_secondary_subtitle_track(&self) -> Option<usize> {
+        match self.widget.current_secondary_subtitle_track() {
+            id @ 0.. => Some(id as usize),
+            _ => None,
+        }
+    }
+
+    fn set_secondary_subtitle_track(&self, subtitle_track_id: Option<usize>) {
+        self.widget
+            .set_secondary_subtitle_track(subtitle_track_id.map_or(0, |id| id as u32));
+    }
+
     fn current_audio_track(&self) -> Option<usize> {
         match self.widget.current_audio_track() {
             id @ 0.. => Some(id as usize),
@@ -222,6 +322,41 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
             .set_audio_track(audio_track_id.map_or(0, |id| id as u32));
     }
 
+    fn set_audio_delay(&self, delay: i64) {
+        self.widget.set_audio_delay(delay as f64 / 1000.0);
+    }
+
+    fn audio_devices(&self) -> Vec<AudioDevice> {
+        get_audio_devices(&self.widget)
+    }
+
+    fn set_audio_device(&self, name: Option<&str>) {
+        self.widget.set_audio_device(name.unwrap_or("auto"));
+    }
+
+    fn set_audio_passthrough(&self, passthrough: bool) {
+        self.widget.set_audio_spdif(if passthrough {
+            AUDIO_PASSTHROUGH_CODECS
+        } else {
+            ""
+        });
+    }
+
+    fn set_audio_filters(&self, audio_filters: AudioFilters) {
+        if self.audio_filters.replace(Some(audio_filters)) == Some(audio_filters) {
+            return;
+        }
+
+        let mut filters = vec![];
+        if audio_filters.normalize_loudness {
+            filters.push(LOUDNORM_FILTER);
+        }
+        if audio_filters.night_mode {
+            filters.push(DYNAUDNORM_FILTER);
+        }
+        self.widget.set_audio_filter(&filters.join(","));
+    }
+
     fn set_subtitle_scale(&self, subtitle_scale: f64) {
         self.widget.set_subtitle_scale(subtitle_scale);
     }
@@ -236,10 +371,15 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
     }
 
     fn set_subtitle_position(&self, position: u32) {
-        assert!((0..150).contains(&position));
+        assert!((0..=150).contains(&position));
         self.widget.set_subtitle_position(position);
     }
 
+    fn set_secondary_subtitle_position(&self, position: u32) {
+        assert!((0..=150).contains(&position));
+        self.widget.set_secondary_subtitle_position(position);
+    }
+
     fn set_subtitle_font(&self, font: &VideoPlayerSubtitleFont) {
         let player = &self.widget;
         player.set_subtitle_font_family(&font.family);
@@ -259,6 +399,17 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
         id
     }
 
+    fn connect_error(&mut self, callback: Box<dyn Fn(String) + Send + 'static>) -> Uuid {
+        let id = uuid();
+        self.signal_handler_ids.insert(
+            id,
+            self.widget.connect_error(move |_, message| {
+                callback(message.to_string());
+            }),
+        );
+        id
+    }
+
     fn connect_position_updated(
         &mut self,
         callback: Box<dyn Fn(usize) + Send + Sync + 'static>,
@@ -363,6 +514,21 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
         id
     }
 
+    fn connect_audio_devices_updated(
+        &mut self,
+        callback: Box<dyn Fn(Vec<AudioDevice>) + Send + Sync + 'static>,
+    ) -> Uuid {
+        let id = uuid();
+        self.signal_handler_ids.insert(
+            id,
+            self.widget
+                .connect_audio_device_list_updated(move |player| {
+                    callback(get_audio_devices(player));
+                }),
+        );
+        id
+    }
+
     fn disconnect_signal_handler(&mut self, id: &Uuid) {
         match self.signal_handler_ids.remove(id) {
             Some(signal_handler_id) => {
@@ -375,6 +541,19 @@ impl VideoPlayerBackend for VideoPlayerBackendMpv {
     }
 }
 
+fn get_audio_devices(player: &VideoPlayerMpv) -> Vec<AudioDevice> {
+    player
+        .audio_device_list()
+        .chunks_exact(2)
+        .map(|device| AudioDevice {
+            name: device[0].to_string(),
+            description: device[1].to_string(),
+        })
+        // Automatic device selection is represented by not choosing a device
+        .filter(|device| device.name != "auto")
+        .collect()
+}
+
 fn get_track_name(track: &Track) -> String {
     let id = track.id() as usize;
     let title = track.title().map(|s| s.to_string());
@@ -408,6 +587,7 @@ impl From<Track> for SubtitleTrack {
         Self {
             id: value.id() as usize,
             name: get_track_name(&value),
+            language: value.language().map(|s| s.to_string()),
         }
     }
 }
@@ -418,6 +598,7 @@ impl From<Track> for AudioTrack {
         Self {
             id: value.id() as usize,
             name: get_track_name(&value),
+            language: value.language().map(|s| s.to_string()),
         }
     }
 }
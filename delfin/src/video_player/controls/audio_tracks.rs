@@ -6,12 +6,25 @@ use relm4::{
     gtk::{self, gio},
     Component, ComponentParts,
 };
+use uuid::Uuid;
 
 use crate::{
+    app::{AppInput, APP_BROKER},
+    globals::CONFIG,
     tr,
-    video_player::backends::{AudioTrack, VideoPlayerBackend},
+    utils::message_broker::ResettableMessageBroker,
+    video_player::{
+        backends::{AudioTrack, VideoPlayerBackend},
+        session::PlaybackChange,
+        sync_offsets::{get_sync_offsets, remember_audio_delay},
+        track_selection::TrackSelector,
+        VideoPlayerInput, VIDEO_PLAYER_BROKER,
+    },
 };
 
+pub static AUDIO_TRACKS_BROKER: ResettableMessageBroker<AudioTracksInput> =
+    ResettableMessageBroker::new();
+
 relm4::new_action_group!(AudioTracksActionGroup, "audio_tracks_actions");
 relm4::new_stateful_action!(
     SelectedAudioTrackAction,
@@ -26,12 +39,22 @@ pub struct AudioTracks {
     video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
     menu: gio::Menu,
     audio_tracks_available: bool,
+    audio_tracks: Vec<AudioTrack>,
+    track_selector: Option<TrackSelector>,
+    initial_track_selected: bool,
+    selected_track: Option<usize>,
+    item_id: Option<Uuid>,
+    // Audio delay in milliseconds
+    delay: i64,
 }
 
 #[derive(Debug)]
 pub enum AudioTracksInput {
-    Reset,
+    Reset { item_id: Option<Uuid> },
     AudioTracksUpdated(Vec<AudioTrack>),
+    SetTrackSelector(TrackSelector),
+    TrackSelected(Option<usize>),
+    AdjustDelay(i64),
 }
 
 #[relm4::component(pub)]
@@ -62,13 +85,22 @@ impl Component for AudioTracks {
             video_player,
             menu: gio::Menu::new(),
             audio_tracks_available: false,
+            audio_tracks: vec![],
+            track_selector: None,
+            initial_track_selected: false,
+            selected_track: None,
+            item_id: None,
+            delay: 0,
         };
 
         model
             .video_player
             .borrow_mut()
-            .connect_audio_tracks_updated(Box::new(move |tracks| {
-                sender.input(AudioTracksInput::AudioTracksUpdated(tracks.clone()));
+            .connect_audio_tracks_updated(Box::new({
+                let sender = sender.clone();
+                move |tracks| {
+                    sender.input(AudioTracksInput::AudioTracksUpdated(tracks.clone()));
+                }
             }));
 
         let widgets = view_output!();
@@ -78,9 +110,9 @@ impl Component for AudioTracks {
                 let video_player = model.video_player.clone();
                 move |_, state, value: Option<i32>| {
                     *state = value;
-                    video_player
-                        .borrow()
-                        .set_audio_track(value.map(|id| id as usize));
+                    let audio_track = value.map(|id| id as usize);
+                    video_player.borrow().set_audio_track(audio_track);
+                    sender.input(AudioTracksInput::TrackSelected(audio_track));
                 }
             });
 
@@ -99,10 +131,66 @@ impl Component for AudioTracks {
         root: &Self::Root,
     ) {
         match message {
-            AudioTracksInput::Reset => {
+            AudioTracksInput::Reset { item_id } => {
                 self.audio_tracks_available = false;
+                self.audio_tracks.clear();
+                self.track_selector = None;
+                self.initial_track_selected = false;
+                self.selected_track = None;
+
+                self.item_id = item_id;
+                self.delay = match item_id {
+                    Some(item_id) if CONFIG.read().video_player.remember_sync_offsets => {
+                        get_sync_offsets(&item_id).audio
+                    }
+                    _ => 0,
+                };
+                self.video_player.borrow().set_audio_delay(self.delay);
+            }
+            AudioTracksInput::AdjustDelay(amount) => {
+                self.delay += amount;
+                self.video_player.borrow().set_audio_delay(self.delay);
+                APP_BROKER.send(AppInput::Toast(
+                    tr!("vp-audio-delay-toast", { "delay" => self.delay }).into(),
+                    Some(1),
+                ));
+
+                if let Some(item_id) = self.item_id {
+                    if CONFIG.read().video_player.remember_sync_offsets {
+                        remember_audio_delay(&item_id, self.delay);
+                    }
+                }
+            }
+            AudioTracksInput::SetTrackSelector(track_selector) => {
+                self.track_selector = Some(track_selector);
+                self.select_initial_track(root);
+            }
+            AudioTracksInput::TrackSelected(audio_track) => {
+                // Remember tracks picked by the user for the rest of the series, ignoring the
+                // menu being synced with the player and the initial track selection
+                if self.initial_track_selected && audio_track != self.selected_track {
+                    if let (Some(track_selector), Some(audio_track)) =
+                        (&self.track_selector, audio_track)
+                    {
+                        track_selector.remember_audio_track(&self.audio_tracks, audio_track);
+                    }
+                }
+                if audio_track != self.selected_track {
+                    let stream_index = match (&self.track_selector, audio_track) {
+                        (Some(track_selector), Some(audio_track)) => {
+                            track_selector.audio_stream_index(&self.audio_tracks, audio_track)
+                        }
+                        _ => None,
+                    };
+                    VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ReportPlaybackChange(
+                        PlaybackChange::AudioTrack(stream_index),
+                    ));
+                }
+                self.selected_track = audio_track;
             }
             AudioTracksInput::AudioTracksUpdated(audio_tracks) => {
+                self.audio_tracks.clone_from(&audio_tracks);
+
                 if audio_tracks.len() < 2 {
                     return;
                 }
@@ -138,9 +226,39 @@ impl Component for AudioTracks {
                     )
                     .expect("Error selecting current audio track.");
                 }
+
+                self.select_initial_track(root);
             }
         }
 
         self.update_view(widgets, sender);
     }
 }
+
+impl AudioTracks {
+    // Pick the audio track based on the user's language preferences, once we know both the
+    // preferences and which tracks are available
+    fn select_initial_track(&mut self, root: &gtk::MenuButton) {
+        if self.initial_track_selected || self.audio_tracks.is_empty() {
+            return;
+        }
+        let Some(track_selector) = &self.track_selector else {
+            return;
+        };
+
+        self.initial_track_selected = true;
+
+        if let Some(audio_track) = track_selector.audio_track(&self.audio_tracks) {
+            self.selected_track = Some(audio_track);
+            root.activate_action(
+                &format!(
+                    "{}.{}",
+                    AudioTracksActionGroup::NAME,
+                    SelectedAudioTrackAction::NAME
+                ),
+                Some(&Some(audio_track as i32).to_variant()),
+            )
+            .expect("Error selecting audio track.");
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use std::{cell::RefCell, sync::Arc};
+
+use gtk::prelude::*;
+use jellyfin_api::types::ChapterInfo;
+use relm4::{factory::FactoryVecDeque, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    app::{AppInput, APP_BROKER},
+    jellyfin_api::api_client::ApiClient,
+    media_details::chapters::{
+        ChapterListItem, ChapterListItemInit, ChapterListItemInput, ChapterListItemOutput,
+    },
+    tr,
+    utils::{message_broker::ResettableMessageBroker, ticks::ticks_to_seconds},
+    video_player::backends::VideoPlayerBackend,
+};
+
+use super::next_prev_episode::{NextPrevEpisodeInput, NEXT_EPISODE_BROKER, PREV_EPISODE_BROKER};
+
+// How far into a chapter (in seconds) going to the previous chapter restarts the current chapter
+// instead
+const PREVIOUS_CHAPTER_THRESHOLD: usize = 3;
+
+pub(crate) static CHAPTERS_BROKER: ResettableMessageBroker<ChaptersInput> =
+    ResettableMessageBroker::new();
+
+#[derive(Clone, Debug)]
+struct Chapter {
+    start: usize,
+    name: Option<String>,
+}
+
+pub(crate) struct Chapters {
+    video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
+    chapter_list: FactoryVecDeque<ChapterListItem>,
+    // Sorted by start time, with the index of the chapter in the list
+    chapters: Vec<(usize, Chapter)>,
+    position: usize,
+    active_chapter: Option<usize>,
+}
+
+#[derive(Debug)]
+pub enum ChaptersInput {
+    Reset,
+    Load {
+        api_client: Arc<ApiClient>,
+        item_id: Uuid,
+        chapters: Vec<ChapterInfo>,
+    },
+    PositionUpdated(usize),
+    NextChapter,
+    PreviousChapter,
+    // Used by media controls, which only have next and previous buttons
+    NextChapterOrEpisode,
+    PreviousChapterOrEpisode,
+    PlayFrom(usize),
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for Chapters {
+    type Init = Arc<RefCell<dyn VideoPlayerBackend>>;
+    type Input = ChaptersInput;
+    type Output = ();
+
+    view! {
+        gtk::MenuButton {
+            set_icon_name: "view-list-symbolic",
+            set_focus_on_click: false,
+            set_direction: gtk::ArrowType::Up,
+            set_tooltip_text: Some(tr!("vp-chapters")),
+            #[watch]
+            set_visible: !model.chapters.is_empty(),
+
+            #[wrap(Some)]
+            set_popover = &gtk::Popover {
+                gtk::ScrolledWindow {
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+                    set_propagate_natural_height: true,
+                    set_max_content_height: 400,
+                    set_min_content_width: 320,
+
+                    #[local_ref]
+                    chapter_list -> gtk::ListBox {
+                        add_css_class: "boxed-list",
+                        add_css_class: "chapter-list",
+                        set_selection_mode: gtk::SelectionMode::None,
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        video_player: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        video_player.borrow_mut().connect_position_updated({
+            let sender = sender.clone();
+            Box::new(move |position| {
+                sender.input(ChaptersInput::PositionUpdated(position));
+            })
+        });
+
+        let model = Chapters {
+            video_player,
+            chapter_list: FactoryVecDeque::builder()
+                .launch(gtk::ListBox::default())
+                .forward(sender.input_sender(), |output| match output {
+                    ChapterListItemOutput::PlayFrom(start) => ChaptersInput::PlayFrom(start),
+                }),
+            chapters: vec![],
+            position: 0,
+            active_chapter: None,
+        };
+
+        let chapter_list = model.chapter_list.widget();
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+        match message {
+            ChaptersInput::Reset => {
+                self.chapter_list.guard().clear();
+                self.chapters.clear();
+                self.position = 0;
+                self.active_chapter = None;
+            }
+            ChaptersInput::Load {
+                api_client,
+                item_id,
+                chapters,
+            } => {
+                let mut chapter_list = self.chapter_list.guard();
+                chapter_list.clear();
+                self.chapters.clear();
+
+                for (index, chapter) in chapters.into_iter().enumerate() {
+                    if let Some(start) = chapter.start_position_ticks {
+                        self.chapters.push((
+                            index,
+                            Chapter {
+                                start: ticks_to_seconds(start).max(0) as usize,
+                                name: chapter.name.clone(),
+                            },
+                        ));
+                    }
+                    chapter_list.push_back(ChapterListItemInit {
+                        api_client: api_client.clone(),
+                        item_id,
+                        index,
+                        chapter,
+                    });
+                }
+                drop(chapter_list);
+
+                self.chapters.sort_by_key(|(_, chapter)| chapter.start);
+                self.active_chapter = None;
+                self.update_active_chapter();
+            }
+            ChaptersInput::PositionUpdated(position) => {
+                self.position = position;
+                self.update_active_chapter();
+            }
+            ChaptersInput::NextChapter => {
+                let starts = self.chapter_starts();
+                if let Some(index) = next_chapter(&starts, self.position) {
+                    self.seek_to_chapter(index);
+                }
+            }
+            ChaptersInput::PreviousChapter => {
+                let starts = self.chapter_starts();
+                if let Some(index) = previous_chapter(&starts, self.position) {
+                    self.seek_to_chapter(index);
+                }
+            }
+            ChaptersInput::NextChapterOrEpisode => {
+                match next_chapter_or_episode(&self.chapter_starts(), self.position) {
+                    Skip::Chapter(index) => self.seek_to_chapter(index),
+                    Skip::Episode => NEXT_EPISODE_BROKER.send(NextPrevEpisodeInput::Play),
+                }
+            }
+            ChaptersInput::PreviousChapterOrEpisode => {
+                match previous_chapter_or_episode(&self.chapter_starts(), self.position) {
+                    Skip::Chapter(index) => self.seek_to_chapter(index),
+                    Skip::Episode => PREV_EPISODE_BROKER.send(NextPrevEpisodeInput::Play),
+                }
+            }
+            ChaptersInput::PlayFrom(start) => {
+                self.video_player.borrow().seek_to(start);
+                self.position = start;
+                self.update_active_chapter();
+            }
+        }
+    }
+}
+
+impl Chapters {
+    fn chapter_starts(&self) -> Vec<usize> {
+        self.chapters
+            .iter()
+            .map(|(_, chapter)| chapter.start)
+            .collect()
+    }
+
+    fn seek_to_chapter(&mut self, index: usize) {
+        let Some((_, chapter)) = self.chapters.get(index) else {
+            return;
+        };
+
+        self.video_player.borrow().seek_to(chapter.start);
+        self.position = chapter.start;
+
+        let name = chapter
+            .name
+            .clone()
+            .unwrap_or_else(|| tr!("vp-chapters.unnamed", { "number" => index + 1 }).to_string());
+        APP_BROKER.send(AppInput::Toast(
+            tr!("vp-chapters.toast", { "name" => name }).to_string(),
+            Some(1),
+        ));
+
+        self.update_active_chapter();
+    }
+
+    fn update_active_chapter(&mut self) {
+        let active_chapter = current_chapter(&self.chapter_starts(), self.position)
+            .and_then(|index| self.chapters.get(index))
+            .map(|(list_index, _)| *list_index);
+
+        if active_chapter == self.active_chapter {
+            return;
+        }
+
+        if let Some(previous) = self.active_chapter {
+            self.chapter_list
+                .send(previous, ChapterListItemInput::SetActive(false));
+        }
+        if let Some(active) = active_chapter {
+            self.chapter_list
+                .send(active, ChapterListItemInput::SetActive(true));
+        }
+        self.active_chapter = active_chapter;
+    }
+}
+
+/// Index of the chapter playing at the given position, given chapter start times in ascending
+/// order.
+fn current_chapter(starts: &[usize], position: usize) -> Option<usize> {
+    starts.iter().rposition(|start| *start <= position)
+}
+
+fn next_chapter(starts: &[usize], position: usize) -> Option<usize> {
+    starts.iter().position(|start| *start > position)
+}
+
+fn previous_chapter(starts: &[usize], position: usize) -> Option<usize> {
+    let current = current_chapter(starts, position)?;
+    if current == 0 || position - starts[current] > PREVIOUS_CHAPTER_THRESHOLD {
+        Some(current)
+    } else {
+        Some(current - 1)
+    }
+}
+
+// Where the media controls' next and previous buttons go
+#[derive(Debug, PartialEq)]
+enum Skip {
+    Chapter(usize),
+    Episode,
+}
+
+fn next_chapter_or_episode(starts: &[usize], position: usize) -> Skip {
+    next_chapter(starts, position).map_or(Skip::Episode, Skip::Chapter)
+}
+
+fn previous_chapter_or_episode(starts: &[usize], position: usize) -> Skip {
+    match previous_chapter(starts, position) {
+        // Near the start of the first chapter, so there's no earlier chapter to go to
+        Some(0) if position - starts[0] <= PREVIOUS_CHAPTER_THRESHOLD => Skip::Episode,
+        Some(index) => Skip::Chapter(index),
+        None => Skip::Episode,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STARTS: [usize; 3] = [0, 60, 300];
+
+    #[test]
+    fn test_next_chapter() {
+        assert_eq!(next_chapter(&STARTS, 0), Some(1));
+        assert_eq!(next_chapter(&STARTS, 120), Some(2));
+        assert_eq!(next_chapter(&STARTS, 300), None);
+        assert_eq!(next_chapter(&[], 10), None);
+    }
+
+    #[test]
+    fn test_previous_chapter() {
+        // Restarts the current chapter if we're past the threshold
+        assert_eq!(previous_chapter(&STARTS, 120), Some(1));
+        // Goes to the previous chapter if we're near the start of the current one
+        assert_eq!(previous_chapter(&STARTS, 61), Some(0));
+        assert_eq!(previous_chapter(&STARTS, 2), Some(0));
+        assert_eq!(previous_chapter(&[], 10), None);
+    }
+
+    #[test]
+    fn test_chapter_or_episode() {
+        assert_eq!(next_chapter_or_episode(&STARTS, 120), Skip::Chapter(2));
+        // Past the start of the last chapter
+        assert_eq!(next_chapter_or_episode(&STARTS, 400), Skip::Episode);
+        assert_eq!(next_chapter_or_episode(&[], 10), Skip::Episode);
+
+        assert_eq!(previous_chapter_or_episode(&STARTS, 61), Skip::Chapter(0));
+        assert_eq!(previous_chapter_or_episode(&STARTS, 30), Skip::Chapter(0));
+        assert_eq!(previous_chapter_or_episode(&STARTS, 2), Skip::Episode);
+        assert_eq!(previous_chapter_or_episode(&[], 10), Skip::Episode);
+    }
+}
@@ -1,11 +1,14 @@
-mod audio_tracks;
+pub(super) mod audio_tracks;
+pub(super) mod chapters;
 pub(super) mod fullscreen;
 pub(super) mod next_prev_episode;
 pub(super) mod play_pause;
 pub(super) mod playback_speed;
 pub(super) mod scrubber;
 pub(super) mod skip_forwards_backwards;
+mod subtitle_search;
 pub(super) mod subtitles;
+pub(super) mod video_settings;
 pub(super) mod volume;
 
 use std::{
@@ -16,9 +19,12 @@ use std::{
 use crate::{
     app::APP_BROKER,
     jellyfin_api::api_client::ApiClient,
+    tr,
     video_player::{
         backends::VideoPlayerBackend,
         controls::{
+            audio_tracks::AUDIO_TRACKS_BROKER,
+            chapters::CHAPTERS_BROKER,
             fullscreen::FULLSCREEN_BROKER,
             next_prev_episode::{
                 NextPrevEpisodeDirection, NextPrevEpisodeOutput, NEXT_EPISODE_BROKER,
@@ -30,9 +36,12 @@ use crate::{
                 SkipForwardsBackwardsDirection, SKIP_BACKWARDS_BROKER, SKIP_FORWARDS_BROKER,
             },
             subtitles::SUBTITLES_BROKER,
+            video_settings::VIDEO_SETTINGS_BROKER,
             volume::VOLUME_BROKER,
         },
         next_up::NEXT_UP_VISIBILE,
+        track_selection::TrackSelector,
+        VideoPlayerInput, VIDEO_PLAYER_BROKER,
     },
 };
 use gtk::prelude::*;
@@ -42,6 +51,7 @@ use relm4::{gtk, Component, ComponentController, ComponentParts, Controller, Sim
 
 use self::{
     audio_tracks::{AudioTracks, AudioTracksInput},
+    chapters::Chapters,
     fullscreen::Fullscreen,
     next_prev_episode::{NextPrevEpisode, NextPrevEpisodeInput},
     play_pause::PlayPause,
@@ -49,6 +59,7 @@ use self::{
     scrubber::Scrubber,
     skip_forwards_backwards::SkipForwardsBackwards,
     subtitles::{Subtitles, SubtitlesInput},
+    video_settings::VideoSettings,
     volume::Volume,
 };
 
@@ -67,8 +78,10 @@ pub struct VideoPlayerControls {
     volume: Option<Controller<Volume>>,
     subtitles: OnceCell<Controller<Subtitles>>,
     audio_tracks: OnceCell<Controller<AudioTracks>>,
+    video_settings: OnceCell<Controller<VideoSettings>>,
     fullscreen: Option<Controller<Fullscreen>>,
     playback_speed: OnceCell<Controller<PlaybackSpeed>>,
+    chapters: OnceCell<Controller<Chapters>>,
 }
 
 pub struct VideoPlayerControlsInit {
@@ -84,6 +97,7 @@ pub enum VideoPlayerControlsInput {
         api_client: Arc<ApiClient>,
         item: Box<BaseItemDto>,
     },
+    SetTrackSelector(TrackSelector),
     SetNextPreviousEpisodes(Box<Option<BaseItemDto>>, Box<Option<BaseItemDto>>),
     PlayPreviousEpisode,
     PlayNextEpisode,
@@ -148,14 +162,18 @@ impl SimpleComponent for VideoPlayerControls {
             volume: None,
             subtitles: OnceCell::new(),
             audio_tracks: OnceCell::new(),
+            video_settings: OnceCell::new(),
             fullscreen: None,
             playback_speed: OnceCell::new(),
+            chapters: OnceCell::new(),
         };
 
         let widgets = view_output!();
         let controls = &widgets.controls;
         let second_row = &widgets.second_row;
 
+        AUDIO_TRACKS_BROKER.reset();
+        CHAPTERS_BROKER.reset();
         FULLSCREEN_BROKER.reset();
         PLAY_PAUSE_BROKER.reset();
         SCRUBBER_BROKER.reset();
@@ -164,6 +182,7 @@ impl SimpleComponent for VideoPlayerControls {
         PREV_EPISODE_BROKER.reset();
         NEXT_EPISODE_BROKER.reset();
         SUBTITLES_BROKER.reset();
+        VIDEO_SETTINGS_BROKER.reset();
         VOLUME_BROKER.reset();
 
         let scrubber = Scrubber::builder()
@@ -222,6 +241,12 @@ impl SimpleComponent for VideoPlayerControls {
         // Push remaining controls to end
         second_row.append(&gtk::Box::builder().hexpand(true).build());
 
+        let chapters = Chapters::builder()
+            .launch_with_broker(player.clone(), &CHAPTERS_BROKER.read())
+            .detach();
+        second_row.append(chapters.widget());
+        model.chapters.set(chapters).unwrap();
+
         let playback_speed = PlaybackSpeed::builder()
             .launch_with_broker(player.clone(), &PLAYBACK_SPEED_BROKER.read())
             .detach();
@@ -234,16 +259,34 @@ impl SimpleComponent for VideoPlayerControls {
         second_row.append(subtitles.widget());
         model.subtitles.set(subtitles).unwrap();
 
-        let audio_tracks = AudioTracks::builder().launch(player.clone()).detach();
+        let audio_tracks = AudioTracks::builder()
+            .launch_with_broker(player.clone(), &AUDIO_TRACKS_BROKER.read())
+            .detach();
         second_row.append(audio_tracks.widget());
         model.audio_tracks.set(audio_tracks).unwrap();
 
+        let video_settings = VideoSettings::builder()
+            .launch_with_broker(player.clone(), &VIDEO_SETTINGS_BROKER.read())
+            .detach();
+        second_row.append(video_settings.widget());
+        model.video_settings.set(video_settings).unwrap();
+
         let volume = Volume::builder()
             .launch_with_broker(player, &VOLUME_BROKER.read())
             .detach();
         second_row.append(volume.widget());
         model.volume = Some(volume);
 
+        let pip = gtk::Button::builder()
+            .icon_name("window-new-symbolic")
+            .tooltip_text(tr!("vp-pip-tooltip"))
+            .focus_on_click(false)
+            .build();
+        pip.connect_clicked(|_| {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::EnterPip);
+        });
+        second_row.append(&pip);
+
         let fullscreen = Fullscreen::builder()
             .launch_with_broker((), &FULLSCREEN_BROKER.read())
             .detach();
@@ -267,11 +310,20 @@ impl SimpleComponent for VideoPlayerControls {
                 self.show_controls = show_controls;
             }
             VideoPlayerControlsInput::SetPlaying { api_client, item } => {
+                let item_id = item.id;
                 if let Some(subtitles) = self.subtitles.get() {
                     subtitles.emit(SubtitlesInput::Reset { api_client, item });
                 }
                 if let Some(audio_tracks) = self.audio_tracks.get() {
-                    audio_tracks.emit(AudioTracksInput::Reset);
+                    audio_tracks.emit(AudioTracksInput::Reset { item_id });
+                }
+            }
+            VideoPlayerControlsInput::SetTrackSelector(track_selector) => {
+                if let Some(subtitles) = self.subtitles.get() {
+                    subtitles.emit(SubtitlesInput::SetTrackSelector(track_selector.clone()));
+                }
+                if let Some(audio_tracks) = self.audio_tracks.get() {
+                    audio_tracks.emit(AudioTracksInput::SetTrackSelector(track_selector));
                 }
             }
             VideoPlayerControlsInput::SetNextPreviousEpisodes(prev, next) => {
@@ -292,12 +344,12 @@ impl SimpleComponent for VideoPlayerControls {
             }
             VideoPlayerControlsInput::PlayPreviousEpisode => {
                 if let (Some(previous), _) = &self.next_prev_episodes {
-                    APP_BROKER.send(crate::app::AppInput::PlayVideo(previous.clone()));
+                    APP_BROKER.send(crate::app::AppInput::ContinuePlayback(previous.clone()));
                 }
             }
             VideoPlayerControlsInput::PlayNextEpisode => {
                 if let (_, Some(next)) = &self.next_prev_episodes {
-                    APP_BROKER.send(crate::app::AppInput::PlayVideo(next.clone()));
+                    APP_BROKER.send(crate::app::AppInput::ContinuePlayback(next.clone()));
                 }
             }
             VideoPlayerControlsInput::Noop => {}
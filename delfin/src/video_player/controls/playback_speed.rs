@@ -12,7 +12,10 @@ use crate::{
     app::{AppInput, APP_BROKER},
     tr,
     utils::message_broker::ResettableMessageBroker,
-    video_player::backends::VideoPlayerBackend,
+    video_player::{
+        backends::VideoPlayerBackend, session::PlaybackChange, VideoPlayerInput,
+        VIDEO_PLAYER_BROKER,
+    },
 };
 
 const PLAYBACK_SPEED_OPTIONS: [f64; 9] = [0.25, 0.5, 0.75, 1.0, 1.25, 1.5, 1.75, 2.0, 4.0];
@@ -58,6 +61,9 @@ impl SimpleComponent for PlaybackSpeed {
                 move |_, state, value: f64| {
                     *state = value;
                     video_player.borrow().set_playback_speed(value);
+                    VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ReportPlaybackChange(
+                        PlaybackChange::PlaybackRate(value),
+                    ));
                 }
             });
 
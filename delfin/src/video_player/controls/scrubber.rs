@@ -1,20 +1,26 @@
-use bytes::Buf;
-use chrono::TimeDelta;
 use jellyfin_api::types::ChapterInfo;
 use std::{cell::RefCell, sync::Arc};
-use tracing::warn;
 
 use gdk::{Rectangle, Texture};
 use graphene::Point;
-use gtk::{gdk, gdk_pixbuf, graphene, prelude::*};
-use relm4::prelude::*;
+use gtk::{gdk, gio, graphene, prelude::*};
+use relm4::{
+    actions::{RelmAction, RelmActionGroup},
+    prelude::*,
+};
 
 use crate::{
     config::video_player_config::DurationDisplay,
     globals::CONFIG,
     tr,
-    utils::{bif::Thumbnail, message_broker::ResettableMessageBroker, ticks::ticks_to_seconds},
-    video_player::backends::VideoPlayerBackend,
+    utils::{
+        message_broker::ResettableMessageBroker, ticks::ticks_to_seconds,
+        timestamp::seconds_to_timestamp,
+    },
+    video_player::{
+        ab_loop::AbLoop, backends::VideoPlayerBackend, trickplay::Trickplay, VideoPlayerInput,
+        VIDEO_PLAYER_BROKER,
+    },
 };
 
 const TIMESTAMP_WIDTH: i32 = 80;
@@ -22,6 +28,11 @@ const TIMESTAMP_WIDTH: i32 = 80;
 pub(crate) static SCRUBBER_BROKER: ResettableMessageBroker<ScrubberInput> =
     ResettableMessageBroker::new();
 
+relm4::new_action_group!(ScrubberActionGroup, "scrubber_actions");
+relm4::new_stateless_action!(SetLoopStartAction, ScrubberActionGroup, "set_loop_start");
+relm4::new_stateless_action!(SetLoopEndAction, ScrubberActionGroup, "set_loop_end");
+relm4::new_stateless_action!(ClearLoopAction, ScrubberActionGroup, "clear_loop");
+
 struct ScrubberPopover {
     position: f64,
     timestamp: usize,
@@ -46,8 +57,11 @@ pub(crate) struct Scrubber {
 
     scrubbing: bool,
     popover: Option<ScrubberPopover>,
-    thumbnails: Option<Vec<Thumbnail>>,
+    trickplay: Option<Trickplay>,
     chapters: Vec<Chapter>,
+    ab_loop: AbLoop,
+    // Where the scrubber was right clicked, which is where loop points from the context menu are set
+    context_menu_timestamp: usize,
 }
 
 #[derive(Debug)]
@@ -60,9 +74,19 @@ pub enum ScrubberInput {
     SetScrubbing(bool),
     ScrubberMouseHover(f64),
     ScrubberMouseLeave,
-    LoadedThumbnails(Option<Vec<Thumbnail>>),
+    LoadedTrickplay(Option<Trickplay>),
     DurationDisplayUpdated(DurationDisplay),
     DisplayChapters(Vec<ChapterInfo>),
+    SetAbLoop(AbLoop),
+    ShowContextMenu(f64),
+    SetLoopStart,
+    SetLoopEnd,
+    ClearLoop,
+}
+
+#[derive(Debug)]
+pub enum ScrubberCommandOutput {
+    TrickplayTileLoaded,
 }
 
 #[relm4::component(pub(crate))]
@@ -70,7 +94,7 @@ impl Component for Scrubber {
     type Init = Arc<RefCell<dyn VideoPlayerBackend>>;
     type Input = ScrubberInput;
     type Output = ();
-    type CommandOutput = ();
+    type CommandOutput = ScrubberCommandOutput;
 
     view! {
         gtk::Box {
@@ -112,6 +136,13 @@ impl Component for Scrubber {
                     },
                 },
 
+                add_controller = gtk::GestureClick {
+                    set_button: gdk::BUTTON_SECONDARY,
+                    connect_pressed[sender] => move |_, _, x, _| {
+                        sender.input(ScrubberInput::ShowContextMenu(x));
+                    },
+                },
+
                 add_controller = gtk::EventControllerMotion {
                     connect_motion[sender] => move |_, x, _| {
                         sender.input(ScrubberInput::ScrubberMouseHover(x));
@@ -165,6 +196,11 @@ impl Component for Scrubber {
                 },
             },
 
+            #[name = "context_menu"]
+            gtk::PopoverMenu::from_model(Some(&context_menu_model())) {
+                set_position: gtk::PositionType::Top,
+            },
+
             #[name = "duration"]
             gtk::Button {
                 set_focus_on_click: false,
@@ -212,14 +248,40 @@ impl Component for Scrubber {
             duration_display: CONFIG.read().video_player.duration_display,
             scrubbing: false,
             popover: None,
-            thumbnails: None,
+            trickplay: None,
             chapters: vec![],
+            ab_loop: AbLoop::default(),
+            context_menu_timestamp: 0,
         };
 
         Scrubber::subscribe_to_config(&sender);
 
         let widgets = view_output!();
 
+        let set_loop_start_action: RelmAction<SetLoopStartAction> = RelmAction::new_stateless({
+            let sender = sender.clone();
+            move |_| {
+                sender.input(ScrubberInput::SetLoopStart);
+            }
+        });
+
+        let set_loop_end_action: RelmAction<SetLoopEndAction> = RelmAction::new_stateless({
+            let sender = sender.clone();
+            move |_| {
+                sender.input(ScrubberInput::SetLoopEnd);
+            }
+        });
+
+        let clear_loop_action: RelmAction<ClearLoopAction> = RelmAction::new_stateless(move |_| {
+            sender.input(ScrubberInput::ClearLoop);
+        });
+
+        let mut group = RelmActionGroup::<ScrubberActionGroup>::new();
+        group.add_action(set_loop_start_action);
+        group.add_action(set_loop_end_action);
+        group.add_action(clear_loop_action);
+        group.register_for_widget(&root);
+
         relm4::ComponentParts { model, widgets }
     }
 
@@ -232,14 +294,16 @@ impl Component for Scrubber {
     ) {
         let scrubber = &widgets.scrubber;
         let popover = &widgets.popover;
+        let context_menu = &widgets.context_menu;
 
         match message {
             ScrubberInput::Reset => {
                 self.loading = true;
                 self.position = 0;
                 self.duration = 0;
-                self.thumbnails = None;
+                self.trickplay = None;
                 self.chapters.clear();
+                self.ab_loop = AbLoop::default();
                 scrubber.clear_marks();
             }
             ScrubberInput::SetPosition(position) => {
@@ -250,7 +314,7 @@ impl Component for Scrubber {
             ScrubberInput::SetDuration(duration) => {
                 self.duration = duration;
                 // Make sure marks are in the right place after scale range changes
-                self.mark_chapters(scrubber);
+                self.update_marks(scrubber);
             }
             ScrubberInput::SetPlaying => {
                 self.loading = false;
@@ -272,8 +336,7 @@ impl Component for Scrubber {
                 }
             }
             ScrubberInput::ScrubberMouseHover(position) => {
-                let percent = (position / scrubber.width() as f64).clamp(0.0, 1.0);
-                let timestamp = (self.duration as f64 * percent) as usize;
+                let timestamp = self.timestamp_at(scrubber, position);
 
                 if self.scrubbing {
                     self.position = timestamp;
@@ -285,15 +348,15 @@ impl Component for Scrubber {
                 self.popover = Some(ScrubberPopover {
                     position: popover_position.map_or(0.0, |p| p.x()) as f64,
                     timestamp,
-                    thumbnail: self.get_thumbnail(timestamp),
+                    thumbnail: self.get_thumbnail(&sender, timestamp),
                     chapter: self.get_chapter(timestamp),
                 });
             }
             ScrubberInput::ScrubberMouseLeave => {
                 self.popover = None;
             }
-            ScrubberInput::LoadedThumbnails(thumbnails) => {
-                self.thumbnails = thumbnails;
+            ScrubberInput::LoadedTrickplay(trickplay) => {
+                self.trickplay = trickplay;
             }
             ScrubberInput::DisplayChapters(chapters) => {
                 self.chapters = chapters
@@ -310,7 +373,59 @@ impl Component for Scrubber {
                 // a given timestamp when scrubbing
                 self.chapters.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
 
-                self.mark_chapters(scrubber);
+                self.update_marks(scrubber);
+            }
+            ScrubberInput::SetAbLoop(ab_loop) => {
+                self.ab_loop = ab_loop;
+                self.update_marks(scrubber);
+            }
+            ScrubberInput::ShowContextMenu(position) => {
+                self.context_menu_timestamp = self.timestamp_at(scrubber, position);
+                self.popover = None;
+
+                let menu_position =
+                    scrubber.compute_point(context_menu, &Point::new(position as f32, 0.0));
+                context_menu.set_pointing_to(Some(&Rectangle::new(
+                    menu_position.map_or(0, |p| p.x() as i32),
+                    -15,
+                    0,
+                    0,
+                )));
+                context_menu.popup();
+            }
+            ScrubberInput::SetLoopStart => {
+                VIDEO_PLAYER_BROKER.send(VideoPlayerInput::SetAbLoopStart(
+                    self.context_menu_timestamp,
+                ));
+            }
+            ScrubberInput::SetLoopEnd => {
+                VIDEO_PLAYER_BROKER
+                    .send(VideoPlayerInput::SetAbLoopEnd(self.context_menu_timestamp));
+            }
+            ScrubberInput::ClearLoop => {
+                VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ClearAbLoop);
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_cmd_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            ScrubberCommandOutput::TrickplayTileLoaded => {
+                // Show the thumbnail if the popover is waiting on the tile that was loaded
+                if let Some(timestamp) = self.popover.as_ref().map(|p| p.timestamp) {
+                    let thumbnail = self.get_thumbnail(&sender, timestamp);
+                    if let Some(popover) = self.popover.as_mut() {
+                        popover.thumbnail = thumbnail;
+                    }
+                }
             }
         }
 
@@ -325,32 +440,23 @@ impl Scrubber {
         });
     }
 
-    fn get_thumbnail(&self, timestamp: usize) -> Option<Texture> {
-        let thumbnails = self.thumbnails.as_ref()?;
-
-        let mut nearest_thumbnail_idx = 0;
-        for (i, thumbnail) in thumbnails.iter().enumerate() {
-            if thumbnail.timestamp < timestamp {
-                nearest_thumbnail_idx = i;
+    fn get_thumbnail(&self, sender: &ComponentSender<Self>, timestamp: usize) -> Option<Texture> {
+        let trickplay = self.trickplay.as_ref()?;
+
+        // Native trickplay tiles are fetched as they're needed
+        if let Trickplay::Native(native_trickplay) = trickplay {
+            for tile_index in native_trickplay.tiles_to_load(timestamp) {
+                sender.oneshot_command({
+                    let native_trickplay = native_trickplay.clone();
+                    async move {
+                        native_trickplay.load_tile(tile_index).await;
+                        ScrubberCommandOutput::TrickplayTileLoaded
+                    }
+                });
             }
         }
 
-        let image = if let Some(thumbnail) = thumbnails.get(nearest_thumbnail_idx) {
-            &thumbnail.image
-        } else {
-            warn!("Error getting trickplay thumbnail");
-            return None;
-        };
-        let pixbuf = match gdk_pixbuf::Pixbuf::from_read(image.clone().reader()) {
-            Ok(pixbuf) => pixbuf,
-            Err(err) => {
-                warn!(
-                    "Error creating pixbuf for scrubber thumbnail at timestamp {timestamp}: {err}"
-                );
-                return None;
-            }
-        };
-        Some(Texture::for_pixbuf(&pixbuf))
+        trickplay.get_thumbnail(timestamp)
     }
 
     fn get_chapter(&self, timestamp: usize) -> Option<Chapter> {
@@ -367,29 +473,39 @@ impl Scrubber {
         Some(self.chapters[nearest_chapter_idx].clone())
     }
 
-    fn mark_chapters(&self, scrubber: &gtk::Scale) {
+    fn timestamp_at(&self, scrubber: &gtk::Scale, position: f64) -> usize {
+        let percent = (position / scrubber.width() as f64).clamp(0.0, 1.0);
+        (self.duration as f64 * percent) as usize
+    }
+
+    fn update_marks(&self, scrubber: &gtk::Scale) {
         scrubber.clear_marks();
         for chapter in &self.chapters {
             scrubber.add_mark(chapter.timestamp as f64, gtk::PositionType::Top, None);
         }
+
+        // Loop points go below the scrubber, so they can't be confused with chapters
+        if let Some(start) = self.ab_loop.start {
+            scrubber.add_mark(start as f64, gtk::PositionType::Bottom, Some("A"));
+        }
+        if let Some(end) = self.ab_loop.end {
+            scrubber.add_mark(end as f64, gtk::PositionType::Bottom, Some("B"));
+        }
     }
 }
 
-fn seconds_to_timestamp(seconds: usize) -> String {
-    let Some(time) = TimeDelta::try_seconds(seconds as i64) else {
-        warn!("Could not convert seconds to TimeDelta");
-        return String::new();
-    };
-
-    let hours = time.num_hours();
-    let minutes = time.num_minutes() - (60 * hours);
-    let seconds = time.num_seconds() % 60;
-
-    if hours > 0 {
-        format!("{hours}:{minutes:0>2}:{seconds:0>2}")
-    } else {
-        format!("{minutes:0>2}:{seconds:0>2}")
-    }
+fn context_menu_model() -> gio::Menu {
+    let menu = gio::Menu::new();
+    menu.append_item(&RelmAction::<SetLoopStartAction>::to_menu_item(tr!(
+        "vp-ab-loop-menu.set-start"
+    )));
+    menu.append_item(&RelmAction::<SetLoopEndAction>::to_menu_item(tr!(
+        "vp-ab-loop-menu.set-end"
+    )));
+    menu.append_item(&RelmAction::<ClearLoopAction>::to_menu_item(tr!(
+        "vp-ab-loop-menu.clear"
+    )));
+    menu
 }
 
 fn duration_to_timestamp(
@@ -409,12 +525,6 @@ fn duration_to_timestamp(
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_seconds_to_timestamp() {
-        assert_eq!(seconds_to_timestamp(8624), "2:23:44");
-        assert_eq!(seconds_to_timestamp(345), "05:45");
-    }
-
     #[test]
     fn test_duration_to_timestamp_total() {
         assert_eq!(
@@ -0,0 +1,341 @@
+use std::sync::Arc;
+
+use adw::prelude::*;
+use relm4::{factory::FactoryVecDeque, prelude::*, FactorySender};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    app::{AppInput, APP_BROKER},
+    jellyfin_api::{api::remote_subtitles::RemoteSubtitleInfo, api_client::ApiClient},
+    tr,
+    utils::main_window::get_main_window,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+enum SearchState {
+    Idle,
+    Searching,
+    Results,
+    NoResults,
+    Error,
+    Downloading,
+}
+
+/// Dialog for searching the server's subtitle providers and downloading a subtitle to the item.
+pub(crate) struct SubtitleSearchDialog {
+    api_client: Arc<ApiClient>,
+    item_id: Uuid,
+    state: SearchState,
+    results: FactoryVecDeque<SubtitleSearchResult>,
+}
+
+pub(crate) struct SubtitleSearchDialogInit {
+    pub(crate) api_client: Arc<ApiClient>,
+    pub(crate) item_id: Uuid,
+    /// Three-letter (ISO 639-2) language code to search for initially
+    pub(crate) language: String,
+}
+
+#[derive(Debug)]
+pub(crate) enum SubtitleSearchDialogInput {
+    Search,
+    Download(String),
+}
+
+#[derive(Debug)]
+pub(crate) enum SubtitleSearchDialogOutput {
+    Downloaded,
+}
+
+#[derive(Debug)]
+pub(crate) enum SubtitleSearchDialogCommandOutput {
+    SearchResults(anyhow::Result<Vec<RemoteSubtitleInfo>>),
+    Downloaded(anyhow::Result<()>),
+}
+
+#[relm4::component(pub(crate))]
+impl Component for SubtitleSearchDialog {
+    type Init = SubtitleSearchDialogInit;
+    type Input = SubtitleSearchDialogInput;
+    type Output = SubtitleSearchDialogOutput;
+    type CommandOutput = SubtitleSearchDialogCommandOutput;
+
+    view! {
+        adw::Window {
+            set_application: Some(&relm4::main_application()),
+            set_transient_for: get_main_window().as_ref(),
+            set_title: Some(tr!("vp-subtitle-search-title")),
+            set_modal: true,
+            set_default_width: 600,
+            set_default_height: 600,
+            set_visible: true,
+
+            #[wrap(Some)]
+            set_content = &adw::ToolbarView {
+                add_top_bar = &adw::HeaderBar {},
+
+                #[wrap(Some)]
+                set_content = &gtk::ScrolledWindow {
+                    set_hscrollbar_policy: gtk::PolicyType::Never,
+
+                    adw::Clamp {
+                        set_margin_all: 20,
+
+                        gtk::Box {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_spacing: 20,
+
+                            adw::PreferencesGroup {
+                                #[name = "language_entry"]
+                                adw::EntryRow {
+                                    set_title: tr!("vp-subtitle-search-language"),
+                                    set_show_apply_button: true,
+                                    #[watch]
+                                    set_editable: !matches!(
+                                        model.state,
+                                        SearchState::Searching | SearchState::Downloading,
+                                    ),
+
+                                    connect_apply[sender] => move |_| {
+                                        sender.input(SubtitleSearchDialogInput::Search);
+                                    },
+                                },
+                            },
+
+                            gtk::Spinner {
+                                set_spinning: true,
+                                set_height_request: 32,
+                                #[watch]
+                                set_visible: matches!(
+                                    model.state,
+                                    SearchState::Searching | SearchState::Downloading,
+                                ),
+                            },
+
+                            adw::StatusPage {
+                                set_icon_name: Some("closed-captioning"),
+                                #[watch]
+                                set_title: &match model.state {
+                                    SearchState::Error => tr!("vp-subtitle-search-error").to_string(),
+                                    _ => tr!("vp-subtitle-search-no-results").to_string(),
+                                },
+                                #[watch]
+                                set_visible: matches!(
+                                    model.state,
+                                    SearchState::NoResults | SearchState::Error,
+                                ),
+                            },
+
+                            #[local_ref]
+                            results_list -> gtk::ListBox {
+                                add_css_class: "boxed-list",
+                                set_selection_mode: gtk::SelectionMode::None,
+                                #[watch]
+                                set_visible: matches!(
+                                    model.state,
+                                    SearchState::Results | SearchState::Downloading,
+                                ),
+                                #[watch]
+                                set_sensitive: model.state == SearchState::Results,
+                            },
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let SubtitleSearchDialogInit {
+            api_client,
+            item_id,
+            language,
+        } = init;
+
+        let results = FactoryVecDeque::builder()
+            .launch(gtk::ListBox::default())
+            .forward(sender.input_sender(), |output| match output {
+                SubtitleSearchResultOutput::Download(id) => SubtitleSearchDialogInput::Download(id),
+            });
+
+        let model = SubtitleSearchDialog {
+            api_client,
+            item_id,
+            state: SearchState::Idle,
+            results,
+        };
+
+        let results_list = model.results.widget();
+        let widgets = view_output!();
+
+        widgets.language_entry.set_text(&language);
+        widgets.language_entry.grab_focus();
+        if !language.is_empty() {
+            sender.input(SubtitleSearchDialogInput::Search);
+        }
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update_with_view(
+        &mut self,
+        widgets: &mut Self::Widgets,
+        message: Self::Input,
+        sender: ComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            SubtitleSearchDialogInput::Search => {
+                let language = widgets.language_entry.text().trim().to_lowercase();
+                if language.is_empty() {
+                    return;
+                }
+
+                self.state = SearchState::Searching;
+                self.results.guard().clear();
+
+                let api_client = self.api_client.clone();
+                let item_id = self.item_id;
+                sender.oneshot_command(async move {
+                    SubtitleSearchDialogCommandOutput::SearchResults(
+                        api_client
+                            .search_remote_subtitles(&item_id, &language)
+                            .await,
+                    )
+                });
+            }
+            SubtitleSearchDialogInput::Download(subtitle_id) => {
+                self.state = SearchState::Downloading;
+
+                let api_client = self.api_client.clone();
+                let item_id = self.item_id;
+                sender.oneshot_command(async move {
+                    SubtitleSearchDialogCommandOutput::Downloaded(
+                        api_client
+                            .download_remote_subtitles(&item_id, &subtitle_id)
+                            .await,
+                    )
+                });
+            }
+        }
+
+        self.update_view(widgets, sender);
+    }
+
+    fn update_cmd(
+        &mut self,
+        message: Self::CommandOutput,
+        sender: ComponentSender<Self>,
+        root: &Self::Root,
+    ) {
+        match message {
+            SubtitleSearchDialogCommandOutput::SearchResults(Ok(results)) => {
+                self.state = if results.is_empty() {
+                    SearchState::NoResults
+                } else {
+                    SearchState::Results
+                };
+
+                let mut guard = self.results.guard();
+                for result in results {
+                    guard.push_back(result);
+                }
+            }
+            SubtitleSearchDialogCommandOutput::SearchResults(Err(err)) => {
+                warn!("Error searching for subtitles: {err}");
+                self.state = SearchState::Error;
+            }
+            SubtitleSearchDialogCommandOutput::Downloaded(Ok(())) => {
+                sender
+                    .output(SubtitleSearchDialogOutput::Downloaded)
+                    .unwrap();
+                root.close();
+            }
+            SubtitleSearchDialogCommandOutput::Downloaded(Err(err)) => {
+                warn!("Error downloading subtitles: {err}");
+                APP_BROKER.send(AppInput::Toast(
+                    tr!("vp-subtitle-search-download-error").into(),
+                    None,
+                ));
+                self.state = SearchState::Results;
+            }
+        }
+    }
+}
+
+/// A single subtitle search result, which can be downloaded to the item.
+#[derive(Debug)]
+struct SubtitleSearchResult {
+    info: RemoteSubtitleInfo,
+}
+
+#[derive(Debug)]
+enum SubtitleSearchResultOutput {
+    Download(String),
+}
+
+#[relm4::factory]
+impl FactoryComponent for SubtitleSearchResult {
+    type Init = RemoteSubtitleInfo;
+    type Input = ();
+    type Output = SubtitleSearchResultOutput;
+    type CommandOutput = ();
+    type ParentWidget = gtk::ListBox;
+
+    view! {
+        adw::ActionRow {
+            set_title: self.info.name.as_deref().unwrap_or(&self.info.id),
+            set_title_lines: 2,
+            set_use_markup: false,
+            set_subtitle: &self.subtitle(),
+
+            add_suffix = &gtk::Image {
+                set_icon_name: Some("check-round-outline-symbolic"),
+                set_tooltip_text: Some(tr!("vp-subtitle-search-hash-match")),
+                set_visible: self.info.is_hash_match == Some(true),
+            },
+
+            add_suffix = &gtk::Button {
+                set_icon_name: "folder-download-symbolic",
+                set_tooltip_text: Some(tr!("vp-subtitle-search-download")),
+                set_valign: gtk::Align::Center,
+                add_css_class: "flat",
+                connect_clicked[sender, id = self.info.id.clone()] => move |_| {
+                    sender
+                        .output(SubtitleSearchResultOutput::Download(id.clone()))
+                        .unwrap();
+                },
+            },
+        }
+    }
+
+    fn init_model(info: Self::Init, _index: &Self::Index, _sender: FactorySender<Self>) -> Self {
+        Self { info }
+    }
+}
+
+impl SubtitleSearchResult {
+    fn subtitle(&self) -> String {
+        let info = &self.info;
+        let download_count = info
+            .download_count
+            .map(|count| tr!("vp-subtitle-search-downloads", { "count" => count }).to_string());
+
+        [
+            info.provider_name.clone(),
+            info.format.clone(),
+            info.author.clone(),
+            download_count,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+}
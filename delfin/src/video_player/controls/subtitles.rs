@@ -1,20 +1,31 @@
-use std::{cell::RefCell, sync::Arc};
+use std::{cell::RefCell, collections::HashSet, path::Path, sync::Arc};
 
 use gtk::prelude::*;
 use jellyfin_api::types::BaseItemDto;
 use relm4::{
     actions::{ActionGroupName, ActionName, RelmAction, RelmActionGroup},
-    gtk::{self, gio},
-    Component, ComponentParts, ComponentSender,
+    gtk::{self, gdk, gio},
+    Component, ComponentController, ComponentParts, ComponentSender, Controller,
 };
 use tracing::warn;
+use uuid::Uuid;
 
+use super::subtitle_search::{
+    SubtitleSearchDialog, SubtitleSearchDialogInit, SubtitleSearchDialogOutput,
+};
 use crate::{
     app::{AppInput, APP_BROKER},
+    globals::CONFIG,
     jellyfin_api::api_client::ApiClient,
     tr,
-    utils::message_broker::ResettableMessageBroker,
-    video_player::backends::{SubtitleTrack, VideoPlayerBackend},
+    utils::{main_window::get_main_window, message_broker::ResettableMessageBroker},
+    video_player::{
+        backends::{SubtitleTrack, VideoPlayerBackend},
+        session::PlaybackChange,
+        sync_offsets::{get_sync_offsets, remember_subtitle_delay},
+        track_selection::{SubtitleTrackSelection, TrackSelector},
+        VideoPlayerInput, VIDEO_PLAYER_BROKER,
+    },
 };
 
 pub static SUBTITLES_BROKER: ResettableMessageBroker<SubtitlesInput> =
@@ -28,6 +39,24 @@ relm4::new_stateful_action!(
     Option<i32>,
     Option<i32>
 );
+relm4::new_stateful_action!(
+    SelectedSecondarySubtitleAction,
+    SubtitleActionGroup,
+    "selected_secondary_subtitle",
+    Option<i32>,
+    Option<i32>
+);
+relm4::new_stateless_action!(FindSubtitlesAction, SubtitleActionGroup, "find_subtitles");
+relm4::new_stateless_action!(
+    OpenSubtitleFileAction,
+    SubtitleActionGroup,
+    "open_subtitle_file"
+);
+
+const SUBTITLE_FILE_EXTENSIONS: [&str; 3] = ["srt", "ass", "vtt"];
+
+// Used when the user has no preferred subtitle language
+const DEFAULT_SEARCH_LANGUAGE: &str = "eng";
 
 #[derive(Debug)]
 pub struct ExternalSubtitleTrack {
@@ -41,6 +70,23 @@ pub struct Subtitles {
     menu: gio::Menu,
     subtitles_available: bool,
     previous_track: Option<usize>,
+    subtitle_tracks: Vec<SubtitleTrack>,
+    track_selector: Option<TrackSelector>,
+    initial_track_selected: bool,
+    selected_track: Option<usize>,
+    initial_secondary_track_selected: bool,
+    selected_secondary_track: Option<usize>,
+    api_client: Option<Arc<ApiClient>>,
+    item_id: Option<Uuid>,
+    // Subtitle delay in milliseconds
+    delay: i64,
+    // URLs of external subtitles already added to the player, so they aren't added again when
+    // reloading external subtitles
+    external_subtitle_urls: HashSet<String>,
+    // After downloading subtitles or opening a subtitle file, the number of tracks there were
+    // before the new subtitles were added, so they can be selected once the player has loaded them
+    track_count_before_added: Option<usize>,
+    search_dialog: Option<Controller<SubtitleSearchDialog>>,
 }
 
 #[derive(Debug)]
@@ -50,7 +96,18 @@ pub enum SubtitlesInput {
         item: Box<BaseItemDto>,
     },
     SubtitlesUpdated(Vec<SubtitleTrack>),
+    SetTrackSelector(TrackSelector),
+    TrackSelected(Option<usize>),
+    SecondaryTrackSelected(Option<usize>),
     ToggleSubtitles,
+    AdjustDelay(i64),
+    FindSubtitles,
+    SubtitlesDownloaded,
+    OpenSubtitleFile,
+    AddSubtitleFile {
+        uri: String,
+        name: String,
+    },
 }
 
 #[derive(Debug)]
@@ -77,8 +134,6 @@ impl Component for Subtitles {
                 {"subtitlesAvailable" => model.subtitles_available.to_string()},
             )),
             #[watch]
-            set_sensitive: model.subtitles_available,
-            #[watch]
             set_has_tooltip: !model.subtitles_available,
         }
     }
@@ -93,13 +148,30 @@ impl Component for Subtitles {
             menu: gio::Menu::new(),
             subtitles_available: false,
             previous_track: None,
+            subtitle_tracks: vec![],
+            track_selector: None,
+            initial_track_selected: false,
+            selected_track: None,
+            initial_secondary_track_selected: false,
+            selected_secondary_track: None,
+            api_client: None,
+            item_id: None,
+            delay: 0,
+            external_subtitle_urls: HashSet::new(),
+            track_count_before_added: None,
+            search_dialog: None,
         };
 
+        model.build_menu();
+
         model
             .video_player
             .borrow_mut()
-            .connect_subtitle_tracks_updated(Box::new(move |tracks| {
-                sender.input(SubtitlesInput::SubtitlesUpdated(tracks.clone()));
+            .connect_subtitle_tracks_updated(Box::new({
+                let sender = sender.clone();
+                move |tracks| {
+                    sender.input(SubtitlesInput::SubtitlesUpdated(tracks.clone()));
+                }
             }));
 
         let widgets = view_output!();
@@ -107,16 +179,46 @@ impl Component for Subtitles {
         let selected_subtitle_action: RelmAction<SelectedSubtitleAction> =
             RelmAction::new_stateful_with_target_value(&None, {
                 let video_player = model.video_player.clone();
+                let sender = sender.clone();
+                move |_, state, value: Option<i32>| {
+                    *state = value;
+                    let subtitle_track = value.map(|id| id as usize);
+                    video_player.borrow().set_subtitle_track(subtitle_track);
+                    sender.input(SubtitlesInput::TrackSelected(subtitle_track));
+                }
+            });
+
+        let selected_secondary_subtitle_action: RelmAction<SelectedSecondarySubtitleAction> =
+            RelmAction::new_stateful_with_target_value(&None, {
+                let video_player = model.video_player.clone();
+                let sender = sender.clone();
                 move |_, state, value: Option<i32>| {
                     *state = value;
+                    let subtitle_track = value.map(|id| id as usize);
                     video_player
                         .borrow()
-                        .set_subtitle_track(value.map(|id| id as usize));
+                        .set_secondary_subtitle_track(subtitle_track);
+                    sender.input(SubtitlesInput::SecondaryTrackSelected(subtitle_track));
                 }
             });
 
+        let find_subtitles_action: RelmAction<FindSubtitlesAction> = RelmAction::new_stateless({
+            let sender = sender.clone();
+            move |_| {
+                sender.input(SubtitlesInput::FindSubtitles);
+            }
+        });
+
+        let open_subtitle_file_action: RelmAction<OpenSubtitleFileAction> =
+            RelmAction::new_stateless(move |_| {
+                sender.input(SubtitlesInput::OpenSubtitleFile);
+            });
+
         let mut group = RelmActionGroup::<SubtitleActionGroup>::new();
         group.add_action(selected_subtitle_action);
+        group.add_action(selected_secondary_subtitle_action);
+        group.add_action(find_subtitles_action);
+        group.add_action(open_subtitle_file_action);
         group.register_for_widget(root);
 
         ComponentParts { model, widgets }
@@ -133,36 +235,49 @@ impl Component for Subtitles {
             SubtitlesInput::Reset { api_client, item } => {
                 self.subtitles_available = false;
                 self.previous_track = None;
-                Subtitles::load_external_subtitles(&sender, &api_client, &item);
+                self.subtitle_tracks.clear();
+                self.track_selector = None;
+                self.initial_track_selected = false;
+                self.selected_track = None;
+                self.initial_secondary_track_selected = false;
+                self.selected_secondary_track = None;
+                self.external_subtitle_urls.clear();
+                self.track_count_before_added = None;
+                self.build_menu();
+
+                self.item_id = item.id;
+                self.delay = match item.id {
+                    Some(item_id) if CONFIG.read().video_player.remember_sync_offsets => {
+                        get_sync_offsets(&item_id).subtitle
+                    }
+                    _ => 0,
+                };
+                self.video_player.borrow().set_subtitle_delay(self.delay);
+
+                if let Some(item_id) = item.id {
+                    Subtitles::load_external_subtitles(&sender, &api_client, item_id);
+                }
+                self.api_client = Some(api_client);
+            }
+            SubtitlesInput::SetTrackSelector(track_selector) => {
+                self.track_selector = Some(track_selector);
+                self.select_initial_track(root);
+            }
+            SubtitlesInput::TrackSelected(subtitle_track) => {
+                self.track_selected(subtitle_track);
+            }
+            SubtitlesInput::SecondaryTrackSelected(subtitle_track) => {
+                self.secondary_track_selected(subtitle_track);
             }
             SubtitlesInput::SubtitlesUpdated(subtitle_streams) => {
                 if subtitle_streams.is_empty() {
                     return;
                 }
 
-                self.subtitles_available = true;
-
-                // Build updated subtitle tracks menu
-                let subs_menu = gio::Menu::new();
-                subtitle_streams
-                    .iter()
-                    .map(|subtitle_stream| {
-                        RelmAction::<SelectedSubtitleAction>::to_menu_item_with_target_value(
-                            &subtitle_stream.name,
-                            &Some(subtitle_stream.id as i32),
-                        )
-                    })
-                    .for_each(|menu_item| subs_menu.append_item(&menu_item));
-                subs_menu.append_item(
-                    &RelmAction::<SelectedSubtitleAction>::to_menu_item_with_target_value(
-                        tr!("vp-subtitle-track-off"),
-                        &None,
-                    ),
-                );
+                self.subtitle_tracks.clone_from(&subtitle_streams);
 
-                self.menu.remove_all();
-                self.menu
-                    .append_section(Some(tr!("vp-subtitle-track-menu")), &subs_menu);
+                self.subtitles_available = true;
+                self.build_menu();
 
                 // Select current subtitle track in menu
                 if let Some(current_subtitle_track) =
@@ -178,6 +293,27 @@ impl Component for Subtitles {
                     )
                     .expect("Error selecting current subtitle track.");
                 }
+
+                // Select current secondary subtitle track in menu
+                if let Some(current_secondary_track) = self
+                    .video_player
+                    .borrow()
+                    .current_secondary_subtitle_track()
+                {
+                    root.activate_action(
+                        &format!(
+                            "{}.{}",
+                            SubtitleActionGroup::NAME,
+                            SelectedSecondarySubtitleAction::NAME
+                        ),
+                        Some(&Some(current_secondary_track as i32).to_variant()),
+                    )
+                    .expect("Error selecting current secondary subtitle track.");
+                }
+
+                self.select_initial_track(root);
+                self.select_initial_secondary_track(root);
+                self.select_added_track(root);
             }
             SubtitlesInput::ToggleSubtitles => 'msg_block: {
                 if !self.subtitles_available {
@@ -193,13 +329,83 @@ impl Component for Subtitles {
                 {
                     self.previous_track = Some(current_subtitle_track);
                     self.video_player.borrow().set_subtitle_track(None);
+                    self.track_selected(None);
                 } else if let Some(previous_track) = self.previous_track {
                     self.video_player
                         .borrow()
                         .set_subtitle_track(Some(previous_track));
+                    self.track_selected(Some(previous_track));
                 } else {
                     self.video_player.borrow().set_subtitle_track(Some(0));
+                    self.track_selected(Some(0));
+                }
+            }
+            SubtitlesInput::AdjustDelay(amount) => {
+                self.delay += amount;
+                self.video_player.borrow().set_subtitle_delay(self.delay);
+                APP_BROKER.send(AppInput::Toast(
+                    tr!("vp-subtitle-delay-toast", { "delay" => self.delay }).into(),
+                    Some(1),
+                ));
+
+                if let Some(item_id) = self.item_id {
+                    if CONFIG.read().video_player.remember_sync_offsets {
+                        remember_subtitle_delay(&item_id, self.delay);
+                    }
+                }
+            }
+            SubtitlesInput::FindSubtitles => {
+                let (Some(api_client), Some(item_id)) = (&self.api_client, self.item_id) else {
+                    return;
+                };
+
+                let language = CONFIG
+                    .read()
+                    .video_player
+                    .subtitles
+                    .subtitle_language
+                    .clone()
+                    .or_else(|| {
+                        self.track_selector
+                            .as_ref()
+                            .and_then(|track_selector| track_selector.subtitle_language())
+                            .map(ToOwned::to_owned)
+                    })
+                    .unwrap_or_else(|| DEFAULT_SEARCH_LANGUAGE.to_owned());
+
+                self.search_dialog = Some(
+                    SubtitleSearchDialog::builder()
+                        .launch(SubtitleSearchDialogInit {
+                            api_client: api_client.clone(),
+                            item_id,
+                            language,
+                        })
+                        .forward(sender.input_sender(), |output| match output {
+                            SubtitleSearchDialogOutput::Downloaded => {
+                                SubtitlesInput::SubtitlesDownloaded
+                            }
+                        }),
+                );
+            }
+            SubtitlesInput::SubtitlesDownloaded => {
+                let (Some(api_client), Some(item_id)) = (&self.api_client, self.item_id) else {
+                    return;
+                };
+
+                self.track_count_before_added = Some(self.subtitle_tracks.len());
+                Subtitles::load_external_subtitles(&sender, api_client, item_id);
+            }
+            SubtitlesInput::OpenSubtitleFile => {
+                Subtitles::open_subtitle_file(&sender);
+            }
+            SubtitlesInput::AddSubtitleFile { uri, name } => {
+                // Already added, mpv would otherwise list the file twice
+                if !self.external_subtitle_urls.insert(uri.clone()) {
+                    return;
                 }
+
+                self.track_count_before_added = Some(self.subtitle_tracks.len());
+                self.video_player.borrow().add_subtitle_track(&uri, &name);
             }
         }
 
@@ -214,25 +420,264 @@ impl Component for Subtitles {
     ) {
         match message {
             SubtitlesCommandOutput::ExternalSubtitlesLoaded(Some(external_subtitles)) => {
+                let mut added = false;
                 for sub in external_subtitles {
+                    if !self.external_subtitle_urls.insert(sub.url.clone()) {
+                        continue;
+                    }
                     self.video_player
                         .borrow()
                         .add_subtitle_track(&sub.url, &sub.name);
+                    added = true;
+                }
+                if !added {
+                    self.track_count_before_added = None;
                 }
             }
-            SubtitlesCommandOutput::ExternalSubtitlesLoaded(None) => {}
+            SubtitlesCommandOutput::ExternalSubtitlesLoaded(None) => {
+                self.track_count_before_added = None;
+            }
         }
     }
 }
 
 impl Subtitles {
+    fn build_menu(&self) {
+        self.menu.remove_all();
+
+        if self.subtitles_available {
+            let subs_menu = gio::Menu::new();
+            self.subtitle_tracks
+                .iter()
+                .map(|subtitle_track| {
+                    RelmAction::<SelectedSubtitleAction>::to_menu_item_with_target_value(
+                        &subtitle_track.name,
+                        &Some(subtitle_track.id as i32),
+                    )
+                })
+                .for_each(|menu_item| subs_menu.append_item(&menu_item));
+            subs_menu.append_item(
+                &RelmAction::<SelectedSubtitleAction>::to_menu_item_with_target_value(
+                    tr!("vp-subtitle-track-off"),
+                    &None,
+                ),
+            );
+            self.menu
+                .append_section(Some(tr!("vp-subtitle-track-menu")), &subs_menu);
+        }
+
+        if self.subtitles_available && self.video_player.borrow().supports_secondary_subtitles() {
+            let secondary_subs_menu = gio::Menu::new();
+            self.subtitle_tracks
+                .iter()
+                .map(|subtitle_track| {
+                    RelmAction::<SelectedSecondarySubtitleAction>::to_menu_item_with_target_value(
+                        &subtitle_track.name,
+                        &Some(subtitle_track.id as i32),
+                    )
+                })
+                .for_each(|menu_item| secondary_subs_menu.append_item(&menu_item));
+            secondary_subs_menu.append_item(
+                &RelmAction::<SelectedSecondarySubtitleAction>::to_menu_item_with_target_value(
+                    tr!("vp-subtitle-track-off"),
+                    &None,
+                ),
+            );
+            self.menu.append_section(
+                Some(tr!("vp-secondary-subtitle-track-menu")),
+                &secondary_subs_menu,
+            );
+        }
+
+        let search_menu = gio::Menu::new();
+        search_menu.append_item(&RelmAction::<FindSubtitlesAction>::to_menu_item(tr!(
+            "vp-subtitle-search"
+        )));
+        search_menu.append_item(&RelmAction::<OpenSubtitleFileAction>::to_menu_item(tr!(
+            "vp-subtitle-open-file"
+        )));
+        self.menu.append_section(None, &search_menu);
+    }
+
+    // Pick the subtitle track based on the user's language preferences, once we know both the
+    // preferences and which tracks are available
+    fn select_initial_track(&mut self, root: &gtk::MenuButton) {
+        if self.initial_track_selected || self.subtitle_tracks.is_empty() {
+            return;
+        }
+        let Some(track_selector) = &self.track_selector else {
+            return;
+        };
+
+        let subtitle_track = match track_selector.subtitle_track(&self.subtitle_tracks) {
+            SubtitleTrackSelection::Track(id) => Some(id as i32),
+            SubtitleTrackSelection::Off => None,
+            // Wait for external subtitles to be added
+            SubtitleTrackSelection::NotLoaded => return,
+        };
+
+        self.initial_track_selected = true;
+        self.selected_track = subtitle_track.map(|id| id as usize);
+
+        root.activate_action(
+            &format!(
+                "{}.{}",
+                SubtitleActionGroup::NAME,
+                SelectedSubtitleAction::NAME
+            ),
+            Some(&subtitle_track.to_variant()),
+        )
+        .expect("Error selecting subtitle track.");
+    }
+
+    // Remember tracks picked by the user for the rest of the series, ignoring the menu being synced
+    // with the player and the initial track selection
+    fn track_selected(&mut self, subtitle_track: Option<usize>) {
+        if self.initial_track_selected && subtitle_track != self.selected_track {
+            if let Some(track_selector) = &self.track_selector {
+                track_selector.remember_subtitle_track(&self.subtitle_tracks, subtitle_track);
+            }
+        }
+        if subtitle_track != self.selected_track {
+            let stream_index = match (&self.track_selector, subtitle_track) {
+                (Some(track_selector), Some(subtitle_track)) => {
+                    track_selector.subtitle_stream_index(&self.subtitle_tracks, subtitle_track)
+                }
+                _ => None,
+            };
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ReportPlaybackChange(
+                PlaybackChange::SubtitleTrack(stream_index),
+            ));
+        }
+        self.selected_track = subtitle_track;
+    }
+
+    // Show the user's preferred secondary subtitles, once the main subtitle track has been picked
+    // so the same track isn't shown twice
+    fn select_initial_secondary_track(&mut self, root: &gtk::MenuButton) {
+        if self.initial_secondary_track_selected || !self.initial_track_selected {
+            return;
+        }
+
+        let Some(language) = CONFIG
+            .read()
+            .video_player
+            .subtitles
+            .secondary_language
+            .clone()
+        else {
+            self.initial_secondary_track_selected = true;
+            return;
+        };
+
+        // External subtitles may still be loading, so keep looking when the tracks are updated
+        let Some(track) = self.subtitle_tracks.iter().find(|track| {
+            Some(track.id) != self.selected_track
+                && track
+                    .language
+                    .as_deref()
+                    .is_some_and(|track_language| track_language.eq_ignore_ascii_case(&language))
+        }) else {
+            return;
+        };
+
+        self.initial_secondary_track_selected = true;
+        self.selected_secondary_track = Some(track.id);
+
+        root.activate_action(
+            &format!(
+                "{}.{}",
+                SubtitleActionGroup::NAME,
+                SelectedSecondarySubtitleAction::NAME
+            ),
+            Some(&Some(track.id as i32).to_variant()),
+        )
+        .expect("Error selecting secondary subtitle track.");
+    }
+
+    // Remember the language of secondary subtitles picked by the user for future playback
+    fn secondary_track_selected(&mut self, subtitle_track: Option<usize>) {
+        if subtitle_track != self.selected_secondary_track {
+            self.initial_secondary_track_selected = true;
+
+            let language = subtitle_track.and_then(|id| {
+                self.subtitle_tracks
+                    .iter()
+                    .find(|track| track.id == id)
+                    .and_then(|track| track.language.clone())
+            });
+
+            let mut config = CONFIG.write();
+            if config.video_player.subtitles.secondary_language != language {
+                config.video_player.subtitles.secondary_language = language;
+                config.save().expect("Error saving config");
+            }
+        }
+        self.selected_secondary_track = subtitle_track;
+    }
+
+    // Select subtitles the user added once the player has loaded them
+    fn select_added_track(&mut self, root: &gtk::MenuButton) {
+        let Some(previous_track_count) = self.track_count_before_added else {
+            return;
+        };
+        if self.subtitle_tracks.len() <= previous_track_count {
+            return;
+        }
+        self.track_count_before_added = None;
+
+        // External subtitles are added after all other tracks
+        let Some(added_track) = self.subtitle_tracks.iter().map(|track| track.id).max() else {
+            return;
+        };
+
+        root.activate_action(
+            &format!(
+                "{}.{}",
+                SubtitleActionGroup::NAME,
+                SelectedSubtitleAction::NAME
+            ),
+            Some(&Some(added_track as i32).to_variant()),
+        )
+        .expect("Error selecting downloaded subtitle track.");
+    }
+
+    fn open_subtitle_file(sender: &ComponentSender<Self>) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(tr!("vp-subtitle-open-file-filter")));
+        for extension in SUBTITLE_FILE_EXTENSIONS {
+            filter.add_suffix(extension);
+        }
+
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+
+        let dialog = gtk::FileDialog::builder()
+            .title(tr!("vp-subtitle-open-file-title"))
+            .modal(true)
+            .filters(&filters)
+            .default_filter(&filter)
+            .build();
+
+        dialog.open(get_main_window().as_ref(), None::<&gio::Cancellable>, {
+            let sender = sender.clone();
+            move |res| match res {
+                Ok(file) => {
+                    if let Some(input) = add_subtitle_file_input(&file) {
+                        sender.input(input);
+                    }
+                }
+                Err(err) if err.matches(gtk::DialogError::Dismissed) => {}
+                Err(err) => warn!("Error opening subtitle file: {err}"),
+            }
+        });
+    }
+
     fn load_external_subtitles(
         sender: &ComponentSender<Self>,
         api_client: &Arc<ApiClient>,
-        item: &BaseItemDto,
+        item_id: Uuid,
     ) {
-        let Some(item_id) = item.id else { return };
-
         sender.oneshot_command({
             let api_client = api_client.clone();
             async move {
@@ -285,3 +730,59 @@ impl Subtitles {
         });
     }
 }
+
+/// Handles subtitle files being dropped onto the video.
+pub(crate) fn subtitle_file_drop_target() -> gtk::DropTarget {
+    let drop_target = gtk::DropTarget::new(gdk::FileList::static_type(), gdk::DragAction::COPY);
+
+    drop_target.connect_drop(|_, value, _, _| {
+        let Ok(files) = value.get::<gdk::FileList>() else {
+            return false;
+        };
+
+        let mut accepted = false;
+        for input in files.files().iter().filter_map(add_subtitle_file_input) {
+            SUBTITLES_BROKER.send(input);
+            accepted = true;
+        }
+        accepted
+    });
+
+    drop_target
+}
+
+// Input for adding a local subtitle file to the current playback, if it's a supported format
+fn add_subtitle_file_input(file: &gio::File) -> Option<SubtitlesInput> {
+    let path = file.path()?;
+    if !is_subtitle_file(&path) {
+        return None;
+    }
+
+    let name = path.file_name()?.to_string_lossy().into_owned();
+    Some(SubtitlesInput::AddSubtitleFile {
+        uri: file.uri().into(),
+        name,
+    })
+}
+
+fn is_subtitle_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| {
+            SUBTITLE_FILE_EXTENSIONS.contains(&extension.to_lowercase().as_str())
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_subtitle_file() {
+        assert!(is_subtitle_file(Path::new("/home/user/Episode 1.srt")));
+        assert!(is_subtitle_file(Path::new("/home/user/episode.en.ASS")));
+        assert!(is_subtitle_file(Path::new("episode.vtt")));
+        assert!(!is_subtitle_file(Path::new("/home/user/episode.mkv")));
+        assert!(!is_subtitle_file(Path::new("/home/user/srt")));
+    }
+}
@@ -0,0 +1,255 @@
+use std::{cell::RefCell, sync::Arc};
+
+use gtk::{gio, prelude::*};
+use relm4::{
+    actions::{RelmAction, RelmActionGroup},
+    gtk,
+    prelude::ComponentParts,
+    SimpleComponent,
+};
+
+use crate::{
+    app::{AppInput, APP_BROKER},
+    tr,
+    utils::message_broker::ResettableMessageBroker,
+    video_player::backends::{VideoAspectRatio, VideoPlayerBackend},
+};
+
+// How much zoom changes by, where each step of 1 doubles the size of the video
+const ZOOM_STEP: f64 = 0.25;
+const MAX_ZOOM: f64 = 2.0;
+
+pub static VIDEO_SETTINGS_BROKER: ResettableMessageBroker<VideoSettingsInput> =
+    ResettableMessageBroker::new();
+
+#[derive(Debug)]
+pub(super) struct VideoSettings {
+    video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
+    menu: gio::Menu,
+    aspect_ratio_action: gio::SimpleAction,
+    panscan_action: gio::SimpleAction,
+    zoom: f64,
+}
+
+#[derive(Debug)]
+pub enum VideoSettingsInput {
+    CycleAspectRatio,
+    ZoomIn,
+    ZoomOut,
+    TogglePanscan,
+    Reset,
+}
+
+#[relm4::component(pub(super))]
+impl SimpleComponent for VideoSettings {
+    type Init = Arc<RefCell<dyn VideoPlayerBackend>>;
+    type Input = VideoSettingsInput;
+    type Output = ();
+
+    view! {
+        gtk::MenuButton {
+            set_menu_model: Some(&model.menu),
+            set_icon_name: "zoom-fit-best-symbolic",
+            set_tooltip_text: Some(tr!("vp-video-settings-tooltip")),
+            set_focus_on_click: false,
+            set_direction: gtk::ArrowType::Up,
+        }
+    }
+
+    fn init(
+        video_player: Self::Init,
+        root: Self::Root,
+        sender: relm4::prelude::ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let aspect_ratio_action: RelmAction<AspectRatioAction> =
+            RelmAction::new_stateful_with_target_value(&0, {
+                let video_player = video_player.clone();
+                move |_, state, value: u32| {
+                    *state = value;
+                    video_player
+                        .borrow()
+                        .set_aspect_ratio(VideoAspectRatio::ALL[value as usize]);
+                }
+            });
+
+        let panscan_action: RelmAction<PanscanAction> = RelmAction::new_stateful(&false, {
+            let video_player = video_player.clone();
+            move |_, state| {
+                *state = !*state;
+                video_player
+                    .borrow()
+                    .set_panscan(if *state { 1.0 } else { 0.0 });
+            }
+        });
+
+        let zoom_in_action: RelmAction<ZoomInAction> = RelmAction::new_stateless({
+            let sender = sender.clone();
+            move |_| {
+                sender.input(VideoSettingsInput::ZoomIn);
+            }
+        });
+
+        let zoom_out_action: RelmAction<ZoomOutAction> = RelmAction::new_stateless({
+            let sender = sender.clone();
+            move |_| {
+                sender.input(VideoSettingsInput::ZoomOut);
+            }
+        });
+
+        let reset_action: RelmAction<ResetAction> = RelmAction::new_stateless(move |_| {
+            sender.input(VideoSettingsInput::Reset);
+        });
+
+        let model = Self {
+            video_player,
+            menu: gio::Menu::new(),
+            aspect_ratio_action: gio::SimpleAction::from(aspect_ratio_action.clone()),
+            panscan_action: gio::SimpleAction::from(panscan_action.clone()),
+            zoom: 0.0,
+        };
+
+        let aspect_ratio_menu = gio::Menu::new();
+        VideoAspectRatio::ALL
+            .iter()
+            .enumerate()
+            .map(|(index, aspect_ratio)| {
+                RelmAction::<AspectRatioAction>::to_menu_item_with_target_value(
+                    &aspect_ratio_label(aspect_ratio),
+                    &(index as u32),
+                )
+            })
+            .for_each(|menu_item| aspect_ratio_menu.append_item(&menu_item));
+        model.menu.append_section(
+            Some(tr!("vp-video-settings-aspect-ratio")),
+            &aspect_ratio_menu,
+        );
+
+        let zoom_menu = gio::Menu::new();
+        zoom_menu.append_item(&RelmAction::<ZoomInAction>::to_menu_item(tr!(
+            "vp-video-settings-zoom-in"
+        )));
+        zoom_menu.append_item(&RelmAction::<ZoomOutAction>::to_menu_item(tr!(
+            "vp-video-settings-zoom-out"
+        )));
+        zoom_menu.append_item(&RelmAction::<PanscanAction>::to_menu_item(tr!(
+            "vp-video-settings-panscan"
+        )));
+        zoom_menu.append_item(&RelmAction::<ResetAction>::to_menu_item(tr!(
+            "vp-video-settings-reset"
+        )));
+        model
+            .menu
+            .append_section(Some(tr!("vp-video-settings-zoom")), &zoom_menu);
+
+        let widgets = view_output!();
+
+        let mut group = RelmActionGroup::<VideoSettingsActionGroup>::new();
+        group.add_action(aspect_ratio_action);
+        group.add_action(panscan_action);
+        group.add_action(zoom_in_action);
+        group.add_action(zoom_out_action);
+        group.add_action(reset_action);
+        group.register_for_widget(&root);
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: relm4::prelude::ComponentSender<Self>) {
+        match message {
+            VideoSettingsInput::CycleAspectRatio => {
+                let index = self
+                    .aspect_ratio_action
+                    .state()
+                    .and_then(|state| state.get::<u32>())
+                    .unwrap_or(0) as usize;
+                let index = (index + 1) % VideoAspectRatio::ALL.len();
+                self.aspect_ratio_action
+                    .activate(Some(&(index as u32).to_variant()));
+                APP_BROKER.send(AppInput::Toast(
+                    tr!("vp-video-settings-aspect-ratio-toast", {
+                        "aspectRatio" => aspect_ratio_label(&VideoAspectRatio::ALL[index]),
+                    })
+                    .into(),
+                    Some(1),
+                ));
+            }
+            VideoSettingsInput::ZoomIn => self.set_zoom(self.zoom + ZOOM_STEP),
+            VideoSettingsInput::ZoomOut => self.set_zoom(self.zoom - ZOOM_STEP),
+            VideoSettingsInput::TogglePanscan => {
+                self.panscan_action.activate(None);
+                let panscan = self
+                    .panscan_action
+                    .state()
+                    .and_then(|state| state.get::<bool>())
+                    .unwrap_or(false);
+                APP_BROKER.send(AppInput::Toast(
+                    tr!("vp-video-settings-panscan-toast", {
+                        "enabled" => panscan.to_string(),
+                    })
+                    .into(),
+                    Some(1),
+                ));
+            }
+            VideoSettingsInput::Reset => {
+                self.aspect_ratio_action.activate(Some(&0u32.to_variant()));
+                if self
+                    .panscan_action
+                    .state()
+                    .and_then(|state| state.get::<bool>())
+                    .unwrap_or(false)
+                {
+                    self.panscan_action.activate(None);
+                }
+                self.set_zoom(0.0);
+            }
+        }
+    }
+}
+
+impl VideoSettings {
+    fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.clamp(-MAX_ZOOM, MAX_ZOOM);
+        self.video_player.borrow().set_zoom(self.zoom);
+        APP_BROKER.send(AppInput::Toast(
+            tr!("vp-video-settings-zoom-toast", {
+                "zoom" => format!("{:.0}", self.zoom.exp2() * 100.0),
+            })
+            .into(),
+            Some(1),
+        ));
+    }
+}
+
+fn aspect_ratio_label(aspect_ratio: &VideoAspectRatio) -> String {
+    match aspect_ratio {
+        VideoAspectRatio::Auto => tr!("vp-video-settings-aspect-ratio-auto").to_string(),
+        VideoAspectRatio::Standard => "4:3".into(),
+        VideoAspectRatio::Widescreen => "16:9".into(),
+        VideoAspectRatio::Cinemascope => "2.35:1".into(),
+    }
+}
+
+relm4::new_action_group!(VideoSettingsActionGroup, "video_settings_actions");
+relm4::new_stateful_action!(
+    AspectRatioAction,
+    VideoSettingsActionGroup,
+    "aspect_ratio",
+    u32,
+    u32
+);
+relm4::new_stateful_action!(PanscanAction, VideoSettingsActionGroup, "panscan", (), bool);
+relm4::new_stateless_action!(ZoomInAction, VideoSettingsActionGroup, "zoom_in");
+relm4::new_stateless_action!(ZoomOutAction, VideoSettingsActionGroup, "zoom_out");
+relm4::new_stateless_action!(ResetAction, VideoSettingsActionGroup, "reset");
+
+impl Clone for AspectRatioAction {
+    fn clone(&self) -> Self {
+        Self {}
+    }
+}
+
+impl Clone for PanscanAction {
+    fn clone(&self) -> Self {
+        Self {}
+    }
+}
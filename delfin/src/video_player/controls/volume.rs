@@ -1,13 +1,17 @@
 use std::{cell::RefCell, sync::Arc, time::Duration};
 
-use gtk::prelude::*;
-use relm4::{gtk, ComponentParts, SimpleComponent};
+use gtk::{gio, prelude::*};
+use relm4::{
+    actions::{RelmAction, RelmActionGroup},
+    gtk, ComponentParts, SimpleComponent,
+};
 
 use crate::{
-    globals::CONFIG,
+    app::{AppInput, APP_BROKER},
+    globals::{AUDIO_DEVICES, CONFIG},
     tr,
     utils::{debounce::Debounce, message_broker::ResettableMessageBroker},
-    video_player::backends::VideoPlayerBackend,
+    video_player::backends::{AudioDevice, VideoPlayerBackend},
 };
 
 pub static VOLUME_BROKER: ResettableMessageBroker<VolumeInput> = ResettableMessageBroker::new();
@@ -17,6 +21,11 @@ pub struct Volume {
     muted: bool,
     volume: f64,
     save_debounce: Debounce,
+    audio_devices: Vec<AudioDevice>,
+    menu: gio::Menu,
+    audio_device_action: gio::SimpleAction,
+    night_mode_action: gio::SimpleAction,
+    normalize_loudness_action: gio::SimpleAction,
 }
 
 #[derive(Debug)]
@@ -31,6 +40,11 @@ pub enum VolumeInput {
 
     SaveSettings,
     LoadSettings,
+
+    AudioDevicesUpdated(Vec<AudioDevice>),
+    // Sync the audio settings menu with the config
+    LoadAudioSettings,
+    ToggleNightMode,
 }
 
 #[relm4::component(pub)]
@@ -80,6 +94,19 @@ impl SimpleComponent for Volume {
                 } @volume_changed_handler,
             },
 
+            gtk::MenuButton {
+                set_menu_model: Some(&model.menu),
+                set_icon_name: "audio-speakers-symbolic",
+                set_tooltip_text: Some(tr!("vp-audio-settings-tooltip")),
+                set_focus_on_click: false,
+                set_direction: gtk::ArrowType::Up,
+                connect_active_notify[sender] => move |button| {
+                    if button.is_active() {
+                        sender.input(VolumeInput::LoadAudioSettings);
+                    }
+                },
+            },
+
             gtk::Separator { add_css_class: "spacer" },
         }
     }
@@ -101,15 +128,61 @@ impl SimpleComponent for Volume {
             }),
         );
 
+        let audio_device_action: RelmAction<AudioDeviceAction> =
+            RelmAction::new_stateful_with_target_value(
+                &config.video_player.audio_device.clone().unwrap_or_default(),
+                |_, state, name: String| {
+                    *state = name.clone();
+                    let mut config = CONFIG.write();
+                    // An empty name picks the audio device automatically
+                    config.video_player.audio_device = Some(name).filter(|name| !name.is_empty());
+                    config.save().expect("Error saving audio device");
+                },
+            );
+
+        let night_mode_action: RelmAction<NightModeAction> =
+            RelmAction::new_stateful(&config.video_player.night_mode, |_, state| {
+                *state = !*state;
+                let mut config = CONFIG.write();
+                config.video_player.night_mode = *state;
+                config.save().expect("Error saving night mode");
+            });
+
+        let normalize_loudness_action: RelmAction<NormalizeLoudnessAction> =
+            RelmAction::new_stateful(&config.video_player.normalize_loudness, |_, state| {
+                *state = !*state;
+                let mut config = CONFIG.write();
+                config.video_player.normalize_loudness = *state;
+                config.save().expect("Error saving loudness normalization");
+            });
+
         let model = Volume {
             video_player: video_player.clone(),
             muted: config.video_player.muted,
             volume: config.video_player.volume,
             save_debounce,
+            audio_devices: vec![],
+            menu: gio::Menu::new(),
+            audio_device_action: gio::SimpleAction::from(audio_device_action.clone()),
+            night_mode_action: gio::SimpleAction::from(night_mode_action.clone()),
+            normalize_loudness_action: gio::SimpleAction::from(normalize_loudness_action.clone()),
         };
 
         let widgets = view_output!();
 
+        let mut group = RelmActionGroup::<VolumeActionGroup>::new();
+        group.add_action(audio_device_action);
+        group.add_action(night_mode_action);
+        group.add_action(normalize_loudness_action);
+        group.register_for_widget(&root);
+
+        AUDIO_DEVICES.subscribe(sender.input_sender(), |audio_devices| {
+            VolumeInput::AudioDevicesUpdated(audio_devices.clone())
+        });
+        sender.input(VolumeInput::AudioDevicesUpdated(
+            AUDIO_DEVICES.read().clone(),
+        ));
+
         model.video_player.borrow_mut().connect_mute_updated({
             let sender = sender.clone();
             Box::new(move |muted| {
@@ -157,6 +230,38 @@ impl SimpleComponent for Volume {
                 self.video_player.borrow().set_muted(self.muted);
                 self.video_player.borrow().set_volume(self.volume);
             }
+            VolumeInput::AudioDevicesUpdated(audio_devices) => {
+                self.audio_devices = audio_devices;
+                self.update_menu();
+            }
+            VolumeInput::LoadAudioSettings => {
+                let config = CONFIG.read();
+                self.audio_device_action.set_state(
+                    &config
+                        .video_player
+                        .audio_device
+                        .clone()
+                        .unwrap_or_default()
+                        .to_variant(),
+                );
+                self.night_mode_action
+                    .set_state(&config.video_player.night_mode.to_variant());
+                self.normalize_loudness_action
+                    .set_state(&config.video_player.normalize_loudness.to_variant());
+            }
+            VolumeInput::ToggleNightMode => {
+                // Night mode may have been changed elsewhere since the menu was last opened
+                let night_mode = CONFIG.read().video_player.night_mode;
+                self.night_mode_action.set_state(&night_mode.to_variant());
+                self.night_mode_action.activate(None);
+                APP_BROKER.send(AppInput::Toast(
+                    tr!("vp-night-mode-toast", {
+                        "enabled" => (!night_mode).to_string(),
+                    })
+                    .into(),
+                    Some(1),
+                ));
+            }
         }
     }
 
@@ -172,4 +277,77 @@ impl Volume {
         config.video_player.muted = self.muted;
         config.save().expect("Error saving volume settings");
     }
+
+    fn update_menu(&self) {
+        self.menu.remove_all();
+
+        let filters = gio::Menu::new();
+        filters.append_item(&RelmAction::<NightModeAction>::to_menu_item(tr!(
+            "vp-night-mode"
+        )));
+        filters.append_item(&RelmAction::<NormalizeLoudnessAction>::to_menu_item(tr!(
+            "vp-normalize-loudness"
+        )));
+        self.menu
+            .append_section(Some(tr!("vp-audio-filters-menu")), &filters);
+
+        // Audio devices aren't available with every backend
+        if self.audio_devices.is_empty() {
+            return;
+        }
+
+        let devices = gio::Menu::new();
+        devices.append_item(
+            &RelmAction::<AudioDeviceAction>::to_menu_item_with_target_value(
+                tr!("vp-audio-device-auto"),
+                &String::new(),
+            ),
+        );
+        for device in &self.audio_devices {
+            devices.append_item(
+                &RelmAction::<AudioDeviceAction>::to_menu_item_with_target_value(
+                    &device.description,
+                    &device.name,
+                ),
+            );
+        }
+        self.menu
+            .append_section(Some(tr!("vp-audio-device-menu")), &devices);
+    }
+}
+
+relm4::new_action_group!(VolumeActionGroup, "volume_actions");
+relm4::new_stateful_action!(
+    AudioDeviceAction,
+    VolumeActionGroup,
+    "audio_device",
+    String,
+    String
+);
+
+relm4::new_stateful_action!(NightModeAction, VolumeActionGroup, "night_mode", (), bool);
+relm4::new_stateful_action!(
+    NormalizeLoudnessAction,
+    VolumeActionGroup,
+    "normalize_loudness",
+    (),
+    bool
+);
+
+impl Clone for AudioDeviceAction {
+    fn clone(&self) -> Self {
+        Self {}
+    }
+}
+
+impl Clone for NightModeAction {
+    fn clone(&self) -> Self {
+        Self {}
+    }
+}
+
+impl Clone for NormalizeLoudnessAction {
+    fn clone(&self) -> Self {
+        Self {}
+    }
 }
@@ -0,0 +1,216 @@
+use std::{cell::Cell, rc::Rc};
+
+use gtk::{gdk, glib, prelude::*};
+use relm4::gtk;
+
+use crate::globals::CONFIG;
+
+use super::{
+    controls::fullscreen::{FullscreenInput, FULLSCREEN_BROKER},
+    VideoPlayerInput, VIDEO_PLAYER_BROKER,
+};
+
+// How far a finger has to move before it counts as a swipe, in pixels
+const SWIPE_THRESHOLD: f64 = 24.0;
+// How many seconds a swipe across the whole width of the video seeks by
+const SWIPE_SEEK_RANGE: f64 = 120.0;
+
+/// Which part of the video was tapped, split into thirds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TapZone {
+    Left,
+    Center,
+    Right,
+}
+
+impl TapZone {
+    fn new(x: f64, width: f64) -> Self {
+        if x < width / 3.0 {
+            TapZone::Left
+        } else if x > width * 2.0 / 3.0 {
+            TapZone::Right
+        } else {
+            TapZone::Center
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum SwipeDirection {
+    Horizontal,
+    Vertical,
+}
+
+impl SwipeDirection {
+    fn new(offset_x: f64, offset_y: f64) -> Option<Self> {
+        if offset_x.hypot(offset_y) < SWIPE_THRESHOLD {
+            None
+        } else if offset_x.abs() > offset_y.abs() {
+            Some(SwipeDirection::Horizontal)
+        } else {
+            Some(SwipeDirection::Vertical)
+        }
+    }
+}
+
+fn swipe_seek_amount(offset_x: f64, width: f64) -> isize {
+    if width <= 0.0 {
+        return 0;
+    }
+    (offset_x / width * SWIPE_SEEK_RANGE).round() as isize
+}
+
+/// Changes volume (or seeks, depending on preferences) when scrolling over the video.
+pub(crate) fn scroll_controller() -> gtk::EventControllerScroll {
+    let controller = gtk::EventControllerScroll::new(
+        gtk::EventControllerScrollFlags::VERTICAL | gtk::EventControllerScrollFlags::DISCRETE,
+    );
+
+    controller.connect_scroll(|_, _, dy| {
+        if dy != 0.0 {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::Scroll(dy));
+        }
+        glib::Propagation::Stop
+    });
+
+    controller
+}
+
+/// Swipe horizontally to seek, or vertically to change volume.
+pub(crate) fn swipe_controller() -> gtk::GestureDrag {
+    let gesture = gtk::GestureDrag::new();
+    gesture.set_touch_only(true);
+
+    let direction: Rc<Cell<Option<SwipeDirection>>> = Rc::default();
+    let last_offset_y: Rc<Cell<f64>> = Rc::default();
+
+    gesture.connect_drag_begin({
+        let direction = direction.clone();
+        let last_offset_y = last_offset_y.clone();
+        move |_, _, _| {
+            direction.set(None);
+            last_offset_y.set(0.0);
+        }
+    });
+
+    gesture.connect_drag_update({
+        let direction = direction.clone();
+        move |gesture, offset_x, offset_y| {
+            let Some(widget) = gesture.widget() else {
+                return;
+            };
+
+            if direction.get().is_none() {
+                let Some(swipe_direction) = SwipeDirection::new(offset_x, offset_y) else {
+                    return;
+                };
+                // Stop the swipe from also being handled as a tap
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                direction.set(Some(swipe_direction));
+                last_offset_y.set(offset_y);
+            }
+
+            match direction.get() {
+                Some(SwipeDirection::Horizontal) => {
+                    let seconds = swipe_seek_amount(offset_x, widget.width() as f64);
+                    VIDEO_PLAYER_BROKER.send(VideoPlayerInput::PreviewSeek(seconds));
+                }
+                Some(SwipeDirection::Vertical) => {
+                    let height = widget.height() as f64;
+                    if height > 0.0 {
+                        // Swiping up the whole height of the video goes from muted to full volume
+                        let change = (last_offset_y.get() - offset_y) / height;
+                        VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ChangeVolume(change));
+                    }
+                    last_offset_y.set(offset_y);
+                }
+                None => {}
+            }
+        }
+    });
+
+    gesture.connect_drag_end(move |gesture, offset_x, _| {
+        if direction.take() != Some(SwipeDirection::Horizontal) {
+            return;
+        }
+        let Some(widget) = gesture.widget() else {
+            return;
+        };
+        let seconds = swipe_seek_amount(offset_x, widget.width() as f64);
+        if seconds != 0 {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::SeekBy(seconds));
+        }
+    });
+
+    gesture
+}
+
+/// Tap to show or hide controls, and double tap the sides of the video to skip backwards or
+/// forwards.
+pub(crate) fn tap_controller() -> gtk::GestureClick {
+    let gesture = gtk::GestureClick::new();
+    gesture.set_touch_only(true);
+
+    gesture.connect_released(|gesture, n_press, x, _| {
+        let Some(widget) = gesture.widget() else {
+            return;
+        };
+
+        match (n_press, TapZone::new(x, widget.width() as f64)) {
+            (1, _) => {
+                VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ToggleControls);
+            }
+            // Keep skipping with each extra tap
+            (_, TapZone::Left) => {
+                let amount = CONFIG.read().video_player.skip_backwards_amount;
+                VIDEO_PLAYER_BROKER.send(VideoPlayerInput::SeekBy(-(amount as isize)));
+            }
+            (_, TapZone::Right) => {
+                let amount = CONFIG.read().video_player.skip_forwards_amount;
+                VIDEO_PLAYER_BROKER.send(VideoPlayerInput::SeekBy(amount as isize));
+            }
+            (2, TapZone::Center) => {
+                FULLSCREEN_BROKER.send(FullscreenInput::ToggleFullscreen);
+            }
+            _ => {}
+        }
+    });
+
+    gesture
+}
+
+/// Whether an event came from a touchscreen, which is handled by the touch gestures instead.
+pub(crate) fn is_touch(gesture: &impl IsA<gtk::EventController>) -> bool {
+    gesture
+        .current_event_device()
+        .is_some_and(|device| device.source() == gdk::InputSource::Touchscreen)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tap_zone() {
+        assert_eq!(TapZone::new(10.0, 900.0), TapZone::Left);
+        assert_eq!(TapZone::new(450.0, 900.0), TapZone::Center);
+        assert_eq!(TapZone::new(890.0, 900.0), TapZone::Right);
+    }
+
+    #[test]
+    fn test_swipe() {
+        assert_eq!(SwipeDirection::new(5.0, -5.0), None);
+        assert_eq!(
+            SwipeDirection::new(-100.0, 20.0),
+            Some(SwipeDirection::Horizontal)
+        );
+        assert_eq!(
+            SwipeDirection::new(10.0, -50.0),
+            Some(SwipeDirection::Vertical)
+        );
+
+        assert_eq!(swipe_seek_amount(450.0, 900.0), 60);
+        assert_eq!(swipe_seek_amount(-90.0, 900.0), -12);
+        assert_eq!(swipe_seek_amount(100.0, 0.0), 0);
+    }
+}
@@ -1,8 +1,14 @@
+use std::time::Duration;
+
 use glib::Propagation;
 use gtk::{gdk, glib, EventControllerKey};
 use relm4::gtk;
 
+use crate::{config::keybindings::KeybindingAction, globals::CONFIG};
+
 use super::controls::{
+    audio_tracks::{AudioTracksInput, AUDIO_TRACKS_BROKER},
+    chapters::{ChaptersInput, CHAPTERS_BROKER},
     fullscreen::{FullscreenInput, FULLSCREEN_BROKER},
     play_pause::{PlayPauseInput, PLAY_PAUSE_BROKER},
     playback_speed::{PlaybackSpeedInput, PLAYBACK_SPEED_BROKER},
@@ -10,61 +16,164 @@ use super::controls::{
         SkipForwardsBackwardsInput, SKIP_BACKWARDS_BROKER, SKIP_FORWARDS_BROKER,
     },
     subtitles::{SubtitlesInput, SUBTITLES_BROKER},
+    video_settings::{VideoSettingsInput, VIDEO_SETTINGS_BROKER},
     volume::{VolumeInput, VOLUME_BROKER},
 };
+use super::{VideoPlayerInput, VIDEO_PLAYER_BROKER};
 
 pub fn keybindings_controller() -> EventControllerKey {
     let controller = EventControllerKey::new();
 
-    controller.connect_key_pressed(move |_, key, _, _| {
-        match key {
-            gdk::Key::space => {
-                PLAY_PAUSE_BROKER.send(PlayPauseInput::TogglePlaying);
-            }
-            gdk::Key::Left => {
-                SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::Skip);
-            }
-            gdk::Key::Right => {
-                SKIP_FORWARDS_BROKER.send(SkipForwardsBackwardsInput::Skip);
-            }
-            gdk::Key::Up => {
-                VOLUME_BROKER.send(VolumeInput::ChangeVolume(0.1));
-            }
-            gdk::Key::Down => {
-                VOLUME_BROKER.send(VolumeInput::ChangeVolume(-0.1));
-            }
-            gdk::Key::m => {
-                VOLUME_BROKER.send(VolumeInput::ToggleMute);
-            }
-            gdk::Key::f => {
-                FULLSCREEN_BROKER.send(FullscreenInput::ToggleFullscreen);
-            }
-            gdk::Key::Escape => {
-                FULLSCREEN_BROKER.send(FullscreenInput::ExitFullscreen);
-            }
-            gdk::Key::c => {
-                SUBTITLES_BROKER
-                    .read()
-                    .send(SubtitlesInput::ToggleSubtitles);
-            }
-            gdk::Key::comma => {
-                SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::FrameStep);
-            }
-            gdk::Key::period => {
-                SKIP_FORWARDS_BROKER.send(SkipForwardsBackwardsInput::FrameStep);
-            }
-            // Left angle bracket/Shift + ,
-            gdk::Key::less => {
-                PLAYBACK_SPEED_BROKER.send(PlaybackSpeedInput::SlowDown);
-            }
-            // Right angle bracket/Shift + .
-            gdk::Key::greater => {
-                PLAYBACK_SPEED_BROKER.send(PlaybackSpeedInput::SpeedUp);
-            }
-            _ => return Propagation::Proceed,
+    controller.connect_key_pressed(move |_, key, _, modifiers| {
+        let Some(action) = keybinding_action(key, modifiers) else {
+            return Propagation::Proceed;
         };
+        perform_action(action);
         Propagation::Stop
     });
 
     controller
 }
+
+/// Makes key presses comparable with keybindings from the config, whether or not Shift was
+/// needed to type the key.
+pub(crate) fn normalize_shortcut(
+    key: gdk::Key,
+    modifiers: gdk::ModifierType,
+) -> (gdk::Key, gdk::ModifierType) {
+    let mut modifiers = modifiers & gtk::accelerator_get_default_mod_mask();
+    let lower = key.to_lower();
+
+    if lower != key {
+        // Shift + z is reported as Z
+        modifiers.insert(gdk::ModifierType::SHIFT_MASK);
+    } else if key.to_upper() == key && key.to_unicode().is_some_and(|c| !c.is_whitespace()) {
+        // Symbols like < already include Shift
+        modifiers.remove(gdk::ModifierType::SHIFT_MASK);
+    }
+
+    (lower, modifiers)
+}
+
+/// Accelerator string for a key press, in the format used by the keybindings config.
+pub(crate) fn shortcut_accelerator(key: gdk::Key, modifiers: gdk::ModifierType) -> String {
+    let (key, modifiers) = normalize_shortcut(key, modifiers);
+    gtk::accelerator_name(key, modifiers).to_string()
+}
+
+fn keybinding_action(key: gdk::Key, modifiers: gdk::ModifierType) -> Option<KeybindingAction> {
+    let shortcut = normalize_shortcut(key, modifiers);
+
+    CONFIG
+        .read()
+        .video_player
+        .keybindings
+        .iter()
+        .find_map(|(accelerator, action)| {
+            let (key, modifiers) = gtk::accelerator_parse(accelerator)?;
+            (normalize_shortcut(key, modifiers) == shortcut).then_some(action)
+        })
+}
+
+fn perform_action(action: KeybindingAction) {
+    match action {
+        KeybindingAction::TogglePlay => {
+            PLAY_PAUSE_BROKER.send(PlayPauseInput::TogglePlaying);
+        }
+        KeybindingAction::SkipBackwards => {
+            SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::Skip);
+        }
+        KeybindingAction::SkipForwards => {
+            SKIP_FORWARDS_BROKER.send(SkipForwardsBackwardsInput::Skip);
+        }
+        KeybindingAction::Seek(seconds) => {
+            let amount = Duration::from_secs(seconds.unsigned_abs());
+            if seconds < 0 {
+                SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::SkipByAmount(amount));
+            } else {
+                SKIP_FORWARDS_BROKER.send(SkipForwardsBackwardsInput::SkipByAmount(amount));
+            }
+        }
+        KeybindingAction::Volume(percent) => {
+            VOLUME_BROKER.send(VolumeInput::ChangeVolume(percent as f64 / 100.0));
+        }
+        KeybindingAction::ToggleMute => {
+            VOLUME_BROKER.send(VolumeInput::ToggleMute);
+        }
+        KeybindingAction::ToggleNightMode => {
+            VOLUME_BROKER.send(VolumeInput::ToggleNightMode);
+        }
+        KeybindingAction::ToggleFullscreen => {
+            FULLSCREEN_BROKER.send(FullscreenInput::ToggleFullscreen);
+        }
+        KeybindingAction::ExitFullscreen => {
+            FULLSCREEN_BROKER.send(FullscreenInput::ExitFullscreen);
+        }
+        KeybindingAction::ToggleSubtitles => {
+            SUBTITLES_BROKER
+                .read()
+                .send(SubtitlesInput::ToggleSubtitles);
+        }
+        KeybindingAction::SubtitleDelay(delay) => {
+            SUBTITLES_BROKER.send(SubtitlesInput::AdjustDelay(delay));
+        }
+        KeybindingAction::AudioDelay(delay) => {
+            AUDIO_TRACKS_BROKER.send(AudioTracksInput::AdjustDelay(delay));
+        }
+        KeybindingAction::FrameStepBackwards => {
+            SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::FrameStep);
+        }
+        KeybindingAction::FrameStepForwards => {
+            SKIP_FORWARDS_BROKER.send(SkipForwardsBackwardsInput::FrameStep);
+        }
+        KeybindingAction::SpeedDown => {
+            PLAYBACK_SPEED_BROKER.send(PlaybackSpeedInput::SlowDown);
+        }
+        KeybindingAction::SpeedUp => {
+            PLAYBACK_SPEED_BROKER.send(PlaybackSpeedInput::SpeedUp);
+        }
+        KeybindingAction::PreviousChapter => {
+            CHAPTERS_BROKER.send(ChaptersInput::PreviousChapter);
+        }
+        KeybindingAction::NextChapter => {
+            CHAPTERS_BROKER.send(ChaptersInput::NextChapter);
+        }
+        KeybindingAction::CycleAspectRatio => {
+            VIDEO_SETTINGS_BROKER.send(VideoSettingsInput::CycleAspectRatio);
+        }
+        KeybindingAction::TogglePanscan => {
+            VIDEO_SETTINGS_BROKER.send(VideoSettingsInput::TogglePanscan);
+        }
+        KeybindingAction::ZoomIn => {
+            VIDEO_SETTINGS_BROKER.send(VideoSettingsInput::ZoomIn);
+        }
+        KeybindingAction::ZoomOut => {
+            VIDEO_SETTINGS_BROKER.send(VideoSettingsInput::ZoomOut);
+        }
+        KeybindingAction::ResetVideo => {
+            VIDEO_SETTINGS_BROKER.send(VideoSettingsInput::Reset);
+        }
+        KeybindingAction::Screenshot => {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::Screenshot {
+                include_subtitles: true,
+            });
+        }
+        KeybindingAction::ScreenshotWithoutSubtitles => {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::Screenshot {
+                include_subtitles: false,
+            });
+        }
+        KeybindingAction::PictureInPicture => {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::EnterPip);
+        }
+        KeybindingAction::ToggleStats => {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ToggleStats);
+        }
+        KeybindingAction::CycleAbLoop => {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::CycleAbLoop);
+        }
+        KeybindingAction::ClearAbLoop => {
+            VIDEO_PLAYER_BROKER.send(VideoPlayerInput::ClearAbLoop);
+        }
+    }
+}
@@ -1,16 +1,29 @@
+mod ab_loop;
 pub mod backends;
 mod controls;
-mod keybindings;
+mod gestures;
+pub(crate) mod keybindings;
 mod mpris;
 mod next_up;
+mod osd;
+mod pip;
+pub(crate) mod playstate_outbox;
+pub(crate) mod resume_dialog;
+pub(crate) mod screenshot;
+mod segment_skipper;
+mod series_tracks;
 mod session;
-mod skip_intro;
+mod stats;
+mod sync_offsets;
+mod track_selection;
 mod trickplay;
 
-use crate::config::video_player_config::{VideoPlayerConfig, VideoPlayerOnLeftClick};
+use crate::config::video_player_config::{
+    VideoPlayerConfig, VideoPlayerOnLeftClick, VideoPlayerOnScroll,
+};
 use crate::utils::inhibit::InhibitCookie;
 use crate::video_player::keybindings::keybindings_controller;
-use crate::video_player::trickplay::fetch_trickplay;
+use crate::video_player::trickplay::{fetch_trickplay, Trickplay};
 use std::cell::RefCell;
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::Arc;
@@ -18,46 +31,57 @@ use std::time::Duration;
 
 use adw::prelude::*;
 use gtk::gdk;
-use jellyfin_api::types::{BaseItemDto, BaseItemKind};
+use jellyfin_api::types::{BaseItemDto, BaseItemKind, MediaSourceInfo};
 use relm4::component::{AsyncComponent, AsyncComponentController, AsyncController};
 use relm4::{gtk, ComponentParts};
 use relm4::{prelude::*, MessageBroker};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+use uuid::Uuid;
 
 use crate::app::{AppInput, APP_BROKER};
-use crate::globals::CONFIG;
+use crate::globals::{AUDIO_DEVICES, CONFIG};
+use crate::jellyfin_api::api::playstate::{PlayMethod, PlaybackSession};
 use crate::jellyfin_api::api::shows::GetEpisodesOptionsBuilder;
 use crate::jellyfin_api::api_client::ApiClient;
-use crate::library::LIBRARY_REFRESH_QUEUED;
-use crate::media_details::MEDIA_DETAILS_REFRESH_QUEUED;
 use crate::tr;
-use crate::utils::bif::Thumbnail;
 use crate::utils::debounce::Debounce;
 use crate::utils::item_name::ItemName;
-use crate::utils::ticks::ticks_to_seconds;
+use crate::utils::playable::get_resume_position;
 use crate::video_player::controls::skip_forwards_backwards::{
     SkipForwardsBackwardsInput, SKIP_BACKWARDS_BROKER, SKIP_FORWARDS_BROKER,
 };
 use crate::video_player::controls::VideoPlayerControlsInit;
 use crate::video_player::next_up::{NextUp, NEXT_UP_VISIBILE};
 
+use self::ab_loop::AbLoop;
 use self::backends::{PlayerState, VideoPlayerBackend};
+use self::controls::chapters::{ChaptersInput, CHAPTERS_BROKER};
 use self::controls::fullscreen::{FullscreenInput, FULLSCREEN_BROKER};
 use self::controls::play_pause::{PlayPauseInput, PLAY_PAUSE_BROKER};
 use self::controls::scrubber::{ScrubberInput, SCRUBBER_BROKER};
+use self::controls::subtitles::subtitle_file_drop_target;
 use self::controls::volume::{VolumeInput, VOLUME_BROKER};
 use self::controls::{VideoPlayerControls, VideoPlayerControlsInput};
 use self::mpris::MprisPlaybackReporter;
 use self::next_up::NextUpInput;
-use self::session::SessionPlaybackReporter;
-use self::skip_intro::{SkipIntro, SkipIntroInput};
+use self::osd::{Osd, OsdInput};
+use self::pip::{PipWindow, PipWindowInit, PipWindowInput, PipWindowOutput};
+use self::segment_skipper::{SegmentSkipper, SegmentSkipperInput};
+use self::series_tracks::get_series_tracks;
+use self::session::{PlaybackChange, SessionPlaybackReporter};
+use self::stats::{StatsOverlay, StatsOverlayInput};
+use self::track_selection::{TrackPreferences, TrackSelector};
 
 // How long the cursor has to be still before it's hidden
 const CURSOR_HIDE_TIMEOUT: Duration = Duration::from_secs(3);
+// How much volume changes by for each step of the scroll wheel
+const SCROLL_VOLUME_STEP: f64 = 0.05;
 
 pub struct VideoPlayer {
     backend: Arc<RefCell<dyn VideoPlayerBackend>>,
     media: Option<BaseItemDto>,
+    // Where to start playback from, in seconds
+    start_position: Option<usize>,
     api_client: Option<Arc<ApiClient>>,
     hiding: Arc<AtomicBool>,
 
@@ -67,8 +91,12 @@ pub struct VideoPlayer {
     session_playback_reporter: SessionPlaybackReporter,
     mpris_playback_reporter: Option<MprisPlaybackReporter>,
     inhibit_cookie: Option<InhibitCookie>,
-    player_state: PlayerState,
-    next: Option<BaseItemDto>,
+    playback: Playback,
+    // Why playback failed, if the player is showing the error page
+    error: Option<String>,
+    // Whether the next video should be played through a transcoded stream
+    transcode: bool,
+    ab_loop: AbLoop,
 
     cursor: Option<gdk::Cursor>,
     cursor_debounce: Debounce,
@@ -76,33 +104,69 @@ pub struct VideoPlayer {
 
     controls: Controller<VideoPlayerControls>,
     next_up: Controller<NextUp>,
-    skip_intro: AsyncController<SkipIntro>,
+    segment_skipper: AsyncController<SegmentSkipper>,
+    stats: Controller<StatsOverlay>,
+    osd: Controller<Osd>,
+    pip: Option<Controller<PipWindow>>,
 }
 
 #[derive(Debug)]
 pub enum VideoPlayerInput {
     ConfigUpdated(VideoPlayerConfig),
-    PlayVideo(Arc<ApiClient>, Box<BaseItemDto>),
-    SetShowControls { show: bool, locked: bool },
+    PlayVideo(Arc<ApiClient>, Box<BaseItemDto>, Option<usize>),
+    SetShowControls {
+        show: bool,
+        locked: bool,
+    },
     ToggleControls,
     EndOfStream,
     StopPlayer,
     PlayerStateChanged(PlayerState),
+    PlaybackError(String),
+    /// Play the current video again after an error
+    Retry {
+        transcode: bool,
+    },
     SetRevealerRevealChild(bool),
     MouseMove(f64, f64),
     MouseHide,
     MouseClick(i32),
+    Screenshot {
+        include_subtitles: bool,
+    },
+    CycleAbLoop,
+    SetAbLoopStart(usize),
+    SetAbLoopEnd(usize),
+    ClearAbLoop,
+    EnterPip,
+    ExitPip,
+    ToggleStats,
+    Scroll(f64),
+    ChangeVolume(f64),
+    /// Show how far a seek would go, while it's still being chosen
+    PreviewSeek(isize),
+    SeekBy(isize),
+    /// Let the server know about a change to playback, like a different audio track
+    ReportPlaybackChange(PlaybackChange),
 }
 
 #[derive(Debug)]
 pub enum VideoPlayerOutput {
     NavigateBack,
+    ShowPlayer,
 }
 
 #[derive(Debug)]
 pub enum VideoPlayerCommandOutput {
     LoadedNextPrev((Option<BaseItemDto>, Option<BaseItemDto>)),
-    LoadedTrickplay(Option<Vec<Thumbnail>>),
+    LoadedTrickplay(Option<Trickplay>),
+    /// Item ID, track selector, play session ID and media source
+    LoadedPlaybackInfo(
+        Uuid,
+        TrackSelector,
+        Option<String>,
+        Option<Box<MediaSourceInfo>>,
+    ),
 }
 
 #[relm4::component(pub)]
@@ -133,6 +197,7 @@ impl Component for VideoPlayer {
                     .or(media.name.clone()))
                 .unwrap_or(tr!("app-name").to_string()),
 
+            #[name = "overlay"]
             #[wrap(Some)]
             set_child = &gtk::Overlay {
                 add_css_class: "video-player",
@@ -140,13 +205,19 @@ impl Component for VideoPlayer {
                 #[local_ref]
                 video_player -> gtk::Widget {
                     #[watch]
-                    set_visible: !matches!(model.player_state, PlayerState::Loading),
+                    set_visible: !matches!(model.playback.state, PlayerState::Loading | PlayerState::Error),
 
                     add_controller = gtk::GestureClick {
-                        connect_released[sender] => move |_, n_press, _, _| {
-                            sender.input(VideoPlayerInput::MouseClick(n_press));
+                        connect_released[sender] => move |gesture, n_press, _, _| {
+                            if !gestures::is_touch(gesture) {
+                                sender.input(VideoPlayerInput::MouseClick(n_press));
+                            }
                         },
                     },
+                    add_controller = gestures::tap_controller(),
+                    add_controller = gestures::swipe_controller(),
+                    add_controller = gestures::scroll_controller(),
+                    add_controller = subtitle_file_drop_target(),
                 },
 
                 #[name = "revealer"]
@@ -167,7 +238,7 @@ impl Component for VideoPlayer {
                 #[name = "spinner"]
                 add_overlay = &gtk::Spinner {
                     #[watch]
-                    set_visible: matches!(model.player_state, PlayerState::Loading | PlayerState::Buffering),
+                    set_visible: matches!(model.playback.state, PlayerState::Loading | PlayerState::Buffering),
                     set_spinning: true,
                     set_halign: gtk::Align::Center,
                     set_valign: gtk::Align::Center,
@@ -175,7 +246,52 @@ impl Component for VideoPlayer {
                     set_height_request: 48,
                 },
 
+                add_overlay = &adw::StatusPage {
+                    #[watch]
+                    set_visible: matches!(model.playback.state, PlayerState::Error),
+                    set_title: tr!("vp-error-page.title"),
+                    #[watch]
+                    set_description: Some(&match &model.error {
+                        Some(error) => tr!("vp-error-page.description", { "error" => error.clone() }).to_string(),
+                        None => tr!("vp-error-page.description-unknown").to_string(),
+                    }),
+                    set_icon_name: Some("warning"),
+
+                    #[wrap(Some)]
+                    set_child = &gtk::Box {
+                        set_orientation: gtk::Orientation::Vertical,
+                        set_halign: gtk::Align::Center,
+                        set_spacing: 12,
+
+                        gtk::Button {
+                            set_label: tr!("vp-error-page.retry"),
+                            set_css_classes: &["pill", "suggested-action"],
+                            connect_clicked[sender] => move |_| {
+                                sender.input(VideoPlayerInput::Retry { transcode: false });
+                            },
+                        },
+
+                        gtk::Button {
+                            set_label: tr!("vp-error-page.transcode"),
+                            add_css_class: "pill",
+                            connect_clicked[sender] => move |_| {
+                                sender.input(VideoPlayerInput::Retry { transcode: true });
+                            },
+                        },
+
+                        gtk::Button {
+                            set_label: tr!("vp-error-page.back"),
+                            add_css_class: "pill",
+                            connect_clicked[sender] => move |_| {
+                                sender.output(VideoPlayerOutput::NavigateBack).unwrap();
+                            },
+                        },
+                    },
+                },
+
                 add_overlay = &gtk::Box {
+                    #[watch]
+                    set_visible: !matches!(model.playback.state, PlayerState::Error),
                     set_orientation: gtk::Orientation::Vertical,
                     set_halign: gtk::Align::Fill,
                     set_valign: gtk::Align::End,
@@ -183,11 +299,24 @@ impl Component for VideoPlayer {
                     set_margin_end: 24,
                     set_margin_bottom: 24,
 
-                    append = model.skip_intro.widget(),
+                    gtk::Label {
+                        #[watch]
+                        set_visible: !model.ab_loop.is_empty(),
+                        #[watch]
+                        set_label: &model.ab_loop.indicator_label(),
+                        set_halign: gtk::Align::Start,
+                        set_margin_bottom: 12,
+                        add_css_class: "osd",
+                        add_css_class: "ab-loop-indicator",
+                    },
+
+                    append = model.segment_skipper.widget(),
                     append = model.controls.widget(),
                 },
 
                 add_overlay: model.next_up.widget(),
+                add_overlay: model.stats.widget(),
+                add_overlay: model.osd.widget(),
             },
 
             connect_hiding[sender] => move |_| {
@@ -218,7 +347,9 @@ impl Component for VideoPlayer {
             });
 
         let next_up = NextUp::builder().launch(backend.clone()).detach();
-        let skip_intro = SkipIntro::builder().launch(backend.clone()).detach();
+        let segment_skipper = SegmentSkipper::builder().launch(backend.clone()).detach();
+        let stats = StatsOverlay::builder().launch(backend.clone()).detach();
+        let osd = Osd::builder().launch(()).detach();
 
         let cursor_debounce = Debounce::new(
             CURSOR_HIDE_TIMEOUT,
@@ -233,6 +364,7 @@ impl Component for VideoPlayer {
         let model = VideoPlayer {
             backend,
             media: None,
+            start_position: None,
             api_client: None,
             hiding: Arc::new(AtomicBool::new(false)),
 
@@ -242,8 +374,13 @@ impl Component for VideoPlayer {
             session_playback_reporter: SessionPlaybackReporter::default(),
             mpris_playback_reporter: None,
             inhibit_cookie: None,
-            player_state: PlayerState::Loading,
-            next: None,
+            playback: Playback {
+                state: PlayerState::Loading,
+                next: None,
+            },
+            error: None,
+            transcode: false,
+            ab_loop: AbLoop::default(),
 
             cursor: None,
             cursor_debounce,
@@ -251,45 +388,27 @@ impl Component for VideoPlayer {
 
             controls,
             next_up,
-            skip_intro,
+            segment_skipper,
+            stats,
+            osd,
+            pip: None,
         };
 
         model.configure_player(&CONFIG.read().video_player);
         VideoPlayer::subscribe_to_config(&sender);
 
-        model.backend.borrow_mut().connect_player_state_changed({
-            let sender = sender.clone();
-            Box::new(move |state| {
-                if cfg!(debug_assertions) {
-                    debug!("Player state changed: {state:#?}");
-                }
-
-                sender.input(VideoPlayerInput::PlayerStateChanged(state));
-            })
-        });
-
-        model.backend.borrow_mut().connect_end_of_stream({
+        connect_backend(&model.backend, &model.hiding, {
             let sender = sender.clone();
-            let hiding = model.hiding.clone();
-            Box::new(move || {
-                if !hiding.load(atomic::Ordering::Relaxed) {
-                    sender.input(VideoPlayerInput::EndOfStream);
-                }
-            })
+            move |input| sender.input(input)
         });
 
-        // TODO
-        // video_player.connect_error({
-        //     let sender = sender.clone();
-        //     move |err, details| {
-        //         println!("Video player error: {err:#?}");
-        //         println!("Details: {details:#?}");
-        //         sender.input(VideoPlayerInput::Toast(format!(
-        //             "Video player error: {}",
-        //             err.message()
-        //         )));
-        //     }
-        // });
+        *AUDIO_DEVICES.write() = model.backend.borrow().audio_devices();
+        model
+            .backend
+            .borrow_mut()
+            .connect_audio_devices_updated(Box::new(|audio_devices| {
+                *AUDIO_DEVICES.write() = audio_devices;
+            }));
 
         let binding = model.backend.clone();
         let binding = binding.borrow();
@@ -321,6 +440,9 @@ impl Component for VideoPlayer {
         let position = self.backend.borrow().position();
 
         self.backend.borrow_mut().stop();
+        let stopped_report = self
+            .session_playback_reporter
+            .stopped_report(position, matches!(self.playback.state, PlayerState::Error));
         self.session_playback_reporter.stop(&self.backend);
 
         // Don't uninhibit when cookie is dropped, otherwise application will
@@ -332,11 +454,12 @@ impl Component for VideoPlayer {
         // This should only be called if the app is closed while the video player is open. We want
         // to tell the server that playback stopped in a blocking manner so that the request goes
         // out before the app is closed.
-        if !matches!(self.player_state, PlayerState::Loading) {
-            if let (Some(api_client), Some(media)) = (&self.api_client, &self.media) {
+        if !matches!(self.playback.state, PlayerState::Loading) {
+            if let (Some(api_client), Some(stopped_report)) = (&self.api_client, stopped_report) {
+                // If it can't be sent, it's sent the next time this account is used
+                playstate_outbox::save_report(api_client, stopped_report);
                 if let Ok(runtime) = tokio::runtime::Runtime::new() {
-                    let _ = runtime
-                        .block_on(api_client.report_playback_stopped(media.id.unwrap(), position));
+                    runtime.block_on(playstate_outbox::send_pending(api_client));
                 }
             }
         }
@@ -353,29 +476,42 @@ impl Component for VideoPlayer {
             VideoPlayerInput::ConfigUpdated(video_player_config) => {
                 self.configure_player(&video_player_config);
             }
-            VideoPlayerInput::PlayVideo(api_client, item) => {
+            VideoPlayerInput::PlayVideo(api_client, item, start_position) => {
+                // Something else was played while the previous video was still in the
+                // picture-in-picture window
+                if self.exit_pip(&widgets.overlay) {
+                    self.stop_player();
+                }
+
                 self.inhibit_cookie = InhibitCookie::new().ok();
 
                 self.set_player_state(PlayerState::Loading);
-                self.next = None;
+                self.playback.next = None;
+                self.stats.emit(StatsOverlayInput::Reset);
                 self.hiding.store(false, atomic::Ordering::Relaxed);
 
                 self.media = Some(*item.clone());
-                let url = api_client.get_stream_url(&item.id.unwrap());
+                // Resume from the last playback position, unless told where to start from
+                self.start_position = start_position.or_else(|| get_resume_position(&item));
+                let transcode = std::mem::take(&mut self.transcode);
+                let url = if transcode {
+                    api_client.get_transcode_stream_url(&item.id.unwrap())
+                } else {
+                    api_client.get_stream_url(&item.id.unwrap())
+                };
+                let play_method = match (transcode, CONFIG.read().video_player.hls_playback) {
+                    (true, _) => PlayMethod::Transcode,
+                    // The server remuxes the video into HLS segments
+                    (false, true) => PlayMethod::DirectStream,
+                    (false, false) => PlayMethod::DirectPlay,
+                };
+                self.stats
+                    .emit(StatsOverlayInput::SetPlayMethod(play_method));
                 info!("Playing video from URL: {url}");
                 self.backend.borrow_mut().play_uri(&url);
 
-                if let Some(playback_position_ticks) = item
-                    .user_data
-                    .as_ref()
-                    .and_then(|user_data| user_data.playback_position_ticks)
-                {
-                    let playback_position = ticks_to_seconds(playback_position_ticks);
-                    self.backend.borrow().seek_to(
-                        playback_position
-                            .try_into()
-                            .expect("Playback position should be positive"),
-                    );
+                if let Some(start_position) = self.start_position {
+                    self.backend.borrow().seek_to(start_position);
                 }
 
                 self.controls.emit(VideoPlayerControlsInput::SetPlaying {
@@ -384,22 +520,22 @@ impl Component for VideoPlayer {
                 });
 
                 if let Some(item_id) = item.id {
-                    // Report start of playback
-                    relm4::spawn({
-                        let api_client = api_client.clone();
-                        async move {
-                            api_client.report_playback_started(item_id).await.unwrap();
-                        }
-                    });
-
-                    // Load intro skipper
-                    self.skip_intro
-                        .emit(SkipIntroInput::Load(item_id, api_client.clone()));
+                    // Load media segments
+                    self.segment_skipper
+                        .emit(SegmentSkipperInput::Load(item_id, api_client.clone()));
                 }
 
-                // Starts a background task that continuously reports playback progress
-                self.session_playback_reporter
-                    .start(&api_client, &item.id.unwrap(), &self.backend);
+                // Reports start of playback, then continuously reports playback progress
+                self.session_playback_reporter.start(
+                    &api_client,
+                    &item.id.unwrap(),
+                    PlaybackSession {
+                        play_method,
+                        can_seek: true,
+                        ..Default::default()
+                    },
+                    &self.backend,
+                );
 
                 self.mpris_playback_reporter = Some(MprisPlaybackReporter::new(
                     api_client.clone(),
@@ -410,6 +546,7 @@ impl Component for VideoPlayer {
                 self.api_client = Some(api_client.clone());
 
                 self.fetch_next_prev(&sender, &item);
+                self.fetch_playback_info(&sender, &item);
 
                 if let Some(item_id) = item.id {
                     relm4::spawn({
@@ -422,7 +559,13 @@ impl Component for VideoPlayer {
                             };
                             fetch_trickplay(&api_client, &sender, &item);
                             if let Some(chapters) = item.chapters {
-                                SCRUBBER_BROKER.send(ScrubberInput::DisplayChapters(chapters));
+                                SCRUBBER_BROKER
+                                    .send(ScrubberInput::DisplayChapters(chapters.clone()));
+                                CHAPTERS_BROKER.send(ChaptersInput::Load {
+                                    api_client,
+                                    item_id,
+                                    chapters,
+                                });
                             }
                         }
                     });
@@ -444,63 +587,57 @@ impl Component for VideoPlayer {
                     locked: false,
                 });
             }
-            VideoPlayerInput::EndOfStream => {
-                match self.player_state {
-                    PlayerState::Playing { paused: _ } => {}
-                    _ => {
-                        return;
+            input @ (VideoPlayerInput::EndOfStream | VideoPlayerInput::PlayerStateChanged(_)) => {
+                let previous_state = self.playback.state;
+                match self.playback.update(input, self.pip.is_some()) {
+                    None => self.player_state_changed(previous_state),
+                    Some(EndOfStreamAction::Ignore) => {}
+                    Some(EndOfStreamAction::PlayNext(next)) => {
+                        APP_BROKER.send(AppInput::ContinuePlayback(next));
+                    }
+                    Some(EndOfStreamAction::ExitPip) => {
+                        // The main window already left the player when the video moved to its own window
+                        self.exit_pip(&widgets.overlay);
+                        self.stop_player();
+                    }
+                    Some(EndOfStreamAction::NavigateBack) => {
+                        sender.output(VideoPlayerOutput::NavigateBack).unwrap();
                     }
-                };
-
-                // Play next episode if available
-                if let Some(next) = &self.next {
-                    APP_BROKER.send(AppInput::PlayVideo(next.clone()));
-                    return;
                 }
-
-                sender.output(VideoPlayerOutput::NavigateBack).unwrap();
             }
             VideoPlayerInput::StopPlayer => {
-                self.inhibit_cookie = None;
-
-                self.hiding.store(true, atomic::Ordering::Relaxed);
-
-                self.backend.borrow_mut().stop();
-
-                let position = self.backend.borrow().position();
-
-                // Report end of playback
-                // (don't report if we're still loading)
-                if !matches!(self.player_state, PlayerState::Loading) {
-                    if let (Some(api_client), Some(media)) = (&self.api_client, &self.media) {
-                        // Report end of playback
-                        relm4::spawn({
-                            let api_client = api_client.clone();
-                            let item_id = media.id.unwrap();
-                            async move {
-                                api_client
-                                    .report_playback_stopped(item_id, position)
-                                    .await
-                                    .unwrap();
-                                *LIBRARY_REFRESH_QUEUED.write() = true;
-                                *MEDIA_DETAILS_REFRESH_QUEUED.write() = true;
-                            }
-                        });
-
-                        self.api_client = None;
-                        self.media = None;
-                    }
+                // The page is hidden when the video moves to the picture-in-picture window
+                if self.pip.is_none() {
+                    self.stop_player();
                 }
+            }
+            VideoPlayerInput::PlaybackError(error) => {
+                warn!("Playback error: {error}");
+                self.error = Some(error);
+            }
+            VideoPlayerInput::Retry { transcode } => {
+                let (Some(api_client), Some(media)) = (self.api_client.clone(), self.media.clone())
+                else {
+                    return;
+                };
 
-                // Stop background playback progress reporter
-                self.session_playback_reporter.stop(&self.backend);
+                // Pick up from where playback failed, if it got that far
+                let position = self.backend.borrow().position();
+                let start_position = if position > 0 {
+                    Some(position)
+                } else {
+                    self.start_position
+                };
 
-                self.mpris_playback_reporter = None;
+                // The failed session is over, even though the same video is played again
+                self.report_playback_stopped(position);
 
-                FULLSCREEN_BROKER.send(FullscreenInput::ExitFullscreen);
-            }
-            VideoPlayerInput::PlayerStateChanged(play_state) => {
-                self.set_player_state(play_state);
+                self.transcode = transcode;
+                sender.input(VideoPlayerInput::PlayVideo(
+                    api_client,
+                    Box::new(media),
+                    start_position,
+                ));
             }
             VideoPlayerInput::SetRevealerRevealChild(reveal) => {
                 self.revealer_reveal_child = reveal;
@@ -537,6 +674,8 @@ impl Component for VideoPlayer {
                     });
                 }
             }
+            // The picture-in-picture window has its own controls
+            VideoPlayerInput::MouseClick(_) if self.pip.is_some() => {}
             VideoPlayerInput::MouseClick(n_press) => match n_press {
                 2 => {
                     if CONFIG.read().video_player.on_left_click == VideoPlayerOnLeftClick::PlayPause
@@ -555,6 +694,126 @@ impl Component for VideoPlayer {
                 },
                 _ => {}
             },
+            VideoPlayerInput::Screenshot { include_subtitles } => {
+                let title = self
+                    .media
+                    .as_ref()
+                    .and_then(|media| media.series_and_episode().or(media.name.clone()))
+                    .unwrap_or_default();
+
+                match screenshot::take_screenshot(
+                    &*self.backend.borrow(),
+                    &CONFIG.read().video_player,
+                    &title,
+                    include_subtitles,
+                ) {
+                    Ok(path) => {
+                        APP_BROKER.send(AppInput::FileToast(
+                            tr!("vp-screenshot-saved").to_string(),
+                            path,
+                        ));
+                    }
+                    Err(err) => {
+                        warn!("Error taking screenshot: {err:#}");
+                        APP_BROKER.send(AppInput::Toast(
+                            tr!("vp-screenshot-error").to_string(),
+                            None,
+                        ));
+                    }
+                }
+            }
+            VideoPlayerInput::EnterPip => {
+                if self.pip.is_some() || self.media.is_none() {
+                    return;
+                }
+
+                FULLSCREEN_BROKER.send(FullscreenInput::ExitFullscreen);
+
+                let video = self.backend.borrow().widget().clone();
+                widgets.overlay.set_child(None::<&gtk::Widget>);
+
+                let pip = PipWindow::builder()
+                    .launch(PipWindowInit {
+                        video,
+                        title: self
+                            .media
+                            .as_ref()
+                            .and_then(|media| media.series_and_episode().or(media.name.clone()))
+                            .unwrap_or(tr!("app-name").to_string()),
+                        playing: matches!(
+                            self.playback.state,
+                            PlayerState::Playing { paused: false }
+                        ),
+                    })
+                    .forward(sender.input_sender(), |output| match output {
+                        PipWindowOutput::Close => VideoPlayerInput::ExitPip,
+                    });
+                self.pip = Some(pip);
+
+                // Return the main window to wherever the video was played from
+                sender.output(VideoPlayerOutput::NavigateBack).unwrap();
+            }
+            VideoPlayerInput::ExitPip => {
+                if self.exit_pip(&widgets.overlay) {
+                    sender.output(VideoPlayerOutput::ShowPlayer).unwrap();
+                }
+            }
+            VideoPlayerInput::CycleAbLoop => {
+                let position = self.backend.borrow().position();
+                self.set_ab_loop(self.ab_loop.cycle(position));
+            }
+            VideoPlayerInput::SetAbLoopStart(start) => {
+                self.set_ab_loop(self.ab_loop.with_start(start));
+            }
+            VideoPlayerInput::SetAbLoopEnd(end) => {
+                self.set_ab_loop(self.ab_loop.with_end(end));
+            }
+            VideoPlayerInput::ClearAbLoop => {
+                self.set_ab_loop(AbLoop::default());
+            }
+            VideoPlayerInput::ToggleStats => {
+                self.stats.emit(StatsOverlayInput::Toggle);
+            }
+            VideoPlayerInput::Scroll(dy) => {
+                let config = CONFIG.read();
+                let video_player_config = &config.video_player;
+                // Scrolling up is negative
+                match video_player_config.on_scroll {
+                    VideoPlayerOnScroll::Volume => {
+                        sender.input(VideoPlayerInput::ChangeVolume(-dy * SCROLL_VOLUME_STEP));
+                    }
+                    VideoPlayerOnScroll::Seek => {
+                        let seconds = if dy < 0.0 {
+                            video_player_config.skip_forwards_amount as isize
+                        } else {
+                            -(video_player_config.skip_backwards_amount as isize)
+                        };
+                        sender.input(VideoPlayerInput::SeekBy(seconds));
+                    }
+                }
+            }
+            VideoPlayerInput::ChangeVolume(change) => {
+                let volume = (self.backend.borrow().volume() + change).clamp(0.0, 1.0);
+                VOLUME_BROKER.send(VolumeInput::ChangeVolume(change));
+                self.osd.emit(OsdInput::Show(
+                    tr!("vp-osd-volume", { "volume" => (volume * 100.0).round() }).to_string(),
+                ));
+            }
+            VideoPlayerInput::PreviewSeek(seconds) => {
+                self.osd.emit(OsdInput::Show(seek_osd_label(seconds)));
+            }
+            VideoPlayerInput::SeekBy(seconds) => {
+                let amount = Duration::from_secs(seconds.unsigned_abs() as u64);
+                if seconds < 0 {
+                    SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::SkipByAmount(amount));
+                } else {
+                    SKIP_FORWARDS_BROKER.send(SkipForwardsBackwardsInput::SkipByAmount(amount));
+                }
+                self.osd.emit(OsdInput::Show(seek_osd_label(seconds)));
+            }
+            VideoPlayerInput::ReportPlaybackChange(change) => {
+                self.session_playback_reporter.report_change(change);
+            }
         }
 
         self.update_view(widgets, sender);
@@ -568,7 +827,7 @@ impl Component for VideoPlayer {
     ) {
         match message {
             VideoPlayerCommandOutput::LoadedNextPrev((prev, next)) => {
-                self.next.clone_from(&next);
+                self.playback.next.clone_from(&next);
 
                 let next = Box::new(next);
                 self.controls
@@ -582,10 +841,30 @@ impl Component for VideoPlayer {
                         .emit(NextUpInput::SetNextUp((next, api_client.clone())));
                 }
             }
-            VideoPlayerCommandOutput::LoadedTrickplay(thumbnails) => {
+            VideoPlayerCommandOutput::LoadedTrickplay(trickplay) => {
                 SCRUBBER_BROKER
                     .read()
-                    .send(ScrubberInput::LoadedThumbnails(thumbnails));
+                    .send(ScrubberInput::LoadedTrickplay(trickplay));
+            }
+            VideoPlayerCommandOutput::LoadedPlaybackInfo(
+                item_id,
+                track_selector,
+                play_session_id,
+                media_source,
+            ) => {
+                // Something else started playing while this was loading
+                if self.media.as_ref().and_then(|media| media.id) != Some(item_id) {
+                    return;
+                }
+
+                self.controls
+                    .emit(VideoPlayerControlsInput::SetTrackSelector(track_selector));
+                self.session_playback_reporter
+                    .set_playback_info(play_session_id, media_source.as_deref());
+                if let Some(media_source) = media_source {
+                    self.stats
+                        .emit(StatsOverlayInput::SetMediaSource(media_source));
+                }
             }
         }
     }
@@ -628,27 +907,27 @@ impl VideoPlayer {
                 }),
         );
         player.set_subtitle_position(video_player_config.subtitles.position);
+        player.set_secondary_subtitle_position(video_player_config.subtitles.secondary_position);
         player.set_subtitle_font(&video_player_config.subtitles.font);
+        player.set_audio_device(video_player_config.audio_device.as_deref());
+        player.set_audio_passthrough(video_player_config.audio_passthrough);
+        player.set_audio_filters(video_player_config.audio_filters());
     }
 
     fn set_player_state(&mut self, new_state: PlayerState) {
-        if matches!(self.player_state, PlayerState::Loading)
+        let previous_state = std::mem::replace(&mut self.playback.state, new_state);
+        self.player_state_changed(previous_state);
+    }
+
+    fn player_state_changed(&mut self, previous_state: PlayerState) {
+        let new_state = self.playback.state;
+        if matches!(previous_state, PlayerState::Loading)
             && matches!(new_state, PlayerState::Playing { paused: _ })
         {
             // Seek once playback begins
             // If we seek too early, MPV ignores it
-            if let Some(playback_position_ticks) = self
-                .media
-                .as_ref()
-                .and_then(|m| m.user_data.clone())
-                .and_then(|user_data| user_data.playback_position_ticks)
-            {
-                let playback_position = ticks_to_seconds(playback_position_ticks);
-                self.backend.borrow().seek_to(
-                    playback_position
-                        .try_into()
-                        .expect("Playback position should be positive"),
-                );
+            if let Some(start_position) = self.start_position {
+                self.backend.borrow().seek_to(start_position);
             }
 
             // Load volume settings once playback starts
@@ -657,7 +936,9 @@ impl VideoPlayer {
 
         match new_state {
             PlayerState::Loading => {
+                self.error = None;
                 SCRUBBER_BROKER.send(ScrubberInput::Reset);
+                CHAPTERS_BROKER.send(ChaptersInput::Reset);
                 PLAY_PAUSE_BROKER.send(PlayPauseInput::SetLoading);
                 SKIP_FORWARDS_BROKER
                     .read()
@@ -666,6 +947,7 @@ impl VideoPlayer {
                     .read()
                     .send(SkipForwardsBackwardsInput::SetLoading(true));
                 self.next_up.emit(NextUpInput::Reset);
+                self.set_ab_loop(AbLoop::default());
             }
             PlayerState::Playing { paused } => {
                 SCRUBBER_BROKER.send(ScrubberInput::SetPlaying);
@@ -679,10 +961,75 @@ impl VideoPlayer {
                     .read()
                     .send(SkipForwardsBackwardsInput::SetLoading(false));
             }
-            PlayerState::Buffering => {}
+            PlayerState::Buffering | PlayerState::Error => {}
+        }
+
+        if let Some(pip) = &self.pip {
+            pip.emit(PipWindowInput::SetPlayerState(new_state));
+        }
+    }
+
+    // Move the video back from the picture-in-picture window, returning whether it was open
+    fn exit_pip(&mut self, overlay: &gtk::Overlay) -> bool {
+        let Some(pip) = self.pip.take() else {
+            return false;
+        };
+
+        let video = self.backend.borrow().widget().clone();
+        if let Some(pip_overlay) = video.parent().and_downcast::<gtk::Overlay>() {
+            pip_overlay.set_child(None::<&gtk::Widget>);
+        }
+        overlay.set_child(Some(&video));
+        pip.widget().destroy();
+
+        true
+    }
+
+    fn stop_player(&mut self) {
+        self.inhibit_cookie = None;
+
+        self.hiding.store(true, atomic::Ordering::Relaxed);
+
+        self.backend.borrow_mut().stop();
+
+        let position = self.backend.borrow().position();
+        self.report_playback_stopped(position);
+
+        // Stop background playback progress reporter
+        self.session_playback_reporter.stop(&self.backend);
+
+        self.mpris_playback_reporter = None;
+
+        FULLSCREEN_BROKER.send(FullscreenInput::ExitFullscreen);
+    }
+
+    fn report_playback_stopped(&mut self, position: usize) {
+        // Don't report if we're still loading
+        if matches!(self.playback.state, PlayerState::Loading) {
+            return;
+        }
+
+        if let (Some(api_client), Some(_)) = (&self.api_client, &self.media) {
+            // When playback failed, the position doesn't mean anything, so the server shouldn't
+            // overwrite the resume position with it
+            if let Some(stopped_report) = self
+                .session_playback_reporter
+                .stopped_report(position, matches!(self.playback.state, PlayerState::Error))
+            {
+                playstate_outbox::queue_report(api_client, stopped_report);
+            }
+
+            self.api_client = None;
+            self.media = None;
         }
+    }
 
-        self.player_state = new_state;
+    fn set_ab_loop(&mut self, ab_loop: AbLoop) {
+        let backend = self.backend.borrow();
+        backend.set_ab_loop_a(ab_loop.start);
+        backend.set_ab_loop_b(ab_loop.end);
+        SCRUBBER_BROKER.send(ScrubberInput::SetAbLoop(ab_loop));
+        self.ab_loop = ab_loop;
     }
 
     fn fetch_next_prev(&self, sender: &ComponentSender<Self>, item: &BaseItemDto) {
@@ -727,6 +1074,268 @@ impl VideoPlayer {
 
         sender.oneshot_command(async { VideoPlayerCommandOutput::LoadedNextPrev((None, None)) });
     }
+
+    fn fetch_playback_info(&self, sender: &ComponentSender<Self>, item: &BaseItemDto) {
+        let (Some(api_client), Some(item_id)) = (&self.api_client, item.id) else {
+            return;
+        };
+        let series_id = item.series_id;
+
+        sender.oneshot_command({
+            let api_client = api_client.clone();
+
+            async move {
+                let user_configuration =
+                    api_client
+                        .get_user_configuration()
+                        .await
+                        .unwrap_or_else(|err| {
+                            warn!("Error getting user configuration: {err}");
+                            Default::default()
+                        });
+                let preferences = TrackPreferences::new(
+                    user_configuration,
+                    &CONFIG.read().video_player.subtitles,
+                );
+
+                let (play_session_id, media_source) =
+                    match api_client.get_playback_info(&item_id).await {
+                        Ok(playback_info) => (
+                            playback_info.play_session_id,
+                            playback_info.media_sources.into_iter().next(),
+                        ),
+                        Err(err) => {
+                            warn!("Error getting playback info: {err}");
+                            (None, None)
+                        }
+                    };
+                let media_streams = media_source
+                    .as_ref()
+                    .and_then(|media_source| media_source.media_streams.clone())
+                    .unwrap_or_default();
+
+                let series_tracks = series_id.and_then(|series_id| get_series_tracks(&series_id));
+
+                VideoPlayerCommandOutput::LoadedPlaybackInfo(
+                    item_id,
+                    TrackSelector::new(preferences, series_id, series_tracks, &media_streams),
+                    play_session_id,
+                    media_source.map(Box::new),
+                )
+            }
+        });
+    }
+}
+
+// Passes on what the backend reports as video player inputs
+fn connect_backend(
+    backend: &RefCell<dyn VideoPlayerBackend>,
+    hiding: &Arc<AtomicBool>,
+    send: impl Fn(VideoPlayerInput) + Clone + Send + 'static,
+) {
+    let mut backend = backend.borrow_mut();
+
+    backend.connect_player_state_changed({
+        let send = send.clone();
+        Box::new(move |state| {
+            if cfg!(debug_assertions) {
+                debug!("Player state changed: {state:#?}");
+            }
+
+            send(VideoPlayerInput::PlayerStateChanged(state));
+        })
+    });
+
+    backend.connect_end_of_stream({
+        let send = send.clone();
+        let hiding = hiding.clone();
+        Box::new(move || {
+            if !hiding.load(atomic::Ordering::Relaxed) {
+                send(VideoPlayerInput::EndOfStream);
+            }
+        })
+    });
+
+    backend.connect_error(Box::new(move |error| {
+        send(VideoPlayerInput::PlaybackError(error));
+    }));
+}
+
+// What's playing and what's up next, which decide what happens once a video ends
+#[derive(Debug)]
+struct Playback {
+    state: PlayerState,
+    next: Option<BaseItemDto>,
+}
+
+#[derive(Debug)]
+enum EndOfStreamAction {
+    Ignore,
+    PlayNext(BaseItemDto),
+    ExitPip,
+    NavigateBack,
+}
+
+impl Playback {
+    // Keeps up with what the backend reports, returning what to do if the video ended
+    fn update(&mut self, input: VideoPlayerInput, in_pip: bool) -> Option<EndOfStreamAction> {
+        match input {
+            VideoPlayerInput::PlayerStateChanged(state) => {
+                self.state = state;
+                None
+            }
+            VideoPlayerInput::EndOfStream => Some(self.end_of_stream_action(in_pip)),
+            _ => None,
+        }
+    }
+
+    fn end_of_stream_action(&self, in_pip: bool) -> EndOfStreamAction {
+        // Only videos that were actually playing should move on
+        if !matches!(self.state, PlayerState::Playing { .. }) {
+            return EndOfStreamAction::Ignore;
+        }
+
+        // Play next episode if available
+        if let Some(next) = &self.next {
+            return EndOfStreamAction::PlayNext(next.clone());
+        }
+
+        if in_pip {
+            return EndOfStreamAction::ExitPip;
+        }
+
+        EndOfStreamAction::NavigateBack
+    }
+}
+
+fn seek_osd_label(seconds: isize) -> String {
+    tr!("vp-osd-seek", { "seconds" => format!("{seconds:+}") }).to_string()
 }
 
 pub static VIDEO_PLAYER_BROKER: MessageBroker<VideoPlayerInput> = MessageBroker::new();
+
+#[cfg(test)]
+mod tests {
+    use super::backends::fake::{Inputs, VideoPlayerBackendFake};
+
+    use super::*;
+
+    // Connects the fake backend the way the video player does, and passes its inputs on to the
+    // video player's playback state
+    struct Harness {
+        fake: Arc<RefCell<VideoPlayerBackendFake>>,
+        hiding: Arc<AtomicBool>,
+        inputs: Inputs<VideoPlayerInput>,
+        playback: Playback,
+    }
+
+    impl Harness {
+        fn new(fake: VideoPlayerBackendFake) -> Self {
+            let fake = Arc::new(RefCell::new(fake));
+            let hiding: Arc<AtomicBool> = Arc::default();
+            let inputs = Inputs::default();
+            connect_backend(&*fake, &hiding, inputs.sender());
+
+            Self {
+                fake,
+                hiding,
+                inputs,
+                playback: Playback {
+                    state: PlayerState::Loading,
+                    next: None,
+                },
+            }
+        }
+
+        // What the video player does with each end of stream it was told about
+        fn end_of_stream_actions(&mut self, in_pip: bool) -> Vec<EndOfStreamAction> {
+            self.inputs
+                .take()
+                .into_iter()
+                .filter_map(|input| self.playback.update(input, in_pip))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_end_of_stream_plays_next() -> anyhow::Result<()> {
+        let next: BaseItemDto = BaseItemDto::builder()
+            .name(Some("Next Episode".to_string()))
+            .try_into()?;
+        let path = std::env::temp_dir().join("delfin-end-of-stream-test.mkv");
+        std::fs::write(&path, b"")?;
+        let uri = gtk::glib::filename_to_uri(&path, None)?;
+        let mut harness = Harness::new(VideoPlayerBackendFake::simulated(30));
+
+        // Loaded once playback starts, see VideoPlayerCommandOutput::LoadedNextPrev
+        harness.fake.borrow_mut().play_uri(&uri);
+        harness.playback.next = Some(next);
+
+        harness.fake.borrow().seek_to(29);
+        let actions = harness.end_of_stream_actions(true);
+        assert!(
+            matches!(&actions[..], [EndOfStreamAction::PlayNext(item)] if item.name.as_deref() == Some("Next Episode")),
+            "{actions:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_end_of_stream_without_next() {
+        let mut harness = Harness::new(VideoPlayerBackendFake::default());
+        harness
+            .fake
+            .borrow()
+            .emit_player_state(PlayerState::Playing { paused: true });
+
+        harness.fake.borrow().emit_end_of_stream();
+        assert!(matches!(
+            harness.end_of_stream_actions(false)[..],
+            [EndOfStreamAction::NavigateBack]
+        ));
+
+        harness.fake.borrow().emit_end_of_stream();
+        assert!(matches!(
+            harness.end_of_stream_actions(true)[..],
+            [EndOfStreamAction::ExitPip]
+        ));
+    }
+
+    #[test]
+    fn test_end_of_stream_ignored_when_not_playing() -> anyhow::Result<()> {
+        let mut harness = Harness::new(VideoPlayerBackendFake::default());
+        harness.playback.next = Some(BaseItemDto::builder().try_into()?);
+
+        // Still loading
+        harness.fake.borrow().emit_end_of_stream();
+        assert!(matches!(
+            harness.end_of_stream_actions(false)[..],
+            [EndOfStreamAction::Ignore]
+        ));
+
+        // Failed after it started playing
+        let fake = harness.fake.borrow();
+        fake.emit_player_state(PlayerState::Playing { paused: false });
+        fake.emit_error("Failed to open file");
+        fake.emit_end_of_stream();
+        drop(fake);
+        let actions = harness.end_of_stream_actions(false);
+        assert!(matches!(harness.playback.state, PlayerState::Error));
+        assert!(matches!(actions[..], [EndOfStreamAction::Ignore]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_end_of_stream_ignored_while_hiding() {
+        let mut harness = Harness::new(VideoPlayerBackendFake::default());
+        harness
+            .fake
+            .borrow()
+            .emit_player_state(PlayerState::Playing { paused: false });
+
+        // Leaving the player stops playback, which shouldn't move on to the next video
+        harness.hiding.store(true, atomic::Ordering::Relaxed);
+        harness.fake.borrow().emit_end_of_stream();
+        assert!(harness.end_of_stream_actions(false).is_empty());
+    }
+}
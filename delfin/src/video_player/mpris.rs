@@ -17,14 +17,12 @@ use crate::{
     jellyfin_api::api_client::ApiClient,
     meson_config::APP_ID,
     utils::item_name::ItemName,
-    video_player::controls::next_prev_episode::{
-        NextPrevEpisodeInput, NEXT_EPISODE_BROKER, PREV_EPISODE_BROKER,
-    },
 };
 
 use super::{
     backends::{PlayerState, VideoPlayerBackend},
     controls::{
+        chapters::{ChaptersInput, CHAPTERS_BROKER},
         play_pause::{PlayPauseInput, PLAY_PAUSE_BROKER},
         skip_forwards_backwards::{
             SkipForwardsBackwardsInput, SKIP_BACKWARDS_BROKER, SKIP_FORWARDS_BROKER,
@@ -44,6 +42,7 @@ enum MprisInput {
 pub struct MprisPlaybackReporter {
     video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
     signal_handler_ids: Vec<Uuid>,
+    player_state_signal_handler_id: Uuid,
     tx: UnboundedSender<MprisInput>,
 }
 
@@ -66,58 +65,7 @@ impl MprisPlaybackReporter {
 
         let (tx, mut rx) = mpsc::unbounded_channel::<MprisInput>();
 
-        let mut signal_handler_ids = Vec::new();
-
-        signal_handler_ids.push(
-            video_player
-                .borrow_mut()
-                .connect_player_state_changed(Box::new({
-                    let tx = tx.clone();
-                    move |player_state| {
-                        if tx.is_closed() {
-                            return;
-                        }
-                        if let PlayerState::Playing { paused } = player_state {
-                            tx.send(if paused {
-                                MprisInput::Pause
-                            } else {
-                                MprisInput::Play
-                            })
-                            .expect("Failed to update MPRIS state");
-                        }
-                    }
-                })),
-        );
-
-        signal_handler_ids.push(
-            video_player
-                .borrow_mut()
-                .connect_duration_updated(Box::new({
-                    let tx = tx.clone();
-                    move |duration| {
-                        if tx.is_closed() {
-                            return;
-                        }
-                        tx.send(MprisInput::Duration(duration))
-                            .expect("Failed to update MPRIS duration");
-                    }
-                })),
-        );
-
-        signal_handler_ids.push(
-            video_player
-                .borrow_mut()
-                .connect_position_updated(Box::new({
-                    let tx = tx.clone();
-                    move |position| {
-                        if tx.is_closed() {
-                            return;
-                        }
-                        tx.send(MprisInput::Position(position))
-                            .expect("Failed to update MPRIS position");
-                    }
-                })),
-        );
+        let reporter = Self::connect(video_player, tx);
 
         tokio::spawn({
             let controls = controls.clone();
@@ -192,9 +140,64 @@ impl MprisPlaybackReporter {
             }
         });
 
+        reporter
+    }
+
+    /// Forwards the player's state to the media controls through `tx`.
+    fn connect(
+        video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
+        tx: UnboundedSender<MprisInput>,
+    ) -> Self {
+        let player_state_signal_handler_id = video_player
+            .borrow_mut()
+            .connect_player_state_changed(Box::new({
+                let tx = tx.clone();
+                move |player_state| {
+                    if tx.is_closed() {
+                        return;
+                    }
+                    if let PlayerState::Playing { paused } = player_state {
+                        tx.send(if paused {
+                            MprisInput::Pause
+                        } else {
+                            MprisInput::Play
+                        })
+                        .expect("Failed to update MPRIS state");
+                    }
+                }
+            }));
+
+        let signal_handler_ids = vec![
+            video_player
+                .borrow_mut()
+                .connect_duration_updated(Box::new({
+                    let tx = tx.clone();
+                    move |duration| {
+                        if tx.is_closed() {
+                            return;
+                        }
+                        tx.send(MprisInput::Duration(duration))
+                            .expect("Failed to update MPRIS duration");
+                    }
+                })),
+            video_player
+                .borrow_mut()
+                .connect_position_updated(Box::new({
+                    let tx = tx.clone();
+                    move |position| {
+                        if tx.is_closed() {
+                            return;
+                        }
+                        tx.send(MprisInput::Position(position))
+                            .expect("Failed to update MPRIS position");
+                    }
+                })),
+        ];
+
         Self {
             video_player,
-            signal_handler_ids: Vec::default(),
+            signal_handler_ids,
+            player_state_signal_handler_id,
             tx,
         }
     }
@@ -207,6 +210,9 @@ impl Drop for MprisPlaybackReporter {
                 .borrow_mut()
                 .disconnect_signal_handler(&id);
         }
+        self.video_player
+            .borrow_mut()
+            .disconnect_player_state_changed(self.player_state_signal_handler_id);
 
         self.tx
             .send(MprisInput::Close)
@@ -248,10 +254,10 @@ fn attach_media_control_events(controls: &mut MediaControls) {
                     SKIP_BACKWARDS_BROKER.send(SkipForwardsBackwardsInput::SkipTo(position));
                 }
                 MediaControlEvent::Previous => {
-                    PREV_EPISODE_BROKER.send(NextPrevEpisodeInput::Play);
+                    CHAPTERS_BROKER.send(ChaptersInput::PreviousChapterOrEpisode);
                 }
                 MediaControlEvent::Next => {
-                    NEXT_EPISODE_BROKER.send(NextPrevEpisodeInput::Play);
+                    CHAPTERS_BROKER.send(ChaptersInput::NextChapterOrEpisode);
                 }
                 MediaControlEvent::Stop | MediaControlEvent::Quit => {
                     APP_BROKER.send(AppInput::NavigateBack);
@@ -271,3 +277,34 @@ fn attach_media_control_events(controls: &mut MediaControls) {
         })
         .unwrap();
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::video_player::backends::fake::VideoPlayerBackendFake;
+
+    use super::*;
+
+    #[test]
+    fn test_forwards_player_updates() {
+        let fake = Arc::<RefCell<VideoPlayerBackendFake>>::default();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let reporter = MprisPlaybackReporter::connect(fake.clone(), tx);
+
+        fake.borrow().emit_duration(120);
+        fake.borrow()
+            .emit_player_state(PlayerState::Playing { paused: true });
+        fake.borrow().emit_position(30);
+        assert!(matches!(rx.try_recv(), Ok(MprisInput::Duration(120))));
+        assert!(matches!(rx.try_recv(), Ok(MprisInput::Pause)));
+        assert!(matches!(rx.try_recv(), Ok(MprisInput::Position(30))));
+
+        drop(reporter);
+        assert!(matches!(rx.try_recv(), Ok(MprisInput::Close)));
+
+        // Nothing is forwarded once the reporter is gone
+        fake.borrow()
+            .emit_player_state(PlayerState::Playing { paused: false });
+        fake.borrow().emit_position(31);
+        assert!(rx.try_recv().is_err());
+    }
+}
@@ -189,18 +189,9 @@ impl Component for NextUp {
             thumbnail: None,
         };
 
-        video_player.borrow_mut().connect_position_updated({
+        connect_video_player(&video_player, {
             let sender = sender.clone();
-            Box::new(move |position| {
-                sender.input(NextUpInput::SetPosition(position));
-            })
-        });
-
-        video_player.borrow_mut().connect_duration_updated({
-            let sender = sender.clone();
-            Box::new(move |duration| {
-                sender.input(NextUpInput::SetDuration(duration));
-            })
+            move |input| sender.input(input)
         });
 
         let widgets = view_output!();
@@ -223,24 +214,12 @@ impl Component for NextUp {
             NextUpInput::SetDuration(duration) => {
                 self.duration = Some(duration);
             }
-            NextUpInput::SetPosition(position) => match self.state {
-                NextUpState::Ready => {
-                    if let (Some(_), Some(duration)) = (&self.next_up.0, &self.duration) {
-                        if duration.saturating_sub(position) <= SHOW_NEXT_UP_AT {
-                            self.set_visible(true);
-                        }
-                    }
-                }
-                NextUpState::Shown(_) => {
-                    if let Some(duration) = &self.duration {
-                        self.state = NextUpState::Shown(duration.saturating_sub(position));
-                    }
-                }
-                NextUpState::Hidden => {}
-            },
+            NextUpInput::SetPosition(position) => {
+                self.set_position(position);
+            }
             NextUpInput::PlayNext => {
                 if let Some(next_up) = &self.next_up.0 {
-                    APP_BROKER.send(AppInput::PlayVideo(next_up.item.clone()));
+                    APP_BROKER.send(AppInput::ContinuePlayback(next_up.item.clone()));
                 }
             }
             NextUpInput::Hide => {
@@ -263,6 +242,24 @@ impl Component for NextUp {
 }
 
 impl NextUp {
+    fn set_position(&mut self, position: usize) {
+        match self.state {
+            NextUpState::Ready => {
+                if let (Some(_), Some(duration)) = (&self.next_up.0, &self.duration) {
+                    if duration.saturating_sub(position) <= SHOW_NEXT_UP_AT {
+                        self.set_visible(true);
+                    }
+                }
+            }
+            NextUpState::Shown(_) => {
+                if let Some(duration) = &self.duration {
+                    self.state = NextUpState::Shown(duration.saturating_sub(position));
+                }
+            }
+            NextUpState::Hidden => {}
+        }
+    }
+
     fn set_visible(&mut self, visible: bool) {
         if visible {
             self.state = NextUpState::Shown(SHOW_NEXT_UP_AT);
@@ -290,3 +287,110 @@ impl NextUp {
         }
     }
 }
+
+// Passes on the video's position and duration as next up inputs
+fn connect_video_player(
+    video_player: &RefCell<dyn VideoPlayerBackend>,
+    send: impl Fn(NextUpInput) + Clone + Send + Sync + 'static,
+) {
+    let mut video_player = video_player.borrow_mut();
+
+    video_player.connect_position_updated({
+        let send = send.clone();
+        Box::new(move |position| {
+            send(NextUpInput::SetPosition(position));
+        })
+    });
+
+    video_player.connect_duration_updated(Box::new(move |duration| {
+        send(NextUpInput::SetDuration(duration));
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::video_player::backends::fake::{Inputs, VideoPlayerBackendFake};
+
+    use super::*;
+
+    fn next_up(next: Option<BaseItemDto>) -> NextUp {
+        NextUp {
+            state: NextUpState::Ready,
+            next_up: MaybeNextUpItem(next.map(NextUpItem::new)),
+            duration: None,
+            thumbnail: None,
+        }
+    }
+
+    fn connect(fake: &Arc<RefCell<VideoPlayerBackendFake>>) -> Inputs<NextUpInput> {
+        let inputs = Inputs::default();
+        connect_video_player(&**fake, inputs.sender());
+        inputs
+    }
+
+    fn handle_inputs(next_up: &mut NextUp, inputs: &Inputs<NextUpInput>) {
+        for input in inputs.take() {
+            match input {
+                NextUpInput::SetDuration(duration) => next_up.duration = Some(duration),
+                NextUpInput::SetPosition(position) => next_up.set_position(position),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_next_up_countdown() -> anyhow::Result<()> {
+        let next: BaseItemDto = BaseItemDto::builder()
+            .name(Some("Next Episode".to_string()))
+            .try_into()?;
+        let mut next_up = next_up(Some(next));
+        let fake = Arc::<RefCell<VideoPlayerBackendFake>>::default();
+        let inputs = connect(&fake);
+
+        fake.borrow().emit_duration(100);
+        fake.borrow().emit_position(60);
+        handle_inputs(&mut next_up, &inputs);
+        assert!(matches!(next_up.state, NextUpState::Ready));
+
+        fake.borrow().emit_position(70);
+        handle_inputs(&mut next_up, &inputs);
+        assert!(matches!(next_up.state, NextUpState::Shown(SHOW_NEXT_UP_AT)));
+
+        fake.borrow().emit_position(88);
+        handle_inputs(&mut next_up, &inputs);
+        assert!(matches!(next_up.state, NextUpState::Shown(12)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_next_up_hidden_without_next_item() {
+        let mut next_up = next_up(None);
+        let fake = Arc::<RefCell<VideoPlayerBackendFake>>::default();
+        let inputs = connect(&fake);
+
+        fake.borrow().emit_duration(100);
+        fake.borrow().emit_position(90);
+        handle_inputs(&mut next_up, &inputs);
+        assert!(matches!(next_up.state, NextUpState::Ready));
+    }
+
+    #[test]
+    fn test_next_up_stays_hidden_once_dismissed() -> anyhow::Result<()> {
+        let next: BaseItemDto = BaseItemDto::builder().try_into()?;
+        let mut next_up = next_up(Some(next));
+        let fake = Arc::<RefCell<VideoPlayerBackendFake>>::default();
+        let inputs = connect(&fake);
+
+        fake.borrow().emit_duration(100);
+        fake.borrow().emit_position(80);
+        handle_inputs(&mut next_up, &inputs);
+        next_up.set_visible(false);
+
+        fake.borrow().emit_position(90);
+        handle_inputs(&mut next_up, &inputs);
+        assert!(matches!(next_up.state, NextUpState::Hidden));
+
+        Ok(())
+    }
+}
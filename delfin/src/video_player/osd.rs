@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use gtk::prelude::*;
+use relm4::prelude::*;
+
+use crate::utils::debounce::Debounce;
+
+// How long feedback stays on screen after the last change
+const OSD_HIDE_TIMEOUT: Duration = Duration::from_millis(1200);
+
+/// Briefly shows feedback in the middle of the video, like the volume while it's being changed
+/// with a gesture.
+pub(crate) struct Osd {
+    label: String,
+    visible: bool,
+    hide_debounce: Debounce,
+}
+
+#[derive(Debug)]
+pub(crate) enum OsdInput {
+    Show(String),
+    Hide,
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for Osd {
+    type Init = ();
+    type Input = OsdInput;
+    type Output = ();
+
+    view! {
+        gtk::Revealer {
+            #[watch]
+            set_reveal_child: model.visible,
+            set_transition_type: gtk::RevealerTransitionType::Crossfade,
+            set_halign: gtk::Align::Center,
+            set_valign: gtk::Align::Center,
+            set_can_target: false,
+
+            gtk::Label {
+                #[watch]
+                set_label: &model.label,
+                add_css_class: "osd",
+                add_css_class: "gesture-osd",
+                add_css_class: "numeric",
+            },
+        }
+    }
+
+    fn init(
+        _init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let hide_debounce = Debounce::new(
+            OSD_HIDE_TIMEOUT,
+            Box::new({
+                let sender = sender.clone();
+                move || {
+                    sender.input(OsdInput::Hide);
+                }
+            }),
+        );
+
+        let model = Osd {
+            label: String::new(),
+            visible: false,
+            hide_debounce,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+        match message {
+            OsdInput::Show(label) => {
+                self.label = label;
+                self.visible = true;
+                self.hide_debounce.debounce();
+            }
+            OsdInput::Hide => {
+                self.visible = false;
+            }
+        }
+    }
+}
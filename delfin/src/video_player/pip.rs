@@ -0,0 +1,163 @@
+use gtk::{glib, prelude::*};
+use relm4::prelude::*;
+
+use crate::{
+    tr,
+    video_player::{
+        backends::PlayerState,
+        controls::play_pause::{PlayPauseInput, PLAY_PAUSE_BROKER},
+    },
+};
+
+// Fits a 16:9 video
+const DEFAULT_WIDTH: i32 = 400;
+const DEFAULT_HEIGHT: i32 = 225;
+
+/// Small window that keeps the video playing while the main window is used to browse the library.
+pub(crate) struct PipWindow {
+    playing: bool,
+    show_controls: bool,
+}
+
+pub(crate) struct PipWindowInit {
+    /// The backend's widget, which is moved into this window until it's closed.
+    pub video: gtk::Widget,
+    pub title: String,
+    pub playing: bool,
+}
+
+#[derive(Debug)]
+pub(crate) enum PipWindowInput {
+    SetPlayerState(PlayerState),
+    SetShowControls(bool),
+}
+
+#[derive(Debug)]
+pub(crate) enum PipWindowOutput {
+    Close,
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for PipWindow {
+    type Init = PipWindowInit;
+    type Input = PipWindowInput;
+    type Output = PipWindowOutput;
+
+    view! {
+        gtk::Window {
+            set_title: Some(&title),
+            set_decorated: false,
+            set_resizable: true,
+            set_default_width: DEFAULT_WIDTH,
+            set_default_height: DEFAULT_HEIGHT,
+            set_visible: true,
+
+            // The video has to be moved out before the window is destroyed, so let the video
+            // player close it
+            connect_close_request[sender] => move |_| {
+                sender.output(PipWindowOutput::Close).unwrap();
+                glib::Propagation::Stop
+            },
+
+            // Lets the window be moved by dragging the video, since there's no title bar
+            #[wrap(Some)]
+            set_child = &gtk::WindowHandle {
+                #[wrap(Some)]
+                set_child = &gtk::Overlay {
+                    add_css_class: "video-player",
+                    set_child: Some(&video),
+
+                    add_controller = gtk::EventControllerMotion {
+                        connect_enter[sender] => move |_, _, _| {
+                            sender.input(PipWindowInput::SetShowControls(true));
+                        },
+                        connect_leave[sender] => move |_| {
+                            sender.input(PipWindowInput::SetShowControls(false));
+                        },
+                    },
+
+                    add_overlay = &gtk::Revealer {
+                        #[watch]
+                        set_reveal_child: model.show_controls,
+                        set_transition_type: gtk::RevealerTransitionType::Crossfade,
+
+                        #[wrap(Some)]
+                        set_child = &gtk::CenterBox {
+                            set_orientation: gtk::Orientation::Vertical,
+                            set_margin_all: 6,
+
+                            #[wrap(Some)]
+                            set_start_widget = &gtk::Button {
+                                set_icon_name: "window-close-symbolic",
+                                set_tooltip_text: Some(tr!("vp-pip-close-tooltip")),
+                                set_halign: gtk::Align::End,
+                                set_focus_on_click: false,
+                                add_css_class: "osd",
+                                add_css_class: "circular",
+                                connect_clicked[sender] => move |_| {
+                                    sender.output(PipWindowOutput::Close).unwrap();
+                                },
+                            },
+
+                            #[wrap(Some)]
+                            set_center_widget = &gtk::Button {
+                                #[watch]
+                                set_icon_name: if model.playing {
+                                    "pause-filled"
+                                } else {
+                                    "play-filled"
+                                },
+                                #[watch]
+                                set_tooltip_text: Some(tr!(
+                                    "vp-play-pause-tooltip",
+                                    {"playing" => model.playing.to_string()},
+                                )),
+                                set_halign: gtk::Align::Center,
+                                set_focus_on_click: false,
+                                add_css_class: "osd",
+                                add_css_class: "circular",
+                                connect_clicked => move |_| {
+                                    PLAY_PAUSE_BROKER.send(PlayPauseInput::TogglePlaying);
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let PipWindowInit {
+            video,
+            title,
+            playing,
+        } = init;
+
+        let model = PipWindow {
+            playing,
+            show_controls: false,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+        match message {
+            PipWindowInput::SetPlayerState(player_state) => {
+                if let PlayerState::Playing { paused } = player_state {
+                    self.playing = !paused;
+                }
+            }
+            PipWindowInput::SetShowControls(show_controls) => {
+                self.show_controls = show_controls;
+            }
+        }
+    }
+}
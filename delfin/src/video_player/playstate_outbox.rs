@@ -0,0 +1,444 @@
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::Result;
+use relm4::once_cell::sync::Lazy;
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::{
+    jellyfin_api::{
+        api::playstate::{PlaybackSession, PlaybackStatus},
+        api_client::ApiClient,
+    },
+    library::LIBRARY_REFRESH_QUEUED,
+    media_details::MEDIA_DETAILS_REFRESH_QUEUED,
+    utils::data_file::{read_data_file, write_data_file},
+};
+
+const PLAYSTATE_OUTBOX_FILE: &str = "playstate_outbox.json";
+
+// How long to wait before trying to send reports again after the server couldn't be reached
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// A playback report that still has to be sent to the server.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum PlaystateReport {
+    Started {
+        item_id: Uuid,
+        #[serde(default)]
+        session: PlaybackSession,
+        #[serde(default)]
+        status: PlaybackStatus,
+    },
+    Progress {
+        event_name: String,
+        item_id: Uuid,
+        position: usize,
+        #[serde(default)]
+        session: PlaybackSession,
+        #[serde(default)]
+        status: PlaybackStatus,
+    },
+    Stopped {
+        item_id: Uuid,
+        position: usize,
+        #[serde(default)]
+        session: PlaybackSession,
+        #[serde(default)]
+        failed: bool,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+struct QueuedReport {
+    account_id: Uuid,
+    report: PlaystateReport,
+}
+
+/// Reports waiting to be sent, oldest first.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
+struct PlaystateOutbox(Vec<QueuedReport>);
+
+impl PlaystateOutbox {
+    fn push(&mut self, account_id: Uuid, report: PlaystateReport) {
+        let item_id = report.item_id();
+        match report {
+            // Only the latest position matters, so a new position update replaces one that
+            // hasn't been sent yet. Other events like pausing are still sent.
+            PlaystateReport::Progress { .. } if report.is_time_update() => {
+                if let Some(last) = self
+                    .0
+                    .iter_mut()
+                    .rev()
+                    .find(|queued| queued.account_id == account_id)
+                {
+                    if last.report.is_time_update() && last.report.item_id() == item_id {
+                        last.report = report;
+                        return;
+                    }
+                }
+            }
+            PlaystateReport::Progress { .. } => {}
+            // Stopping includes the final position, so earlier progress reports aren't needed
+            PlaystateReport::Stopped { .. } => {
+                self.0.retain(|queued| {
+                    queued.account_id != account_id
+                        || !matches!(queued.report, PlaystateReport::Progress { .. })
+                        || queued.report.item_id() != item_id
+                });
+            }
+            PlaystateReport::Started { .. } => {}
+        }
+
+        self.0.push(QueuedReport { account_id, report });
+    }
+
+    fn next(&self, account_id: Uuid) -> Option<PlaystateReport> {
+        self.0
+            .iter()
+            .find(|queued| queued.account_id == account_id)
+            .map(|queued| queued.report.clone())
+    }
+
+    fn remove(&mut self, account_id: Uuid, report: &PlaystateReport) {
+        // The report may have been replaced or dropped while it was being sent
+        if let Some(index) = self
+            .0
+            .iter()
+            .position(|queued| queued.account_id == account_id && queued.report == *report)
+        {
+            self.0.remove(index);
+        }
+    }
+}
+
+impl PlaystateReport {
+    fn item_id(&self) -> Uuid {
+        match self {
+            PlaystateReport::Started { item_id, .. }
+            | PlaystateReport::Progress { item_id, .. }
+            | PlaystateReport::Stopped { item_id, .. } => *item_id,
+        }
+    }
+
+    fn is_time_update(&self) -> bool {
+        matches!(self, PlaystateReport::Progress { event_name, .. } if event_name == "timeupdate")
+    }
+}
+
+static PLAYSTATE_OUTBOX: Lazy<Mutex<PlaystateOutbox>> = Lazy::new(|| {
+    let outbox: PlaystateOutbox = read_data_file(PLAYSTATE_OUTBOX_FILE);
+    SAVED_REPORTS.store(!outbox.0.is_empty(), Ordering::Release);
+    Mutex::new(outbox)
+});
+
+// Whether the outbox file has reports in it, which need clearing out once they've been sent
+static SAVED_REPORTS: AtomicBool = AtomicBool::new(false);
+
+// Reports for an account have to be sent one at a time to keep them in order
+static SENDING: Lazy<Mutex<HashSet<Uuid>>> = Lazy::new(Mutex::default);
+static RETRYING: Lazy<Mutex<HashSet<Uuid>>> = Lazy::new(Mutex::default);
+
+fn save_outbox(outbox: &PlaystateOutbox) {
+    if let Err(err) = write_data_file(PLAYSTATE_OUTBOX_FILE, outbox) {
+        warn!("Error saving playstate outbox: {err}");
+    }
+    SAVED_REPORTS.store(!outbox.0.is_empty(), Ordering::Release);
+}
+
+/// Sends a playback report, along with any earlier reports that couldn't be sent. Reports are
+/// only written to disk if they can't be sent.
+pub fn queue_report(api_client: &Arc<ApiClient>, report: PlaystateReport) {
+    PLAYSTATE_OUTBOX
+        .lock()
+        .unwrap()
+        .push(api_client.account.id, report);
+    flush(api_client.clone());
+}
+
+/// Saves a playback report to disk without sending it yet.
+pub fn save_report(api_client: &ApiClient, report: PlaystateReport) {
+    let mut outbox = PLAYSTATE_OUTBOX.lock().unwrap();
+    outbox.push(api_client.account.id, report);
+    save_outbox(&outbox);
+}
+
+/// Sends reports left over from earlier, retrying until the server can be reached.
+pub fn flush(api_client: Arc<ApiClient>) {
+    relm4::spawn(async move {
+        if send_pending(&api_client).await {
+            return;
+        }
+
+        // Only one task per account needs to keep retrying
+        let account_id = api_client.account.id;
+        if !RETRYING.lock().unwrap().insert(account_id) {
+            return;
+        }
+        loop {
+            sleep(RETRY_INTERVAL).await;
+            if send_pending(&api_client).await {
+                break;
+            }
+        }
+        RETRYING.lock().unwrap().remove(&account_id);
+    });
+}
+
+/// Sends reports until none are left for this account. Returns false if the server couldn't be
+/// reached, in which case the remaining reports are kept for later.
+pub async fn send_pending(api_client: &ApiClient) -> bool {
+    let account_id = api_client.account.id;
+
+    loop {
+        // Another task is already sending, and will pick up anything new
+        if !SENDING.lock().unwrap().insert(account_id) {
+            return true;
+        }
+
+        let sent_all = send_queued(api_client, account_id).await;
+        SENDING.lock().unwrap().remove(&account_id);
+
+        let next = {
+            let outbox = PLAYSTATE_OUTBOX.lock().unwrap();
+            // Keep unsent reports in case Delfin is closed before they can be sent, and stop
+            // reports that were saved earlier from being sent again
+            if !sent_all || SAVED_REPORTS.load(Ordering::Acquire) {
+                save_outbox(&outbox);
+            }
+            // Check for reports that were queued just as sending finished
+            outbox.next(account_id)
+        };
+        if !sent_all || next.is_none() {
+            return sent_all;
+        }
+    }
+}
+
+async fn send_queued(api_client: &ApiClient, account_id: Uuid) -> bool {
+    loop {
+        let next = PLAYSTATE_OUTBOX.lock().unwrap().next(account_id);
+        let Some(report) = next else {
+            return true;
+        };
+
+        match send_report(api_client, &report).await {
+            Ok(()) => {
+                debug!("Sent playstate report: {report:?}");
+                if matches!(report, PlaystateReport::Stopped { .. }) {
+                    *LIBRARY_REFRESH_QUEUED.write() = true;
+                    *MEDIA_DETAILS_REFRESH_QUEUED.write() = true;
+                }
+            }
+            Err(err) if is_rejected(&err) => {
+                // Retrying won't help if the server doesn't accept the report
+                warn!("Server rejected playstate report {report:?}: {err}");
+            }
+            Err(err) => {
+                warn!("Error sending playstate report, will retry: {err}");
+                return false;
+            }
+        }
+        PLAYSTATE_OUTBOX.lock().unwrap().remove(account_id, &report);
+    }
+}
+
+async fn send_report(api_client: &ApiClient, report: &PlaystateReport) -> Result<()> {
+    match report {
+        PlaystateReport::Started {
+            item_id,
+            session,
+            status,
+        } => {
+            api_client
+                .report_playback_started(*item_id, session, status)
+                .await
+        }
+        PlaystateReport::Progress {
+            event_name,
+            item_id,
+            position,
+            session,
+            status,
+        } => {
+            api_client
+                .report_playback_progress(event_name, *item_id, *position, session, status)
+                .await
+        }
+        PlaystateReport::Stopped {
+            item_id,
+            position,
+            session,
+            failed,
+        } => {
+            api_client
+                .report_playback_stopped(*item_id, *position, session, *failed)
+                .await
+        }
+    }
+}
+
+fn is_rejected(err: &anyhow::Error) -> bool {
+    let status = err
+        .downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status);
+    // Authentication errors aren't included, since the reports can still be sent after signing
+    // in again
+    matches!(
+        status,
+        Some(StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND | StatusCode::UNPROCESSABLE_ENTITY)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(item_id: Uuid, position: usize) -> PlaystateReport {
+        PlaystateReport::Progress {
+            event_name: "timeupdate".into(),
+            item_id,
+            position,
+            session: PlaybackSession::default(),
+            status: PlaybackStatus::default(),
+        }
+    }
+
+    fn pause(item_id: Uuid, position: usize) -> PlaystateReport {
+        PlaystateReport::Progress {
+            event_name: "pause".into(),
+            item_id,
+            position,
+            session: PlaybackSession::default(),
+            status: PlaybackStatus::default(),
+        }
+    }
+
+    fn started(item_id: Uuid) -> PlaystateReport {
+        PlaystateReport::Started {
+            item_id,
+            session: PlaybackSession::default(),
+            status: PlaybackStatus::default(),
+        }
+    }
+
+    fn stopped(item_id: Uuid, position: usize) -> PlaystateReport {
+        PlaystateReport::Stopped {
+            item_id,
+            position,
+            session: PlaybackSession::default(),
+            failed: false,
+        }
+    }
+
+    fn reports(outbox: &PlaystateOutbox) -> Vec<PlaystateReport> {
+        outbox
+            .0
+            .iter()
+            .map(|queued| queued.report.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_collapse_progress() {
+        let account_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let mut outbox = PlaystateOutbox::default();
+
+        outbox.push(account_id, started(item_id));
+        outbox.push(account_id, progress(item_id, 10));
+        outbox.push(account_id, progress(item_id, 20));
+
+        assert_eq!(reports(&outbox), [started(item_id), progress(item_id, 20)]);
+    }
+
+    #[test]
+    fn test_keep_progress_events() {
+        let account_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let mut outbox = PlaystateOutbox::default();
+
+        outbox.push(account_id, progress(item_id, 10));
+        outbox.push(account_id, pause(item_id, 12));
+        outbox.push(account_id, pause(item_id, 12));
+        outbox.push(account_id, progress(item_id, 20));
+        outbox.push(account_id, progress(item_id, 30));
+
+        assert_eq!(
+            reports(&outbox),
+            [
+                progress(item_id, 10),
+                pause(item_id, 12),
+                pause(item_id, 12),
+                progress(item_id, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stopped_replaces_progress() {
+        let account_id = Uuid::new_v4();
+        let first_item_id = Uuid::new_v4();
+        let second_item_id = Uuid::new_v4();
+        let mut outbox = PlaystateOutbox::default();
+
+        outbox.push(account_id, progress(first_item_id, 10));
+        outbox.push(account_id, stopped(first_item_id, 15));
+        outbox.push(account_id, progress(second_item_id, 5));
+        // Playing the stopped item again starts new progress
+        outbox.push(account_id, progress(first_item_id, 30));
+
+        assert_eq!(
+            reports(&outbox),
+            [
+                stopped(first_item_id, 15),
+                progress(second_item_id, 5),
+                progress(first_item_id, 30),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_sent_in_order_per_account() {
+        let account_id = Uuid::new_v4();
+        let other_account_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let mut outbox = PlaystateOutbox::default();
+
+        outbox.push(other_account_id, progress(item_id, 50));
+        outbox.push(account_id, started(item_id));
+        outbox.push(account_id, progress(item_id, 10));
+
+        let next = outbox.next(account_id).unwrap();
+        assert_eq!(next, started(item_id));
+        outbox.remove(account_id, &next);
+        assert_eq!(outbox.next(account_id), Some(progress(item_id, 10)));
+        assert_eq!(outbox.next(other_account_id), Some(progress(item_id, 50)));
+    }
+
+    #[test]
+    fn test_remove_replaced_report() {
+        let account_id = Uuid::new_v4();
+        let item_id = Uuid::new_v4();
+        let mut outbox = PlaystateOutbox::default();
+
+        outbox.push(account_id, progress(item_id, 10));
+        let sending = outbox.next(account_id).unwrap();
+        // A newer position came in while the old one was being sent
+        outbox.push(account_id, progress(item_id, 20));
+        outbox.remove(account_id, &sending);
+
+        assert_eq!(reports(&outbox), [progress(item_id, 20)]);
+    }
+}
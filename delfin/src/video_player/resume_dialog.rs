@@ -0,0 +1,110 @@
+use adw::{prelude::*, ResponseAppearance};
+use jellyfin_api::types::BaseItemDto;
+use relm4::prelude::*;
+
+use crate::{
+    app::{AppInput, APP_BROKER},
+    tr,
+    utils::{item_name::ItemName, timestamp::seconds_to_timestamp},
+};
+
+enum Responses {
+    Cancel,
+    Restart,
+    Resume,
+}
+
+impl From<Responses> for &str {
+    fn from(val: Responses) -> Self {
+        match val {
+            Responses::Cancel => "cancel",
+            Responses::Restart => "restart",
+            Responses::Resume => "resume",
+        }
+    }
+}
+
+/// Asks whether to resume partially watched media, or start it from the beginning.
+pub(crate) struct ResumeDialog {
+    item: BaseItemDto,
+    resume_position: usize,
+}
+
+#[derive(Debug)]
+pub(crate) enum ResumeDialogInput {
+    Restart,
+    Resume,
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for ResumeDialog {
+    type Init = (BaseItemDto, usize);
+    type Input = ResumeDialogInput;
+    type Output = ();
+
+    view! {
+        adw::MessageDialog {
+            set_visible: true,
+            set_modal: true,
+            set_heading: Some(tr!("vp-resume-dialog.heading")),
+            set_body: &body,
+            add_responses: &[
+                (Responses::Cancel.into(), tr!("vp-resume-dialog.response-cancel")),
+                (Responses::Restart.into(), tr!("vp-resume-dialog.response-restart")),
+                (Responses::Resume.into(), &resume_label),
+            ],
+            set_default_response: Some(Responses::Resume.into()),
+            set_close_response: Responses::Cancel.into(),
+            set_response_appearance: (Responses::Resume.into(), ResponseAppearance::Suggested),
+            connect_response: (Some(Responses::Restart.into()), {
+                let sender = sender.clone();
+                move |_, _| {
+                    sender.input(ResumeDialogInput::Restart);
+                }
+            }),
+            connect_response: (Some(Responses::Resume.into()), move |_, _| {
+                sender.input(ResumeDialogInput::Resume);
+            }),
+        }
+    }
+
+    fn init(
+        init: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let (item, resume_position) = init;
+
+        let position = seconds_to_timestamp(resume_position);
+        let body = match item.series_and_episode().or(item.name.clone()) {
+            Some(name) => tr!("vp-resume-dialog.body", {
+                "name" => name,
+                "position" => position.clone(),
+            })
+            .to_string(),
+            None => tr!("vp-resume-dialog.body-unnamed", {
+                "position" => position.clone(),
+            })
+            .to_string(),
+        };
+        let resume_label =
+            tr!("vp-resume-dialog.response-resume", { "position" => position }).to_string();
+
+        let model = ResumeDialog {
+            item,
+            resume_position,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, _sender: ComponentSender<Self>) {
+        let start_position = match message {
+            ResumeDialogInput::Restart => 0,
+            ResumeDialogInput::Resume => self.resume_position,
+        };
+        APP_BROKER.send(AppInput::PlayVideoFrom(self.item.clone(), start_position));
+    }
+}
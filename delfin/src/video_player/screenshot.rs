@@ -0,0 +1,113 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use chrono::{DateTime, Local};
+
+use crate::{
+    config::video_player_config::VideoPlayerConfig, utils::timestamp::seconds_to_timestamp,
+};
+
+use super::backends::VideoPlayerBackend;
+
+pub(crate) const SCREENSHOT_FILENAME_DEFAULT: &str = "{title} {timestamp}";
+
+/// Save a screenshot of the current frame, returning the path it was saved to.
+pub(crate) fn take_screenshot(
+    player: &dyn VideoPlayerBackend,
+    config: &VideoPlayerConfig,
+    title: &str,
+    include_subtitles: bool,
+) -> anyhow::Result<PathBuf> {
+    let directory = screenshot_directory(config)?;
+    std::fs::create_dir_all(&directory)
+        .with_context(|| format!("Error creating {}", directory.display()))?;
+
+    let filename = screenshot_filename(
+        &config.screenshot_filename,
+        title,
+        player.position(),
+        Local::now(),
+    );
+    let path = unique_path(&directory, &filename);
+
+    player.screenshot(&path, include_subtitles)?;
+    Ok(path)
+}
+
+pub(crate) fn screenshot_directory(config: &VideoPlayerConfig) -> anyhow::Result<PathBuf> {
+    if let Some(directory) = &config.screenshot_directory {
+        return Ok(directory.clone());
+    }
+
+    dirs::picture_dir()
+        .map(|pictures| pictures.join("Screenshots"))
+        .context("Could not find pictures folder")
+}
+
+// Fill in the filename template, where {title} is the video's name, {timestamp} is the playback
+// position, and {date} is when the screenshot was taken
+fn screenshot_filename(
+    template: &str,
+    title: &str,
+    position: usize,
+    now: DateTime<Local>,
+) -> String {
+    let filename = template
+        .replace("{title}", title)
+        .replace(
+            "{timestamp}",
+            &seconds_to_timestamp(position).replace(':', "-"),
+        )
+        .replace("{date}", &now.format("%Y-%m-%d %H-%M-%S").to_string());
+
+    let filename: String = filename
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | '\0') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    let filename = filename.trim();
+
+    if filename.is_empty() {
+        "Screenshot".into()
+    } else {
+        filename.into()
+    }
+}
+
+// Avoid overwriting earlier screenshots with the same name
+fn unique_path(directory: &Path, filename: &str) -> PathBuf {
+    let mut path = directory.join(format!("{filename}.png"));
+    let mut count = 2;
+    while path.exists() {
+        path = directory.join(format!("{filename} ({count}).png"));
+        count += 1;
+    }
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_screenshot_filename() {
+        let now = Local.with_ymd_and_hms(2024, 5, 6, 7, 8, 9).unwrap();
+
+        assert_eq!(
+            screenshot_filename(SCREENSHOT_FILENAME_DEFAULT, "Big Buck Bunny", 345, now),
+            "Big Buck Bunny 05-45",
+        );
+        assert_eq!(
+            screenshot_filename("{date} - {title}", "AC/DC Live", 8624, now),
+            "2024-05-06 07-08-09 - AC_DC Live",
+        );
+        assert_eq!(screenshot_filename(" {title} ", "", 0, now), "Screenshot");
+    }
+}
@@ -0,0 +1,368 @@
+use std::{cell::RefCell, ops::Range, sync::Arc};
+
+use gtk::prelude::*;
+use relm4::{
+    component::{AsyncComponent, AsyncComponentParts},
+    prelude::*,
+    AsyncComponentSender,
+};
+use reqwest::StatusCode;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::{
+    config::video_player_config::{MediaSegmentBehaviour, VideoPlayerMediaSegmentsConfig},
+    globals::CONFIG,
+    jellyfin_api::{
+        api::media_segments::{MediaSegment, MediaSegmentType},
+        api_client::ApiClient,
+    },
+    tr,
+};
+
+use super::backends::VideoPlayerBackend;
+
+// How many seconds before a segment starts to show the auto skip countdown
+const AUTO_SKIP_COUNTDOWN: f32 = 5.0;
+
+#[derive(Debug)]
+enum State {
+    Hidden,
+    Visible(MediaSegmentType),
+    AutoSkipCountdown(MediaSegmentType, usize),
+}
+
+impl State {
+    fn visibility(&self) -> bool {
+        matches!(self, State::Visible(_) | State::AutoSkipCountdown(..))
+    }
+
+    fn label(&self) -> String {
+        match self {
+            Self::Visible(segment_type) => tr!("vp-skip-segment.manual", {
+                "segmentType" => segment_type_name(segment_type),
+            })
+            .to_string(),
+            Self::AutoSkipCountdown(segment_type, seconds) => tr!("vp-skip-segment.auto", {
+                "segmentType" => segment_type_name(segment_type),
+                "seconds" => seconds,
+            })
+            .to_string(),
+            State::Hidden => String::default(),
+        }
+    }
+}
+
+fn segment_type_name(segment_type: &MediaSegmentType) -> &'static str {
+    match segment_type {
+        MediaSegmentType::Intro => "intro",
+        MediaSegmentType::Outro => "outro",
+        MediaSegmentType::Recap => "recap",
+        MediaSegmentType::Preview => "preview",
+        MediaSegmentType::Commercial => "commercial",
+        MediaSegmentType::Unknown => "unknown",
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct SegmentSkipper {
+    video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
+    segments: Vec<MediaSegment>,
+    // Tracks which segments were automatically skipped, so they aren't skipped again if the user
+    // rewinds to watch them
+    already_skipped: Vec<bool>,
+    active_segment: Option<usize>,
+    state: State,
+    config: VideoPlayerMediaSegmentsConfig,
+    intro_skipper: bool,
+}
+
+#[derive(Debug)]
+pub(crate) enum SegmentSkipperInput {
+    Load(Uuid, Arc<ApiClient>),
+    PositionUpdate(usize),
+    SkipSegment,
+    ConfigUpdated(VideoPlayerMediaSegmentsConfig, bool),
+}
+
+#[relm4::component(pub(crate) async)]
+impl AsyncComponent for SegmentSkipper {
+    type Init = Arc<RefCell<dyn VideoPlayerBackend>>;
+    type Input = SegmentSkipperInput;
+    type Output = ();
+    type CommandOutput = ();
+
+    view! {
+        gtk::Button {
+            #[watch]
+            set_visible: model.state.visibility(),
+            #[watch]
+            set_label: model.state.label().as_ref(),
+            add_css_class: "opaque",
+            set_margin_bottom: 12,
+            set_halign: gtk::Align::End,
+
+            connect_clicked[sender] => move |_| {
+                sender.input(SegmentSkipperInput::SkipSegment);
+            },
+        }
+    }
+
+    async fn init(
+        video_player: Self::Init,
+        root: Self::Root,
+        sender: AsyncComponentSender<Self>,
+    ) -> AsyncComponentParts<Self> {
+        video_player
+            .borrow_mut()
+            .connect_position_updated(Box::new({
+                let sender = sender.clone();
+                move |position| {
+                    sender.input(SegmentSkipperInput::PositionUpdate(position));
+                }
+            }));
+
+        let model = SegmentSkipper {
+            video_player,
+            segments: vec![],
+            already_skipped: vec![],
+            active_segment: None,
+            state: State::Hidden,
+            config: CONFIG.read().video_player.media_segments.clone(),
+            intro_skipper: CONFIG.read().video_player.intro_skipper,
+        };
+
+        SegmentSkipper::subscribe_to_config(&sender);
+
+        let widgets = view_output!();
+
+        AsyncComponentParts { model, widgets }
+    }
+
+    async fn update(
+        &mut self,
+        message: Self::Input,
+        _sender: AsyncComponentSender<Self>,
+        _root: &Self::Root,
+    ) {
+        match message {
+            SegmentSkipperInput::Load(id, api_client) => {
+                let segments = match api_client.get_media_segments(&id).await {
+                    Ok(segments) => segments,
+                    // Servers older than 10.10 don't support media segments
+                    Err(err) if is_not_found(&err) => {
+                        debug!("Server doesn't support media segments");
+                        vec![]
+                    }
+                    Err(err) => {
+                        warn!("Error getting media segments for {id}: {err:#?}");
+                        vec![]
+                    }
+                };
+                self.segments = if segments.is_empty() && self.intro_skipper {
+                    // Fall back to the Intro Skipper plugin when the server has no segments
+                    get_intro_skipper_segments(&api_client, &id).await
+                } else {
+                    segments
+                };
+                self.already_skipped = vec![false; self.segments.len()];
+                self.active_segment = None;
+                self.state = State::Hidden;
+            }
+            SegmentSkipperInput::PositionUpdate(position) => self.set_position(position),
+            SegmentSkipperInput::SkipSegment => self.skip_segment(),
+            SegmentSkipperInput::ConfigUpdated(config, intro_skipper) => {
+                self.config = config;
+                self.intro_skipper = intro_skipper;
+            }
+        }
+    }
+}
+
+impl SegmentSkipper {
+    fn subscribe_to_config(sender: &AsyncComponentSender<Self>) {
+        CONFIG.subscribe(sender.input_sender(), |config| {
+            SegmentSkipperInput::ConfigUpdated(
+                config.video_player.media_segments.clone(),
+                config.video_player.intro_skipper,
+            )
+        });
+    }
+
+    fn set_position(&mut self, position: usize) {
+        let position = position as f32;
+
+        self.active_segment = None;
+        self.state = State::Hidden;
+
+        for (index, segment) in self.segments.iter().enumerate() {
+            let already_skipped = self.already_skipped[index];
+            let countdown_range = Range {
+                start: segment.start() - AUTO_SKIP_COUNTDOWN,
+                end: segment.start(),
+            };
+
+            match self.config.behaviour(segment.type_) {
+                MediaSegmentBehaviour::Ignore => continue,
+
+                // Auto skip and hide button
+                MediaSegmentBehaviour::AutoSkip
+                    if !already_skipped && segment.range().contains(&position) =>
+                {
+                    self.already_skipped[index] = true;
+                    self.video_player.borrow().seek_to(segment.end() as usize);
+                    break;
+                }
+
+                // Show auto skip countdown
+                MediaSegmentBehaviour::AutoSkip
+                    if !already_skipped && countdown_range.contains(&position) =>
+                {
+                    self.active_segment = Some(index);
+                    self.state = State::AutoSkipCountdown(
+                        segment.type_,
+                        (segment.start() - position).ceil() as usize,
+                    );
+                    break;
+                }
+
+                // User is asked to skip this segment type, OR the segment was already
+                // skipped automatically, in which case we show a manual skip button, in
+                // case the user rewound so they could watch the segment
+                _ if segment.range().contains(&position) => {
+                    self.active_segment = Some(index);
+                    self.state = State::Visible(segment.type_);
+                    break;
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    fn skip_segment(&mut self) {
+        if let Some(index) = self.active_segment.take() {
+            if let Some(segment) = self.segments.get(index) {
+                self.already_skipped[index] = true;
+                self.video_player.borrow().seek_to(segment.end() as usize);
+            }
+        }
+        self.state = State::Hidden;
+    }
+}
+
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        == Some(StatusCode::NOT_FOUND)
+}
+
+async fn get_intro_skipper_segments(api_client: &ApiClient, id: &Uuid) -> Vec<MediaSegment> {
+    match api_client.get_intro_timestamps(id).await {
+        Ok(intro_timestamps) => intro_timestamps.into_iter().map(Into::into).collect(),
+        Err(err) => {
+            warn!("Error getting intro timestamps for {id}: {err:#?}");
+            vec![]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::video_player::backends::fake::{Inputs, VideoPlayerBackendFake};
+
+    use super::*;
+
+    // Forwards positions from the fake backend to the segment skipper, the way it's connected
+    // when the component is created
+    struct Harness {
+        fake: Arc<RefCell<VideoPlayerBackendFake>>,
+        skipper: SegmentSkipper,
+        positions: Inputs<usize>,
+    }
+
+    impl Harness {
+        fn new(segments: Vec<MediaSegment>) -> Self {
+            let fake = Arc::<RefCell<VideoPlayerBackendFake>>::default();
+            let positions = Inputs::default();
+            fake.borrow_mut()
+                .connect_position_updated(Box::new(positions.sender()));
+
+            let skipper = SegmentSkipper {
+                video_player: fake.clone(),
+                already_skipped: vec![false; segments.len()],
+                segments,
+                active_segment: None,
+                state: State::Hidden,
+                config: VideoPlayerMediaSegmentsConfig::default(),
+                intro_skipper: false,
+            };
+
+            Self {
+                fake,
+                skipper,
+                positions,
+            }
+        }
+
+        fn emit_position(&mut self, position: usize) {
+            self.fake.borrow().emit_position(position);
+            self.forward_positions();
+        }
+
+        fn forward_positions(&mut self) {
+            for position in self.positions.take() {
+                self.skipper.set_position(position);
+            }
+        }
+    }
+
+    fn segment(type_: MediaSegmentType, start: i64, end: i64) -> MediaSegment {
+        MediaSegment {
+            type_,
+            start_ticks: start * 10_000_000,
+            end_ticks: end * 10_000_000,
+        }
+    }
+
+    #[test]
+    fn test_auto_skip() {
+        let mut harness = Harness::new(vec![segment(MediaSegmentType::Intro, 10, 40)]);
+
+        harness.emit_position(7);
+        assert!(matches!(
+            harness.skipper.state,
+            State::AutoSkipCountdown(MediaSegmentType::Intro, 3)
+        ));
+        assert!(harness.fake.borrow().seeks().is_empty());
+
+        harness.emit_position(10);
+        assert_eq!(harness.fake.borrow().seeks(), [40]);
+        assert!(matches!(harness.skipper.state, State::Hidden));
+
+        // Rewinding into the intro offers to skip it instead of skipping it again
+        harness.emit_position(20);
+        assert!(matches!(
+            harness.skipper.state,
+            State::Visible(MediaSegmentType::Intro)
+        ));
+        assert_eq!(harness.fake.borrow().seeks(), [40]);
+    }
+
+    #[test]
+    fn test_manual_skip() {
+        let mut harness = Harness::new(vec![segment(MediaSegmentType::Outro, 100, 120)]);
+
+        harness.emit_position(105);
+        assert!(matches!(
+            harness.skipper.state,
+            State::Visible(MediaSegmentType::Outro)
+        ));
+        assert!(harness.fake.borrow().seeks().is_empty());
+
+        harness.skipper.skip_segment();
+        harness.forward_positions();
+        assert_eq!(harness.fake.borrow().seeks(), [120]);
+        assert!(matches!(harness.skipper.state, State::Hidden));
+    }
+}
@@ -0,0 +1,55 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::utils::data_file::{read_data_file, write_data_file};
+
+const SERIES_TRACKS_FILE: &str = "series_tracks.json";
+
+/// Identifies a track independently of its index, which can differ between episodes.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct TrackDescription {
+    pub language: Option<String>,
+    pub title: Option<String>,
+    pub forced: bool,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
+pub enum SubtitleChoice {
+    Off,
+    Track(TrackDescription),
+}
+
+/// Audio and subtitle tracks the user picked while watching a series.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SeriesTracks {
+    pub audio: Option<TrackDescription>,
+    pub subtitle: Option<SubtitleChoice>,
+}
+
+type SeriesTracksFile = HashMap<Uuid, SeriesTracks>;
+
+pub fn get_series_tracks(series_id: &Uuid) -> Option<SeriesTracks> {
+    read_data_file::<SeriesTracksFile>(SERIES_TRACKS_FILE).remove(series_id)
+}
+
+pub fn remember_audio_track(series_id: &Uuid, audio: TrackDescription) {
+    update_series_tracks(series_id, |series_tracks| series_tracks.audio = Some(audio));
+}
+
+pub fn remember_subtitle_track(series_id: &Uuid, subtitle: SubtitleChoice) {
+    update_series_tracks(series_id, |series_tracks| {
+        series_tracks.subtitle = Some(subtitle)
+    });
+}
+
+fn update_series_tracks(series_id: &Uuid, update: impl FnOnce(&mut SeriesTracks)) {
+    let mut series_tracks_file: SeriesTracksFile = read_data_file(SERIES_TRACKS_FILE);
+    update(series_tracks_file.entry(*series_id).or_default());
+
+    if let Err(err) = write_data_file(SERIES_TRACKS_FILE, &series_tracks_file) {
+        warn!("Error saving series tracks: {err}");
+    }
+}
@@ -2,71 +2,242 @@ use std::{
     cell::RefCell,
     sync::{
         atomic::{AtomicBool, AtomicUsize, Ordering},
-        Arc, RwLock,
+        Arc, Mutex, RwLock,
     },
+    time::Duration,
 };
 
-use tracing::warn;
+use jellyfin_api::types::MediaSourceInfo;
 use uuid::Uuid;
 
 use crate::{
-    globals::CONFIG, jellyfin_api::api_client::ApiClient, library::LIBRARY_REFRESH_QUEUED,
+    globals::CONFIG,
+    jellyfin_api::{
+        api::playstate::{PlaybackSession, PlaybackStatus},
+        api_client::ApiClient,
+    },
+    library::LIBRARY_REFRESH_QUEUED,
     media_details::MEDIA_DETAILS_REFRESH_QUEUED,
+    utils::debounce::Debounce,
+};
+
+use super::{
+    backends::{PlayerState, VideoPlayerBackend},
+    playstate_outbox::{queue_report, PlaystateReport},
 };
 
-use super::backends::{PlayerState, VideoPlayerBackend};
+// Volume changes come in quick succession while the volume is being dragged, so only the volume
+// it ends up at is reported
+const VOLUME_REPORT_DELAY: Duration = Duration::from_secs(1);
+
+/// Something the user changed during playback, which is reported to the server right away.
+#[derive(Debug)]
+pub enum PlaybackChange {
+    /// Jellyfin's index for the audio stream being played
+    AudioTrack(Option<i64>),
+    /// Jellyfin's index for the subtitle stream being shown
+    SubtitleTrack(Option<i64>),
+    PlaybackRate(f64),
+}
+
+// Playback state shared with the player's callbacks
+struct SessionState {
+    item_id: Uuid,
+    // Whether the server has been told playback started, and it hasn't stopped since
+    started: AtomicBool,
+    position: AtomicUsize,
+    session: Mutex<PlaybackSession>,
+    status: Mutex<PlaybackStatus>,
+}
+
+impl SessionState {
+    fn new(item_id: Uuid, session: PlaybackSession, video_player: &dyn VideoPlayerBackend) -> Self {
+        Self {
+            item_id,
+            started: AtomicBool::default(),
+            position: AtomicUsize::default(),
+            session: Mutex::new(session),
+            status: Mutex::new(PlaybackStatus {
+                is_muted: video_player.muted(),
+                volume_level: volume_level(video_player.volume()),
+                playback_rate: video_player.playback_speed(),
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn report_progress(&self, queue_report: &QueueReport, event_name: &str) {
+        if self.started.load(Ordering::Acquire) {
+            queue_report(self.progress_report(event_name));
+        }
+    }
+
+    fn progress_report(&self, event_name: &str) -> PlaystateReport {
+        PlaystateReport::Progress {
+            event_name: event_name.into(),
+            item_id: self.item_id,
+            position: self.position.load(Ordering::Relaxed),
+            session: self.session.lock().unwrap().clone(),
+            status: self.status.lock().unwrap().clone(),
+        }
+    }
+}
+
+fn volume_level(volume: f64) -> u32 {
+    (volume * 100.0).round().clamp(0.0, 100.0) as u32
+}
+
+// Signal handlers to disconnect, and the player state changed handler
+type SignalHandlerIds = (Vec<Uuid>, Uuid);
 
 #[derive(Default)]
-pub struct SessionPlaybackReporter(Option<(Uuid, Uuid)>);
+pub struct SessionPlaybackReporter {
+    signal_handler_ids: Option<SignalHandlerIds>,
+    reporting: Option<(Arc<SessionState>, QueueReport)>,
+}
 
 impl SessionPlaybackReporter {
     pub fn start(
         &mut self,
         api_client: &Arc<ApiClient>,
         item_id: &Uuid,
+        session: PlaybackSession,
         video_player: &Arc<RefCell<dyn VideoPlayerBackend>>,
     ) {
         self.stop(video_player);
-        self.0 = Some(start_session_reporting(api_client, item_id, video_player));
+
+        // Playback is reported as started once the playback info is loaded, see
+        // set_playback_info()
+        let state = Arc::new(SessionState::new(
+            *item_id,
+            session,
+            &*video_player.borrow(),
+        ));
+        let queue_report = start_session_reporting(api_client);
+        self.signal_handler_ids = Some(connect_session_reporting(
+            &mut *video_player.borrow_mut(),
+            CONFIG.read().video_player.position_update_frequency,
+            VOLUME_REPORT_DELAY,
+            state.clone(),
+            queue_report.clone(),
+        ));
+        self.reporting = Some((state, queue_report));
     }
 
     pub fn stop(&mut self, video_player: &Arc<RefCell<dyn VideoPlayerBackend>>) {
-        if let Some((position_updated_signal_handler_id, player_state_signal_handler_id)) =
-            self.0.take()
+        if let Some((state, _)) = self.reporting.take() {
+            // Stops progress that was still waiting to be reported
+            state.started.store(false, Ordering::Release);
+        }
+
+        if let Some((signal_handler_ids, player_state_signal_handler_id)) =
+            self.signal_handler_ids.take()
         {
             let mut video_player = video_player.borrow_mut();
-            video_player.disconnect_signal_handler(&position_updated_signal_handler_id);
-            video_player.disconnect_signal_handler(&player_state_signal_handler_id);
+            for signal_handler_id in &signal_handler_ids {
+                video_player.disconnect_signal_handler(signal_handler_id);
+            }
+            video_player.disconnect_player_state_changed(player_state_signal_handler_id);
         }
     }
-}
 
-fn start_session_reporting(
-    api_client: &Arc<ApiClient>,
-    item_id: &Uuid,
-    video_player: &Arc<RefCell<dyn VideoPlayerBackend>>,
-) -> (Uuid, Uuid) {
-    let config = CONFIG.read();
+    /// Fills in the session details the server returned with the playback info, and reports
+    /// that playback started.
+    pub fn set_playback_info(
+        &self,
+        play_session_id: Option<String>,
+        media_source: Option<&MediaSourceInfo>,
+    ) {
+        let Some((state, queue_report)) = &self.reporting else {
+            return;
+        };
+
+        let session = {
+            let mut session = state.session.lock().unwrap();
+            session.play_session_id = play_session_id;
+            if let Some(media_source) = media_source {
+                session.media_source_id.clone_from(&media_source.id);
+                // Live streams can't be seeked
+                session.can_seek = media_source.is_infinite_stream != Some(true);
+            }
+            session.clone()
+        };
+
+        if !state.started.swap(true, Ordering::AcqRel) {
+            queue_report(PlaystateReport::Started {
+                item_id: state.item_id,
+                session,
+                status: state.status.lock().unwrap().clone(),
+            });
+        }
+    }
+
+    pub fn report_change(&self, change: PlaybackChange) {
+        let Some((state, queue_report)) = &self.reporting else {
+            return;
+        };
+
+        let event_name = {
+            let mut status = state.status.lock().unwrap();
+            match change {
+                PlaybackChange::AudioTrack(index) => {
+                    status.audio_stream_index = index;
+                    "audiotrackchange"
+                }
+                PlaybackChange::SubtitleTrack(index) => {
+                    status.subtitle_stream_index = index;
+                    "subtitletrackchange"
+                }
+                PlaybackChange::PlaybackRate(playback_rate) => {
+                    status.playback_rate = playback_rate;
+                    "playbackratechange"
+                }
+            }
+        };
+        state.report_progress(queue_report, event_name);
+    }
+
+    /// The report to send when playback stops, if playback is being reported.
+    pub fn stopped_report(&self, position: usize, failed: bool) -> Option<PlaystateReport> {
+        let (state, _) = self.reporting.as_ref()?;
+        Some(PlaystateReport::Stopped {
+            item_id: state.item_id,
+            position,
+            session: state.session.lock().unwrap().clone(),
+            failed,
+        })
+    }
+}
 
-    let position_update_frequency = config.video_player.position_update_frequency;
+// Sends a report for the session to the server
+type QueueReport = Arc<dyn Fn(PlaystateReport) + Send + Sync>;
 
+fn start_session_reporting(api_client: &Arc<ApiClient>) -> QueueReport {
     *LIBRARY_REFRESH_QUEUED.write() = true;
     *MEDIA_DETAILS_REFRESH_QUEUED.write() = true;
 
-    let position = Arc::new(AtomicUsize::default());
-    let is_paused = Arc::new(AtomicBool::new(false));
-
-    let mut video_player = video_player.borrow_mut();
+    Arc::new({
+        let api_client = api_client.clone();
+        move |report| {
+            queue_report(&api_client, report);
+        }
+    })
+}
 
+fn connect_session_reporting(
+    video_player: &mut dyn VideoPlayerBackend,
+    position_update_frequency: usize,
+    volume_report_delay: Duration,
+    state: Arc<SessionState>,
+    queue_report: QueueReport,
+) -> SignalHandlerIds {
     let position_updated_signal_handler_id = video_player.connect_position_updated(Box::new({
-        let api_client = api_client.clone();
-        let item_id = *item_id;
+        let queue_report = queue_report.clone();
         let last_update = RwLock::<usize>::new(0);
-        let position_store = position.clone();
-        let is_paused = is_paused.clone();
+        let state = state.clone();
 
         move |position| {
-            position_store.store(position, Ordering::Relaxed);
+            state.position.store(position, Ordering::Relaxed);
 
             // Avoid deadlocks
             let last_update_val = match last_update.try_read() {
@@ -85,65 +256,277 @@ fn start_session_reporting(
                     let mut last_update = last_update.write().expect("Error writing last_update");
                     *last_update = position;
 
-                    tokio::spawn({
-                        let api_client = api_client.clone();
-                        let is_paused = is_paused.clone();
-                        async move {
-                            if (api_client
-                                .report_playback_progress(
-                                    "timeupdate",
-                                    item_id,
-                                    position,
-                                    is_paused.load(Ordering::Relaxed),
-                                )
-                                .await)
-                                .is_err()
-                            {
-                                warn!("Error reporting playback progress.");
-                            }
-                        }
-                    });
+                    state.report_progress(&queue_report, "timeupdate");
                 }
                 _ => {}
             }
         }
     }));
 
-    let player_state_signal_handler_id = video_player.connect_player_state_changed(Box::new({
-        let api_client = api_client.clone();
-        let item_id = *item_id;
-        let position = position.clone();
-        let is_paused = is_paused.clone();
+    let volume_debounce = Arc::new(Mutex::new(Debounce::new(
+        volume_report_delay,
+        Box::new({
+            let queue_report = queue_report.clone();
+            let state = state.clone();
+            move || state.report_progress(&queue_report, "volumechange")
+        }),
+    )));
 
-        move |player_state| {
-            if let PlayerState::Playing { paused } = player_state {
-                is_paused.store(paused, Ordering::Relaxed);
-
-                tokio::spawn({
-                    let api_client = api_client.clone();
-                    let position = position.clone();
-                    let is_paused = is_paused.clone();
-                    async move {
-                        if (api_client
-                            .report_playback_progress(
-                                "timeupdate",
-                                item_id,
-                                position.load(Ordering::Relaxed),
-                                is_paused.load(Ordering::Relaxed),
-                            )
-                            .await)
-                            .is_err()
-                        {
-                            warn!("Error reporting playback progress.");
-                        }
-                    }
-                });
-            }
+    let volume_updated_signal_handler_id = video_player.connect_volume_updated(Box::new({
+        let volume_debounce = volume_debounce.clone();
+        let state = state.clone();
+        move |volume| {
+            state.status.lock().unwrap().volume_level = volume_level(volume);
+            volume_debounce.lock().unwrap().debounce();
+        }
+    }));
+
+    let mute_updated_signal_handler_id = video_player.connect_mute_updated(Box::new({
+        let state = state.clone();
+        move |muted| {
+            state.status.lock().unwrap().is_muted = muted;
+            volume_debounce.lock().unwrap().debounce();
         }
     }));
 
+    let player_state_signal_handler_id =
+        video_player.connect_player_state_changed(Box::new(move |player_state| {
+            if let PlayerState::Playing { paused } = player_state {
+                state.status.lock().unwrap().is_paused = paused;
+                state.report_progress(&queue_report, if paused { "pause" } else { "unpause" });
+            }
+        }));
+
     (
-        position_updated_signal_handler_id,
+        vec![
+            position_updated_signal_handler_id,
+            volume_updated_signal_handler_id,
+            mute_updated_signal_handler_id,
+        ],
         player_state_signal_handler_id,
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::video_player::backends::fake::VideoPlayerBackendFake;
+
+    use super::*;
+
+    type Reports = Arc<Mutex<Vec<PlaystateReport>>>;
+
+    // Connects session reporting to a fake player, returning the reports that get sent. Nothing is
+    // reported until the playback info is loaded.
+    fn connect_loading(
+        fake: &mut VideoPlayerBackendFake,
+        position_update_frequency: usize,
+    ) -> (Reports, SessionPlaybackReporter) {
+        let state = Arc::new(SessionState::new(
+            Uuid::nil(),
+            PlaybackSession::default(),
+            &*fake,
+        ));
+        let reports: Reports = Arc::default();
+        let queue_report: QueueReport = Arc::new({
+            let reports = reports.clone();
+            move |report| reports.lock().unwrap().push(report)
+        });
+        let ids = connect_session_reporting(
+            fake,
+            position_update_frequency,
+            Duration::ZERO,
+            state.clone(),
+            queue_report.clone(),
+        );
+        let reporter = SessionPlaybackReporter {
+            signal_handler_ids: Some(ids),
+            reporting: Some((state, queue_report)),
+        };
+        (reports, reporter)
+    }
+
+    fn connect(
+        fake: &mut VideoPlayerBackendFake,
+        position_update_frequency: usize,
+    ) -> (Reports, SessionPlaybackReporter) {
+        let (reports, reporter) = connect_loading(fake, position_update_frequency);
+        reporter.set_playback_info(None, None);
+        (reports, reporter)
+    }
+
+    // Reported positions and whether playback was paused
+    fn positions(reports: &Reports) -> Vec<(usize, bool)> {
+        reports
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|report| match report {
+                PlaystateReport::Progress {
+                    position, status, ..
+                } => Some((*position, status.is_paused)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn event_names(reports: &Reports) -> Vec<String> {
+        reports
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|report| match report {
+                PlaystateReport::Progress { event_name, .. } => Some(event_name.clone()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_started_after_playback_info() {
+        let mut fake = VideoPlayerBackendFake::default();
+        let (reports, reporter) = connect_loading(&mut fake, 10);
+
+        fake.emit_player_state(PlayerState::Playing { paused: false });
+        fake.emit_position(10);
+        assert!(reports.lock().unwrap().is_empty());
+
+        reporter.set_playback_info(Some("session".into()), None);
+        fake.emit_position(20);
+        let reports = reports.lock().unwrap().clone();
+        assert!(
+            matches!(
+                &reports[..],
+                [
+                    PlaystateReport::Started { session, .. },
+                    PlaystateReport::Progress { position: 20, .. },
+                ] if session.play_session_id.as_deref() == Some("session")
+            ),
+            "{reports:?}"
+        );
+    }
+
+    #[test]
+    fn test_report_frequency() {
+        let mut fake = VideoPlayerBackendFake::default();
+        let (reports, _) = connect(&mut fake, 10);
+
+        for position in 0..=25 {
+            fake.emit_position(position);
+        }
+        assert_eq!(positions(&reports), [(10, false), (20, false)]);
+    }
+
+    #[test]
+    fn test_report_rewind() {
+        let mut fake = VideoPlayerBackendFake::default();
+        let (reports, _) = connect(&mut fake, 10);
+
+        fake.emit_position(30);
+        fake.emit_position(31);
+        // Rewinding is reported right away
+        fake.emit_position(5);
+        fake.emit_position(6);
+        fake.emit_position(15);
+        assert_eq!(positions(&reports), [(30, false), (5, false), (15, false)]);
+    }
+
+    #[test]
+    fn test_report_pause() {
+        let mut fake = VideoPlayerBackendFake::default();
+        let (reports, _) = connect(&mut fake, 10);
+
+        fake.emit_player_state(PlayerState::Playing { paused: false });
+        fake.emit_position(12);
+        fake.pause();
+        fake.emit_position(22);
+        fake.play();
+        // Buffering and loading aren't reported
+        fake.emit_player_state(PlayerState::Buffering);
+
+        assert_eq!(
+            positions(&reports),
+            [(0, false), (12, false), (12, true), (22, true), (22, false)]
+        );
+    }
+
+    #[test]
+    fn test_stop_reporting() {
+        let fake = Arc::<RefCell<VideoPlayerBackendFake>>::default();
+        let (reports, mut reporter) = connect(&mut fake.borrow_mut(), 10);
+        fake.borrow().emit_position(10);
+
+        let video_player: Arc<RefCell<dyn VideoPlayerBackend>> = fake.clone();
+        reporter.stop(&video_player);
+
+        fake.borrow()
+            .emit_player_state(PlayerState::Playing { paused: true });
+        fake.borrow().emit_position(20);
+        assert_eq!(positions(&reports), [(10, false)]);
+    }
+
+    #[tokio::test]
+    async fn test_report_events() {
+        let mut fake = VideoPlayerBackendFake::default();
+        let (reports, reporter) = connect(&mut fake, 10);
+
+        fake.emit_player_state(PlayerState::Playing { paused: false });
+        fake.pause();
+        fake.set_volume(0.5);
+        fake.set_muted(true);
+        // Volume changes are reported together
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        reporter.report_change(PlaybackChange::AudioTrack(Some(2)));
+        reporter.report_change(PlaybackChange::SubtitleTrack(None));
+        reporter.report_change(PlaybackChange::PlaybackRate(1.5));
+
+        assert_eq!(
+            event_names(&reports),
+            [
+                "unpause",
+                "pause",
+                "volumechange",
+                "audiotrackchange",
+                "subtitletrackchange",
+                "playbackratechange",
+            ]
+        );
+        let Some(PlaystateReport::Progress { status, .. }) = reports.lock().unwrap().pop() else {
+            unreachable!();
+        };
+        assert_eq!(
+            status,
+            PlaybackStatus {
+                is_paused: true,
+                is_muted: true,
+                volume_level: 50,
+                playback_rate: 1.5,
+                audio_stream_index: Some(2),
+                subtitle_stream_index: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_stopped_report() {
+        let mut fake = VideoPlayerBackendFake::default();
+        let (_, reporter) = connect(&mut fake, 10);
+
+        reporter.set_playback_info(Some("session".into()), None);
+        let Some(PlaystateReport::Stopped {
+            position,
+            session,
+            failed,
+            ..
+        }) = reporter.stopped_report(42, true)
+        else {
+            unreachable!();
+        };
+        assert_eq!(position, 42);
+        assert_eq!(session.play_session_id.as_deref(), Some("session"));
+        assert!(failed);
+
+        assert!(SessionPlaybackReporter::default()
+            .stopped_report(42, false)
+            .is_none());
+    }
+}
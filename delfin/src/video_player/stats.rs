@@ -0,0 +1,219 @@
+use std::{cell::RefCell, sync::Arc, time::Duration};
+
+use gtk::{glib, prelude::*};
+use jellyfin_api::types::MediaSourceInfo;
+use relm4::prelude::*;
+
+use crate::{jellyfin_api::api::playstate::PlayMethod, tr};
+
+use super::backends::{PlaybackStats, VideoPlayerBackend};
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+fn play_method_label(play_method: PlayMethod) -> String {
+    let method = match play_method {
+        PlayMethod::DirectPlay => "direct-play",
+        PlayMethod::DirectStream => "direct-stream",
+        PlayMethod::Transcode => "transcode",
+    };
+    tr!("vp-stats-play-method", { "method" => method }).to_string()
+}
+
+/// Shows technical details about playback on top of the video, for troubleshooting stutter.
+pub(crate) struct StatsOverlay {
+    video_player: Arc<RefCell<dyn VideoPlayerBackend>>,
+    visible: bool,
+    stats: PlaybackStats,
+    // How the video is being streamed, rather than how the server says it could be
+    play_method: Option<PlayMethod>,
+    // Reported by the server, rather than the player
+    source_bitrate: Option<u64>,
+    refresh_source: Option<glib::SourceId>,
+}
+
+#[derive(Debug)]
+pub(crate) enum StatsOverlayInput {
+    Reset,
+    Toggle,
+    Refresh,
+    SetPlayMethod(PlayMethod),
+    SetMediaSource(Box<MediaSourceInfo>),
+}
+
+#[relm4::component(pub(crate))]
+impl SimpleComponent for StatsOverlay {
+    type Init = Arc<RefCell<dyn VideoPlayerBackend>>;
+    type Input = StatsOverlayInput;
+    type Output = ();
+
+    view! {
+        gtk::Box {
+            #[watch]
+            set_visible: model.visible,
+            set_halign: gtk::Align::Start,
+            set_valign: gtk::Align::Start,
+            set_margin_top: 60,
+            set_margin_start: 12,
+            set_spacing: 12,
+            set_can_target: false,
+            add_css_class: "osd",
+            add_css_class: "stats-overlay",
+
+            gtk::Label {
+                set_label: &stat_names().join("\n"),
+                set_xalign: 0.0,
+                add_css_class: "dim-label",
+            },
+
+            gtk::Label {
+                #[watch]
+                set_label: &model.stat_values().join("\n"),
+                set_xalign: 0.0,
+                add_css_class: "numeric",
+            },
+        }
+    }
+
+    fn init(
+        video_player: Self::Init,
+        root: Self::Root,
+        sender: ComponentSender<Self>,
+    ) -> ComponentParts<Self> {
+        let model = StatsOverlay {
+            video_player,
+            visible: false,
+            stats: PlaybackStats::default(),
+            play_method: None,
+            source_bitrate: None,
+            refresh_source: None,
+        };
+
+        let widgets = view_output!();
+
+        ComponentParts { model, widgets }
+    }
+
+    fn update(&mut self, message: Self::Input, sender: ComponentSender<Self>) {
+        match message {
+            StatsOverlayInput::Reset => {
+                self.stats = PlaybackStats::default();
+                self.play_method = None;
+                self.source_bitrate = None;
+            }
+            StatsOverlayInput::Toggle => {
+                self.visible = !self.visible;
+
+                // Only poll the player while the stats are shown
+                if let Some(refresh_source) = self.refresh_source.take() {
+                    refresh_source.remove();
+                }
+                if self.visible {
+                    self.stats = self.video_player.borrow().playback_stats();
+                    self.refresh_source = Some(glib::timeout_add_local(REFRESH_INTERVAL, {
+                        let sender = sender.clone();
+                        move || {
+                            sender.input(StatsOverlayInput::Refresh);
+                            glib::ControlFlow::Continue
+                        }
+                    }));
+                }
+            }
+            StatsOverlayInput::Refresh => {
+                self.stats = self.video_player.borrow().playback_stats();
+            }
+            StatsOverlayInput::SetPlayMethod(play_method) => {
+                self.play_method = Some(play_method);
+            }
+            StatsOverlayInput::SetMediaSource(media_source) => {
+                self.source_bitrate = media_source
+                    .bitrate
+                    .filter(|bitrate| *bitrate > 0)
+                    .map(|bitrate| bitrate as u64);
+            }
+        }
+    }
+}
+
+impl StatsOverlay {
+    fn stat_values(&self) -> Vec<String> {
+        let stats = &self.stats;
+        let unknown = || tr!("vp-stats.unknown").to_string();
+
+        let play_method = match (self.play_method, self.source_bitrate) {
+            (Some(play_method), Some(bitrate)) => {
+                format!(
+                    "{} ({})",
+                    play_method_label(play_method),
+                    format_bitrate(bitrate)
+                )
+            }
+            (Some(play_method), None) => play_method_label(play_method),
+            (None, _) => unknown(),
+        };
+
+        vec![
+            play_method,
+            stats.container.clone().unwrap_or_else(unknown),
+            stats.video_codec.clone().unwrap_or_else(unknown),
+            stats
+                .resolution
+                .map_or_else(unknown, |(width, height)| format!("{width}×{height}")),
+            stats
+                .fps
+                .map_or_else(unknown, |fps| format!("{fps:.3} fps")),
+            stats
+                .hardware_decoding
+                .clone()
+                .unwrap_or_else(|| tr!("vp-stats.hardware-decoding-off").to_string()),
+            stats.video_bitrate.map_or_else(unknown, format_bitrate),
+            stats.audio_codec.clone().unwrap_or_else(unknown),
+            stats.audio_bitrate.map_or_else(unknown, format_bitrate),
+            stats
+                .cache_duration
+                .map_or_else(unknown, |seconds| format!("{seconds:.1} s")),
+            stats
+                .dropped_frames
+                .map_or_else(unknown, |dropped_frames| dropped_frames.to_string()),
+        ]
+    }
+}
+
+// Labels for each of the values from StatsOverlay::stat_values, in the same order
+fn stat_names() -> Vec<String> {
+    [
+        "vp-stats.playback",
+        "vp-stats.container",
+        "vp-stats.video-codec",
+        "vp-stats.resolution",
+        "vp-stats.frame-rate",
+        "vp-stats.hardware-decoding",
+        "vp-stats.video-bitrate",
+        "vp-stats.audio-codec",
+        "vp-stats.audio-bitrate",
+        "vp-stats.buffered",
+        "vp-stats.dropped-frames",
+    ]
+    .into_iter()
+    .map(|key| tr!(key).to_string())
+    .collect()
+}
+
+fn format_bitrate(bits_per_second: u64) -> String {
+    match bits_per_second {
+        1_000_000.. => format!("{:.1} Mbps", bits_per_second as f64 / 1_000_000.0),
+        1_000.. => format!("{} kbps", bits_per_second / 1_000),
+        _ => format!("{bits_per_second} bps"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bitrate() {
+        assert_eq!(format_bitrate(8_123_456), "8.1 Mbps");
+        assert_eq!(format_bitrate(640_000), "640 kbps");
+        assert_eq!(format_bitrate(512), "512 bps");
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::utils::data_file::{read_data_file, write_data_file};
+
+const SYNC_OFFSETS_FILE: &str = "sync_offsets.json";
+
+/// Subtitle and audio delays the user set for an item, in milliseconds.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq)]
+#[serde(default)]
+pub struct SyncOffsets {
+    pub subtitle: i64,
+    pub audio: i64,
+}
+
+type SyncOffsetsFile = HashMap<Uuid, SyncOffsets>;
+
+pub fn get_sync_offsets(item_id: &Uuid) -> SyncOffsets {
+    read_data_file::<SyncOffsetsFile>(SYNC_OFFSETS_FILE)
+        .remove(item_id)
+        .unwrap_or_default()
+}
+
+pub fn remember_subtitle_delay(item_id: &Uuid, delay: i64) {
+    update_sync_offsets(item_id, |sync_offsets| sync_offsets.subtitle = delay);
+}
+
+pub fn remember_audio_delay(item_id: &Uuid, delay: i64) {
+    update_sync_offsets(item_id, |sync_offsets| sync_offsets.audio = delay);
+}
+
+fn update_sync_offsets(item_id: &Uuid, update: impl FnOnce(&mut SyncOffsets)) {
+    let mut sync_offsets_file: SyncOffsetsFile = read_data_file(SYNC_OFFSETS_FILE);
+
+    let sync_offsets = sync_offsets_file.entry(*item_id).or_default();
+    update(sync_offsets);
+    // Don't keep items around that are back in sync
+    if *sync_offsets == SyncOffsets::default() {
+        sync_offsets_file.remove(item_id);
+    }
+
+    if let Err(err) = write_data_file(SYNC_OFFSETS_FILE, &sync_offsets_file) {
+        warn!("Error saving sync offsets: {err}");
+    }
+}
@@ -0,0 +1,472 @@
+use jellyfin_api::types::{MediaStream, MediaStreamType};
+use uuid::Uuid;
+
+use crate::{
+    config::video_player_config::VideoPlayerSubtitlesConfig,
+    jellyfin_api::api::user::{SubtitleMode, UserConfiguration},
+    video_player::{
+        backends::{AudioTrack, SubtitleTrack},
+        series_tracks::{
+            remember_audio_track, remember_subtitle_track, SeriesTracks, SubtitleChoice,
+            TrackDescription,
+        },
+    },
+};
+
+/// Language preferences used to pick tracks when playback starts.
+///
+/// These come from the user's Jellyfin configuration, with any local overrides applied on top.
+#[derive(Clone, Debug)]
+pub struct TrackPreferences {
+    audio_language: Option<String>,
+    play_default_audio_track: bool,
+    subtitle_language: Option<String>,
+    subtitle_mode: SubtitleMode,
+}
+
+impl TrackPreferences {
+    pub(crate) fn new(
+        user_configuration: UserConfiguration,
+        overrides: &VideoPlayerSubtitlesConfig,
+    ) -> Self {
+        Self {
+            audio_language: overrides
+                .audio_language
+                .clone()
+                .or(user_configuration.audio_language_preference)
+                .filter(|language| !language.is_empty()),
+            play_default_audio_track: user_configuration.play_default_audio_track,
+            subtitle_language: overrides
+                .subtitle_language
+                .clone()
+                .or(user_configuration.subtitle_language_preference)
+                .filter(|language| !language.is_empty()),
+            subtitle_mode: overrides
+                .subtitle_mode
+                .unwrap_or(user_configuration.subtitle_mode),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+struct StreamInfo {
+    language: Option<String>,
+    title: Option<String>,
+    forced: bool,
+    default: bool,
+    // Jellyfin's index for the stream, which is used when reporting playback
+    index: Option<i64>,
+}
+
+impl StreamInfo {
+    fn has_language(&self, language: &str) -> bool {
+        self.language
+            .as_deref()
+            .is_some_and(|l| l.eq_ignore_ascii_case(language))
+    }
+
+    fn description(&self) -> TrackDescription {
+        TrackDescription {
+            language: self.language.clone(),
+            title: self.title.clone(),
+            forced: self.forced,
+        }
+    }
+}
+
+impl From<&MediaStream> for StreamInfo {
+    fn from(stream: &MediaStream) -> Self {
+        Self {
+            language: stream.language.clone(),
+            title: stream.title.clone(),
+            forced: stream.is_forced.unwrap_or(false),
+            default: stream.is_default.unwrap_or(false),
+            index: stream.index.map(i64::from),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SubtitleTrackSelection {
+    Track(usize),
+    Off,
+    /// The chosen subtitle track hasn't been added to the player yet.
+    NotLoaded,
+}
+
+/// Picks the initial audio and subtitle tracks for an item, using the language metadata of its
+/// Jellyfin media streams.
+#[derive(Clone, Debug)]
+pub struct TrackSelector {
+    preferences: TrackPreferences,
+    series_id: Option<Uuid>,
+    // Tracks previously picked for this series, which take priority over language preferences
+    series_tracks: SeriesTracks,
+    audio_streams: Vec<StreamInfo>,
+    // Embedded subtitles followed by external subtitles, matching the order the player lists them
+    subtitle_streams: Vec<StreamInfo>,
+}
+
+impl TrackSelector {
+    pub(crate) fn new(
+        preferences: TrackPreferences,
+        series_id: Option<Uuid>,
+        series_tracks: Option<SeriesTracks>,
+        media_streams: &[MediaStream],
+    ) -> Self {
+        let mut audio_streams = vec![];
+        let mut embedded_subtitle_streams = vec![];
+        let mut external_subtitle_streams = vec![];
+
+        for stream in media_streams {
+            match stream.type_ {
+                Some(MediaStreamType::Audio) => audio_streams.push(stream.into()),
+                Some(MediaStreamType::Subtitle) => match stream.is_external {
+                    // Only external text subtitles are added to the player
+                    Some(true) => {
+                        if stream.is_text_subtitle_stream == Some(true)
+                            && stream.delivery_url.is_some()
+                        {
+                            external_subtitle_streams.push(stream.into());
+                        }
+                    }
+                    _ => embedded_subtitle_streams.push(stream.into()),
+                },
+                _ => {}
+            }
+        }
+
+        embedded_subtitle_streams.append(&mut external_subtitle_streams);
+
+        Self {
+            preferences,
+            series_id,
+            series_tracks: series_tracks.unwrap_or_default(),
+            audio_streams,
+            subtitle_streams: embedded_subtitle_streams,
+        }
+    }
+
+    /// The user's preferred subtitle language, if they have one.
+    pub(crate) fn subtitle_language(&self) -> Option<&str> {
+        self.preferences.subtitle_language.as_deref()
+    }
+
+    /// ID of the audio track to use, if any.
+    pub(crate) fn audio_track(&self, tracks: &[AudioTrack]) -> Option<usize> {
+        let tracks = sorted_tracks(tracks.iter().map(|t| (t.id, t.language.clone())));
+        let streams = merge_stream_info(&self.audio_streams, &tracks);
+
+        let index = self
+            .series_tracks
+            .audio
+            .as_ref()
+            .and_then(|audio| find_track(&streams, audio))
+            .or_else(|| select_audio_stream(&self.preferences, &streams))?;
+        tracks.get(index).map(|(id, _)| *id)
+    }
+
+    pub(crate) fn subtitle_track(&self, tracks: &[SubtitleTrack]) -> SubtitleTrackSelection {
+        let tracks = sorted_tracks(tracks.iter().map(|t| (t.id, t.language.clone())));
+        let streams = merge_stream_info(&self.subtitle_streams, &tracks);
+
+        let index = match &self.series_tracks.subtitle {
+            Some(SubtitleChoice::Off) => return SubtitleTrackSelection::Off,
+            Some(SubtitleChoice::Track(subtitle)) => find_track(&streams, subtitle),
+            None => None,
+        };
+        let index = index.or_else(|| {
+            let audio_language = select_audio_stream(&self.preferences, &self.audio_streams)
+                .and_then(|index| self.audio_streams[index].language.as_deref());
+            select_subtitle_stream(&self.preferences, &streams, audio_language)
+        });
+
+        match index {
+            Some(index) => match tracks.get(index) {
+                Some((id, _)) => SubtitleTrackSelection::Track(*id),
+                None => SubtitleTrackSelection::NotLoaded,
+            },
+            None => SubtitleTrackSelection::Off,
+        }
+    }
+
+    /// Jellyfin's index for the stream an audio track plays.
+    pub(crate) fn audio_stream_index(&self, tracks: &[AudioTrack], id: usize) -> Option<i64> {
+        let tracks = sorted_tracks(tracks.iter().map(|t| (t.id, t.language.clone())));
+        stream_index(&self.audio_streams, &tracks, id)
+    }
+
+    /// Jellyfin's index for the stream a subtitle track shows.
+    pub(crate) fn subtitle_stream_index(&self, tracks: &[SubtitleTrack], id: usize) -> Option<i64> {
+        let tracks = sorted_tracks(tracks.iter().map(|t| (t.id, t.language.clone())));
+        stream_index(&self.subtitle_streams, &tracks, id)
+    }
+
+    /// Remember the audio track the user picked, so it can be used for later episodes.
+    pub(crate) fn remember_audio_track(&self, tracks: &[AudioTrack], id: usize) {
+        let Some(series_id) = self.series_id else {
+            return;
+        };
+
+        let tracks = sorted_tracks(tracks.iter().map(|t| (t.id, t.language.clone())));
+        let streams = merge_stream_info(&self.audio_streams, &tracks);
+        if let Some(index) = tracks.iter().position(|(track_id, _)| *track_id == id) {
+            remember_audio_track(&series_id, streams[index].description());
+        }
+    }
+
+    /// Remember the subtitle track the user picked, so it can be used for later episodes.
+    pub(crate) fn remember_subtitle_track(&self, tracks: &[SubtitleTrack], id: Option<usize>) {
+        let Some(series_id) = self.series_id else {
+            return;
+        };
+
+        let Some(id) = id else {
+            remember_subtitle_track(&series_id, SubtitleChoice::Off);
+            return;
+        };
+
+        let tracks = sorted_tracks(tracks.iter().map(|t| (t.id, t.language.clone())));
+        let streams = merge_stream_info(&self.subtitle_streams, &tracks);
+        if let Some(index) = tracks.iter().position(|(track_id, _)| *track_id == id) {
+            remember_subtitle_track(
+                &series_id,
+                SubtitleChoice::Track(streams[index].description()),
+            );
+        }
+    }
+}
+
+// Track IDs and languages, in the order the player lists them
+fn sorted_tracks(
+    tracks: impl Iterator<Item = (usize, Option<String>)>,
+) -> Vec<(usize, Option<String>)> {
+    let mut tracks: Vec<_> = tracks.collect();
+    tracks.sort_by_key(|(id, _)| *id);
+    tracks
+}
+
+fn stream_index(
+    streams: &[StreamInfo],
+    tracks: &[(usize, Option<String>)],
+    id: usize,
+) -> Option<i64> {
+    let position = tracks.iter().position(|(track_id, _)| *track_id == id)?;
+    streams.get(position)?.index
+}
+
+/// Finds the stream matching a previously picked track, ignoring the title if there's no exact
+/// match.
+fn find_track(streams: &[StreamInfo], track: &TrackDescription) -> Option<usize> {
+    streams
+        .iter()
+        .position(|stream| stream.description() == *track)
+        .or_else(|| {
+            streams.iter().position(|stream| {
+                stream.language == track.language && stream.forced == track.forced
+            })
+        })
+}
+
+/// Combines stream info from Jellyfin with the tracks reported by the player, falling back to the
+/// player's language metadata for tracks Jellyfin doesn't know about.
+fn merge_stream_info(
+    streams: &[StreamInfo],
+    tracks: &[(usize, Option<String>)],
+) -> Vec<StreamInfo> {
+    let mut streams = streams.to_vec();
+    for (_, language) in tracks.iter().skip(streams.len()) {
+        streams.push(StreamInfo {
+            language: language.clone(),
+            ..Default::default()
+        });
+    }
+    streams
+}
+
+fn select_audio_stream(preferences: &TrackPreferences, streams: &[StreamInfo]) -> Option<usize> {
+    if preferences.play_default_audio_track {
+        if let Some(index) = streams.iter().position(|stream| stream.default) {
+            return Some(index);
+        }
+    }
+
+    preferences
+        .audio_language
+        .as_deref()
+        .and_then(|language| streams.iter().position(|s| s.has_language(language)))
+        .or_else(|| (!streams.is_empty()).then_some(0))
+}
+
+fn select_subtitle_stream(
+    preferences: &TrackPreferences,
+    streams: &[StreamInfo],
+    audio_language: Option<&str>,
+) -> Option<usize> {
+    let language = preferences.subtitle_language.as_deref();
+    let in_language = |stream: &StreamInfo| language.is_some_and(|l| stream.has_language(l));
+
+    // Find a matching stream, preferring ones in the preferred language
+    let find = |filter: &dyn Fn(&StreamInfo) -> bool| {
+        streams
+            .iter()
+            .position(|stream| filter(stream) && in_language(stream))
+            .or_else(|| streams.iter().position(filter))
+    };
+
+    match preferences.subtitle_mode {
+        SubtitleMode::Default => find(&|stream| stream.default || stream.forced),
+        SubtitleMode::Always => streams
+            .iter()
+            .position(in_language)
+            .or_else(|| find(&|stream| stream.default || stream.forced))
+            .or_else(|| (!streams.is_empty()).then_some(0)),
+        SubtitleMode::OnlyForced => find(&|stream| stream.forced),
+        SubtitleMode::Smart => {
+            let foreign_audio = match (language, audio_language) {
+                (Some(language), Some(audio_language)) => {
+                    !language.eq_ignore_ascii_case(audio_language)
+                }
+                _ => false,
+            };
+
+            if foreign_audio {
+                streams
+                    .iter()
+                    .position(in_language)
+                    .or_else(|| find(&|stream| stream.forced))
+            } else {
+                find(&|stream| stream.forced)
+            }
+        }
+        SubtitleMode::None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(language: &str, forced: bool, default: bool) -> StreamInfo {
+        StreamInfo {
+            language: Some(language.into()),
+            title: None,
+            forced,
+            default,
+            index: None,
+        }
+    }
+
+    fn preferences(
+        audio_language: Option<&str>,
+        subtitle_language: Option<&str>,
+        subtitle_mode: SubtitleMode,
+    ) -> TrackPreferences {
+        TrackPreferences {
+            audio_language: audio_language.map(Into::into),
+            play_default_audio_track: false,
+            subtitle_language: subtitle_language.map(Into::into),
+            subtitle_mode,
+        }
+    }
+
+    #[test]
+    fn test_select_audio_stream() {
+        let streams = [stream("jpn", false, true), stream("eng", false, false)];
+
+        let prefs = preferences(Some("eng"), None, SubtitleMode::Default);
+        assert_eq!(select_audio_stream(&prefs, &streams), Some(1));
+
+        let prefs = TrackPreferences {
+            play_default_audio_track: true,
+            ..prefs
+        };
+        assert_eq!(select_audio_stream(&prefs, &streams), Some(0));
+
+        let prefs = preferences(Some("fre"), None, SubtitleMode::Default);
+        assert_eq!(select_audio_stream(&prefs, &streams), Some(0));
+        assert_eq!(select_audio_stream(&prefs, &[]), None);
+    }
+
+    #[test]
+    fn test_select_subtitle_stream() {
+        let streams = [
+            stream("eng", true, false),
+            stream("eng", false, false),
+            stream("fre", false, true),
+        ];
+
+        let prefs = preferences(None, Some("eng"), SubtitleMode::Default);
+        assert_eq!(select_subtitle_stream(&prefs, &streams, None), Some(0));
+
+        let prefs = preferences(None, Some("fre"), SubtitleMode::Always);
+        assert_eq!(select_subtitle_stream(&prefs, &streams, None), Some(2));
+
+        let prefs = preferences(None, Some("ger"), SubtitleMode::OnlyForced);
+        assert_eq!(select_subtitle_stream(&prefs, &streams, None), Some(0));
+
+        let prefs = preferences(None, Some("eng"), SubtitleMode::None);
+        assert_eq!(select_subtitle_stream(&prefs, &streams, None), None);
+    }
+
+    #[test]
+    fn test_find_track() {
+        let mut streams = vec![stream("jpn", false, false), stream("jpn", false, false)];
+        streams[1].title = Some("Commentary".into());
+
+        let track = TrackDescription {
+            language: Some("jpn".into()),
+            title: Some("Commentary".into()),
+            forced: false,
+        };
+        assert_eq!(find_track(&streams, &track), Some(1));
+
+        // Falls back to matching the language if the title changed
+        let track = TrackDescription {
+            title: Some("Main".into()),
+            ..track
+        };
+        assert_eq!(find_track(&streams, &track), Some(0));
+
+        let track = TrackDescription {
+            forced: true,
+            ..track
+        };
+        assert_eq!(find_track(&streams, &track), None);
+    }
+
+    #[test]
+    fn test_stream_index() {
+        let mut streams = vec![stream("jpn", false, true), stream("eng", false, false)];
+        streams[0].index = Some(1);
+        streams[1].index = Some(2);
+        let tracks = sorted_tracks([(2, None), (1, None), (3, None)].into_iter());
+
+        assert_eq!(stream_index(&streams, &tracks, 1), Some(1));
+        assert_eq!(stream_index(&streams, &tracks, 2), Some(2));
+        // Tracks Jellyfin doesn't know about, like subtitles added from a file
+        assert_eq!(stream_index(&streams, &tracks, 3), None);
+        assert_eq!(stream_index(&streams, &tracks, 4), None);
+    }
+
+    #[test]
+    fn test_select_subtitle_stream_smart() {
+        let streams = [stream("eng", false, false), stream("eng", true, false)];
+        let prefs = preferences(None, Some("eng"), SubtitleMode::Smart);
+
+        // Foreign audio uses full subtitles
+        assert_eq!(
+            select_subtitle_stream(&prefs, &streams, Some("jpn")),
+            Some(0)
+        );
+        // Audio in the preferred language only uses forced subtitles
+        assert_eq!(
+            select_subtitle_stream(&prefs, &streams, Some("eng")),
+            Some(1)
+        );
+        assert_eq!(
+            select_subtitle_stream(&prefs, &streams[..1], Some("ENG")),
+            None
+        );
+    }
+}
@@ -1,8 +1,18 @@
-use std::{io::Cursor, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
 
-use image::{EncodableLayout, ImageFormat};
+use anyhow::{Context, Result};
+use bytes::Buf;
+use gtk::{gdk, gdk_pixbuf, glib, prelude::*};
+use image::{ImageFormat, RgbImage};
 use jellyfin_api::types::BaseItemDto;
-use relm4::ComponentSender;
+use relm4::{gtk, ComponentSender};
 use tracing::warn;
 use uuid::Uuid;
 
@@ -10,107 +20,375 @@ use crate::{globals::CONFIG, jellyfin_api::api_client::ApiClient, utils::bif::Th
 
 use super::{VideoPlayer, VideoPlayerCommandOutput};
 
-pub(crate) fn fetch_trickplay(
-    api_client: &Arc<ApiClient>,
-    sender: &ComponentSender<VideoPlayer>,
-    item: &BaseItemDto,
-) {
-    let Some(item_id) = item.id else {
-        return;
-    };
+// How long to wait before requesting a tile that failed to load again
+const TILE_RETRY_INTERVAL: Duration = Duration::from_secs(10);
 
-    sender.oneshot_command({
-        let api_client = api_client.clone();
-        let item = item.clone();
-        async move {
-            if let Some(thumbnails) = load_native_trickplay(&api_client, &item_id, item).await {
-                return VideoPlayerCommandOutput::LoadedTrickplay(Some(thumbnails));
-            }
+// Once the tile cache grows past this size, the least recently used items are removed from it
+const TILE_CACHE_MAX_SIZE: u64 = 500 * 1024 * 1024;
 
-            // If native trickplay wasn't found, fall back on Jellyscrub
-            if let Some(thumbnails) = load_jellyscrub_trickplay(&api_client, &item_id).await {
-                return VideoPlayerCommandOutput::LoadedTrickplay(Some(thumbnails));
-            }
+// Cached tiles start with their width and height, followed by the decoded RGB pixels
+const TILE_CACHE_HEADER_SIZE: usize = 8;
 
-            VideoPlayerCommandOutput::LoadedTrickplay(None)
+#[derive(Clone, Debug)]
+pub(crate) enum Trickplay {
+    Native(Arc<NativeTrickplay>),
+    Jellyscrub(Arc<Vec<Thumbnail>>),
+}
+
+impl Trickplay {
+    /// Returns the thumbnail for the given timestamp, if it's available.
+    pub(crate) fn get_thumbnail(&self, timestamp: usize) -> Option<gdk::Texture> {
+        match self {
+            Self::Native(trickplay) => trickplay.get_thumbnail(timestamp),
+            Self::Jellyscrub(thumbnails) => get_jellyscrub_thumbnail(thumbnails, timestamp),
         }
-    });
+    }
 }
 
-async fn load_native_trickplay(
-    api_client: &Arc<ApiClient>,
-    item_id: &Uuid,
-    item: BaseItemDto,
-) -> Option<Vec<Thumbnail>> {
-    let (width, trickplay) = item
-        .trickplay
-        .as_ref()
-        .and_then(|trickplay| {
-            trickplay
-                .get(&item_id.simple().to_string())
-                .map(ToOwned::to_owned)
+#[derive(Debug)]
+enum TileState {
+    Loading,
+    Loaded(Arc<RgbImage>),
+    Failed(Instant),
+}
+
+/// Jellyfin's native trickplay, where thumbnails are stored in a grid on each tile image.
+/// Tiles are fetched as they're needed, and cached on disk once decoded so they only need to be
+/// downloaded and decoded once.
+#[derive(Debug)]
+pub(crate) struct NativeTrickplay {
+    api_client: Arc<ApiClient>,
+    item_id: Uuid,
+    width: usize,
+    height: usize,
+    tile_width: usize,
+    tile_height: usize,
+    thumbnail_count: usize,
+    interval: usize,
+    tiles: Mutex<HashMap<usize, TileState>>,
+}
+
+impl NativeTrickplay {
+    fn new(api_client: &Arc<ApiClient>, item_id: &Uuid, item: &BaseItemDto) -> Option<Self> {
+        let (width, trickplay) = item
+            .trickplay
+            .as_ref()
+            .and_then(|trickplay| {
+                trickplay
+                    .get(&item_id.simple().to_string())
+                    .map(ToOwned::to_owned)
+            })
+            // Use the first available width width
+            .and_then(|trickplay| trickplay.into_iter().next())?;
+
+        Some(Self {
+            api_client: api_client.clone(),
+            item_id: *item_id,
+            width: width.parse().ok()?,
+            height: trickplay.height? as usize,
+            tile_width: trickplay.tile_width? as usize,
+            tile_height: trickplay.tile_height? as usize,
+            thumbnail_count: trickplay.thumbnail_count? as usize,
+            interval: trickplay.interval? as usize,
+            tiles: Mutex::default(),
         })
-        // Use the first available width width
-        .and_then(|trickplay| trickplay.into_iter().next())?;
-    let width: usize = width.parse().unwrap();
-
-    let (tile_width, tile_height, thumbnail_count, height, interval) = (
-        trickplay.tile_width? as usize,
-        trickplay.tile_height? as usize,
-        trickplay.thumbnail_count? as usize,
-        trickplay.height? as usize,
-        trickplay.interval? as usize,
-    );
-
-    // TODO: parallelize or fetch tiles as needed
-    let mut tiles = vec![];
-    for i in 0..=(thumbnail_count / tile_width / tile_height) {
-        match api_client.get_trickplay_tile(item_id, width, i).await {
-            Ok(tile) => {
-                tiles.push(tile);
-            }
+    }
+
+    fn thumbnails_per_tile(&self) -> usize {
+        self.tile_width * self.tile_height
+    }
+
+    fn thumbnail_index(&self, timestamp: usize) -> usize {
+        (timestamp * 1000 / self.interval.max(1)).min(self.thumbnail_count.saturating_sub(1))
+    }
+
+    fn tile_count(&self) -> usize {
+        self.thumbnail_count.div_ceil(self.thumbnails_per_tile())
+    }
+
+    fn get_thumbnail(&self, timestamp: usize) -> Option<gdk::Texture> {
+        let thumbnail_index = self.thumbnail_index(timestamp);
+        let tile_index = thumbnail_index / self.thumbnails_per_tile();
+
+        let tile = match self.tiles.lock().unwrap().get(&tile_index) {
+            Some(TileState::Loaded(tile)) => tile.clone(),
+            _ => return None,
+        };
+
+        // Get thumbnail position on it's tile
+        let (x, y) = {
+            let index_in_tile = thumbnail_index % self.thumbnails_per_tile();
+            (
+                (index_in_tile % self.tile_width) * self.width,
+                (index_in_tile / self.tile_width) * self.height,
+            )
+        };
+        if x + self.width > tile.width() as usize || y + self.height > tile.height() as usize {
+            warn!("Trickplay thumbnail {thumbnail_index} is outside of tile {tile_index}");
+            return None;
+        }
+
+        let thumbnail = image::imageops::crop_imm(
+            tile.as_ref(),
+            x as u32,
+            y as u32,
+            self.width as u32,
+            self.height as u32,
+        )
+        .to_image();
+
+        Some(texture_from_image(thumbnail))
+    }
+
+    /// Returns the tiles needed to show thumbnails around the given timestamp that haven't been
+    /// requested yet. The returned tiles are marked as loading, so they're only requested once.
+    pub(crate) fn tiles_to_load(&self, timestamp: usize) -> Vec<usize> {
+        let tile_index = self.thumbnail_index(timestamp) / self.thumbnails_per_tile();
+        let last_tile_index = self.tile_count().saturating_sub(1);
+
+        let mut tiles = self.tiles.lock().unwrap();
+        // Load neighbouring tiles too, so thumbnails are ready when scrubbing past tile boundaries
+        (tile_index.saturating_sub(1)..=(tile_index + 1).min(last_tile_index))
+            .filter(|index| {
+                let should_load = match tiles.get(index) {
+                    None => true,
+                    Some(TileState::Failed(failed_at)) => {
+                        failed_at.elapsed() >= TILE_RETRY_INTERVAL
+                    }
+                    Some(TileState::Loading | TileState::Loaded(_)) => false,
+                };
+                if should_load {
+                    tiles.insert(*index, TileState::Loading);
+                }
+                should_load
+            })
+            .collect()
+    }
+
+    pub(crate) async fn load_tile(&self, tile_index: usize) {
+        let state = match self.fetch_tile(tile_index).await {
+            Ok(tile) => TileState::Loaded(Arc::new(tile)),
             Err(err) => {
-                warn!("Failed to fetch trickplay tile: {err:#?}");
-                return None;
+                warn!("Failed to load trickplay tile {tile_index}: {err:#?}");
+                TileState::Failed(Instant::now())
             }
-        }
+        };
+        self.tiles.lock().unwrap().insert(tile_index, state);
     }
 
-    // TODO: tile processing (loading images and splitting) is very slow
+    async fn fetch_tile(&self, tile_index: usize) -> Result<RgbImage> {
+        let cache_file = tile_cache_file(&self.item_id, self.width, tile_index);
 
-    let tiles: Vec<_> = tiles
-        .into_iter()
-        .map(|tile| {
-            image::load_from_memory_with_format(tile.as_bytes(), ImageFormat::Jpeg).unwrap()
-        })
-        .collect();
-
-    let thumbnails: Vec<_> = (0..thumbnail_count)
-        .map(|thumbnail_index| {
-            let tile_index = thumbnail_index / tile_width / tile_height;
-            // Get thumbnail position on it's tile
-            let (x, y) = {
-                let index_in_tile = thumbnail_index % (tile_width * tile_height);
-                (index_in_tile % tile_width, index_in_tile / tile_height)
-            };
-            let thumbnail = tiles[tile_index].crop_imm(
-                (x * width) as u32,
-                (y * height) as u32,
-                width as u32,
-                height as u32,
-            );
-            let mut image = vec![];
-            thumbnail
-                .write_to(&mut Cursor::new(&mut image), ImageFormat::Jpeg)
-                .unwrap();
-            Thumbnail {
-                timestamp: thumbnail_index * interval / 1000,
-                image: image.into(),
+        if let Some(cache_file) = cache_file.clone().filter(|cache_file| cache_file.exists()) {
+            // Reading a whole tile is slow too, keep it off the async runtime
+            let cached = relm4::spawn_blocking(move || {
+                let tile = read_tile_cache(&cache_file);
+                // Don't keep a broken tile around, so it's downloaded again
+                if tile.is_err() {
+                    if let Err(err) = fs::remove_file(&cache_file) {
+                        warn!("Failed to remove trickplay tile {tile_index} from cache: {err:#?}");
+                    }
+                }
+                tile
+            })
+            .await?;
+
+            match cached {
+                Ok(tile) => return Ok(tile),
+                Err(err) => {
+                    warn!("Failed to read trickplay tile {tile_index} from cache: {err:#?}");
+                }
             }
+        }
+
+        let tile = self
+            .api_client
+            .get_trickplay_tile(&self.item_id, self.width, tile_index)
+            .await?;
+
+        // Decoding is slow, keep it off the async runtime
+        relm4::spawn_blocking(move || -> Result<RgbImage> {
+            let tile = image::load_from_memory_with_format(&tile, ImageFormat::Jpeg)?.into_rgb8();
+            if let Some(cache_file) = &cache_file {
+                if let Err(err) = write_tile_cache(cache_file, &tile) {
+                    warn!("Failed to cache trickplay tile {tile_index}: {err:#?}");
+                }
+            }
+            Ok(tile)
         })
-        .collect();
+        .await?
+    }
+}
 
-    Some(thumbnails)
+fn tile_cache_dir() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("delfin").join("trickplay"))
+}
+
+fn tile_cache_file(item_id: &Uuid, width: usize, tile_index: usize) -> Option<PathBuf> {
+    Some(
+        tile_cache_dir()?
+            .join(item_id.simple().to_string())
+            .join(width.to_string())
+            .join(format!("{tile_index}.rgb")),
+    )
+}
+
+fn read_tile_cache(cache_file: &Path) -> Result<RgbImage> {
+    let mut data = fs::read(cache_file)?;
+    if data.len() < TILE_CACHE_HEADER_SIZE {
+        anyhow::bail!("Cached trickplay tile is missing its header");
+    }
+    let width = u32::from_le_bytes(data[0..4].try_into()?);
+    let height = u32::from_le_bytes(data[4..8].try_into()?);
+    data.drain(..TILE_CACHE_HEADER_SIZE);
+    let tile =
+        RgbImage::from_raw(width, height, data).context("Cached trickplay tile is incomplete")?;
+
+    // Mark the tile as recently used, so it's kept when the cache is cleaned up
+    if let Err(err) = File::options()
+        .write(true)
+        .open(cache_file)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+    {
+        warn!("Failed to update trickplay cache access time: {err:#?}");
+    }
+
+    Ok(tile)
+}
+
+fn write_tile_cache(cache_file: &Path, tile: &RgbImage) -> Result<()> {
+    let dir = cache_file
+        .parent()
+        .context("Trickplay cache file has no parent directory")?;
+    fs::create_dir_all(dir)?;
+    // Write to a temporary file first, so an interrupted write never leaves a partial tile behind
+    let temp_file = cache_file.with_extension("rgb.part");
+    let mut file = File::create(&temp_file)?;
+    file.write_all(&tile.width().to_le_bytes())?;
+    file.write_all(&tile.height().to_le_bytes())?;
+    file.write_all(tile.as_raw())?;
+    drop(file);
+    fs::rename(&temp_file, cache_file)?;
+    Ok(())
+}
+
+/// Removes the least recently used items from the tile cache until it's no larger than `max_size`.
+/// Tiles for `keep` are left alone, since they're about to be used.
+fn evict_tile_cache(cache_dir: &Path, max_size: u64, keep: &Uuid) -> Result<()> {
+    let keep = keep.simple().to_string();
+
+    let mut total_size = 0;
+    let mut items = vec![];
+    for entry in fs::read_dir(cache_dir)? {
+        let path = entry?.path();
+        let (size, modified) = disk_usage(&path)?;
+        total_size += size;
+        if path.is_dir() && path.file_name().is_some_and(|name| name != keep.as_str()) {
+            items.push((path, size, modified));
+        }
+    }
+
+    items.sort_by_key(|(_, _, modified)| *modified);
+    for (path, size, _) in items {
+        if total_size <= max_size {
+            break;
+        }
+        fs::remove_dir_all(&path)?;
+        total_size -= size;
+    }
+
+    Ok(())
+}
+
+/// Returns the total size of a file or directory, and when any file in it was last modified. Cached
+/// tiles are touched whenever they're read, so this is also when they were last used.
+fn disk_usage(path: &Path) -> Result<(u64, SystemTime)> {
+    let metadata = fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok((metadata.len(), metadata.modified()?));
+    }
+
+    let mut size = 0;
+    let mut modified = SystemTime::UNIX_EPOCH;
+    for entry in fs::read_dir(path)? {
+        let (entry_size, entry_modified) = disk_usage(&entry?.path())?;
+        size += entry_size;
+        modified = modified.max(entry_modified);
+    }
+    Ok((size, modified))
+}
+
+fn texture_from_image(image: RgbImage) -> gdk::Texture {
+    let (width, height) = image.dimensions();
+    gdk::MemoryTexture::new(
+        width as i32,
+        height as i32,
+        gdk::MemoryFormat::R8g8b8,
+        &glib::Bytes::from_owned(image.into_raw()),
+        width as usize * 3,
+    )
+    .upcast()
+}
+
+fn get_jellyscrub_thumbnail(thumbnails: &[Thumbnail], timestamp: usize) -> Option<gdk::Texture> {
+    let mut nearest_thumbnail_idx = 0;
+    for (i, thumbnail) in thumbnails.iter().enumerate() {
+        if thumbnail.timestamp < timestamp {
+            nearest_thumbnail_idx = i;
+        }
+    }
+
+    let image = if let Some(thumbnail) = thumbnails.get(nearest_thumbnail_idx) {
+        &thumbnail.image
+    } else {
+        warn!("Error getting trickplay thumbnail");
+        return None;
+    };
+    let pixbuf = match gdk_pixbuf::Pixbuf::from_read(image.clone().reader()) {
+        Ok(pixbuf) => pixbuf,
+        Err(err) => {
+            warn!("Error creating pixbuf for scrubber thumbnail at timestamp {timestamp}: {err}");
+            return None;
+        }
+    };
+    Some(gdk::Texture::for_pixbuf(&pixbuf))
+}
+
+pub(crate) fn fetch_trickplay(
+    api_client: &Arc<ApiClient>,
+    sender: &ComponentSender<VideoPlayer>,
+    item: &BaseItemDto,
+) {
+    let Some(item_id) = item.id else {
+        return;
+    };
+
+    if let Some(trickplay) = NativeTrickplay::new(api_client, &item_id, item) {
+        if let Some(cache_dir) = tile_cache_dir().filter(|cache_dir| cache_dir.exists()) {
+            relm4::spawn_blocking(move || {
+                if let Err(err) = evict_tile_cache(&cache_dir, TILE_CACHE_MAX_SIZE, &item_id) {
+                    warn!("Failed to clean up trickplay cache: {err:#?}");
+                }
+            });
+        }
+
+        sender.oneshot_command(async move {
+            VideoPlayerCommandOutput::LoadedTrickplay(Some(Trickplay::Native(Arc::new(trickplay))))
+        });
+        return;
+    }
+
+    // If native trickplay wasn't found, fall back on Jellyscrub
+    sender.oneshot_command({
+        let api_client = api_client.clone();
+        async move {
+            VideoPlayerCommandOutput::LoadedTrickplay(
+                load_jellyscrub_trickplay(&api_client, &item_id)
+                    .await
+                    .map(|thumbnails| Trickplay::Jellyscrub(Arc::new(thumbnails))),
+            )
+        }
+    });
 }
 
 async fn load_jellyscrub_trickplay(
@@ -147,3 +425,86 @@ async fn load_jellyscrub_trickplay(
 
     Some(thumbnails)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+
+    use super::*;
+
+    fn cached_tile_path(cache_dir: &Path, item_id: &Uuid) -> PathBuf {
+        cache_dir
+            .join(item_id.simple().to_string())
+            .join("320")
+            .join("0.rgb")
+    }
+
+    // Caches a tile taking up `width * 3` bytes, plus the header, that was last used `age` seconds
+    // ago
+    fn write_cached_tile(cache_dir: &Path, item_id: &Uuid, width: u32, age: u64) -> Result<()> {
+        let cache_file = cached_tile_path(cache_dir, item_id);
+        write_tile_cache(&cache_file, &RgbImage::new(width, 1))?;
+        File::options()
+            .write(true)
+            .open(&cache_file)?
+            .set_modified(SystemTime::now() - Duration::from_secs(age))?;
+        Ok(())
+    }
+
+    fn cached_items(cache_dir: &Path) -> Result<Vec<String>> {
+        let mut items: Vec<_> = fs::read_dir(cache_dir)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+            .collect::<Result<_>>()?;
+        items.sort();
+        Ok(items)
+    }
+
+    #[test]
+    fn test_evict_tile_cache() -> Result<()> {
+        let cache_dir = std::env::temp_dir().join(format!("delfin-trickplay-{}", Uuid::new_v4()));
+        let oldest = Uuid::new_v4();
+        let older = Uuid::new_v4();
+        let newer = Uuid::new_v4();
+        let playing = Uuid::new_v4();
+
+        write_cached_tile(&cache_dir, &oldest, 100, 400)?;
+        write_cached_tile(&cache_dir, &older, 100, 300)?;
+        write_cached_tile(&cache_dir, &newer, 100, 200)?;
+        // Tiles for the item being played are kept, even though they're the oldest
+        write_cached_tile(&cache_dir, &playing, 100, 500)?;
+
+        // Each tile takes up 308 bytes, so only two of them fit
+        let result = evict_tile_cache(&cache_dir, 800, &playing);
+        let remaining = cached_items(&cache_dir);
+        fs::remove_dir_all(&cache_dir)?;
+        result?;
+
+        let mut expected = vec![newer.simple().to_string(), playing.simple().to_string()];
+        expected.sort();
+        assert_eq!(remaining?, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_tile_cache_keeps_tile() -> Result<()> {
+        let cache_dir = std::env::temp_dir().join(format!("delfin-trickplay-{}", Uuid::new_v4()));
+        let used = Uuid::new_v4();
+        let unused = Uuid::new_v4();
+
+        write_cached_tile(&cache_dir, &used, 100, 400)?;
+        write_cached_tile(&cache_dir, &unused, 100, 200)?;
+
+        // Reading a tile counts as using it, so the tile that was written more recently is removed
+        let tile = read_tile_cache(&cached_tile_path(&cache_dir, &used));
+        let result = evict_tile_cache(&cache_dir, 400, &Uuid::new_v4());
+        let remaining = cached_items(&cache_dir);
+        fs::remove_dir_all(&cache_dir)?;
+        result?;
+
+        assert_eq!(tile?.dimensions(), (100, 1));
+        assert_eq!(remaining?, [used.simple().to_string()]);
+
+        Ok(())
+    }
+}
@@ -1 +1,2 @@
 app-name = Delfin
+app-open-folder = Open Folder
@@ -25,7 +25,26 @@ library-media-tile-unnamed-item = Unnamed Item
 library-item-count = { $start } - { $end } of { $total }
 library-search-empty =
     .title = Start typing to search
-    .description = Or, try one of these suggestions:
-library-search-title = Results for “{ $searchText }”
+library-search-recent =
+    .title = Recent Searches
+    .clear = Clear recent searches
+library-search-suggestions = Suggestions
+library-search-category =
+    .movies = Movies
+    .series = Shows
+    .episodes = Episodes
+    .people = People
+    .collections = Collections
+    .genres = Genres
+library-search-no-results =
+    .title = No results found
+    .description = Nothing matched “{ $searchText }”
+library-search-error =
+    .title = Error searching library
+    .description = Check the console for errors or try again.
+library-search-related =
+    .person = Featuring { $name }
+    .genre = { $name }
+library-search-back-to-results = Back to results
 library-unnamed-collection = Unnamed Collection
 library-collection-refresh-button = Refresh collection
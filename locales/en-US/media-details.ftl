@@ -13,6 +13,7 @@ media-details-play-button =
            *[false] Play
         } S{ $seasonNumber }:E{ $episodeNumber }
     .next-episode = Play next episode
+media-details-play-from-beginning = Play from the beginning
 media-details-episode-list-empty = No episodes were found for this season.
 media-details-years = { $startYear } – { $endYear }
     .until-present = { $startYear } – Present
@@ -38,3 +39,6 @@ media-details-toggle-watched-error = Failed to mark { $type ->
         *[false] not watched
     }
 media-details-backdrop-error = Failed to load media backdrop
+media-details-chapters = Chapters
+    .current = Currently playing
+    .unnamed = Chapter { $number }
@@ -45,6 +45,49 @@ prefs-vp-on-left-click =
 prefs-vp-on-left-click-options =
     .play-pause = Play/pause video
     .toggle-controls = Show/hide controls
+prefs-vp-on-scroll =
+    .title = When scrolling over the video
+    .subtitle = What happens when you scroll inside the video player
+prefs-vp-on-scroll-options =
+    .volume = Change volume
+    .seek = Skip backwards/forwards
+prefs-vp-resume-behaviour =
+    .title = When playing something you've started watching
+    .subtitle = Whether to resume from where you left off or start from the beginning
+prefs-vp-resume-behaviour-options =
+    .resume = Always resume
+    .ask = Always ask
+    .restart = Always start from the beginning
+prefs-vp-remember-sync-offsets =
+    .title = Remember subtitle and audio delays
+    .subtitle = Restore the delays you set when playing the same video again
+
+# Audio
+# =====
+
+prefs-vp-audio = Audio
+prefs-vp-audio-device =
+    .title = Audio output
+    .subtitle = Devices become available once a video has been played
+prefs-vp-audio-device-auto = Automatic
+prefs-vp-audio-passthrough =
+    .title = Surround sound passthrough
+    .subtitle = Send AC3, E-AC3, DTS, and TrueHD audio to your receiver over S/PDIF or HDMI without decoding it
+prefs-vp-night-mode =
+    .title = Night mode
+    .subtitle = Make loud scenes quieter and dialogue louder
+prefs-vp-normalize-loudness =
+    .title = Normalize loudness
+    .subtitle = Play every video at a similar volume
+
+# Screenshots
+# ===========
+
+prefs-vp-screenshots = Screenshots
+    .description = In the file name, {"{"}title{"}"} is replaced with the video's name, {"{"}timestamp{"}"} with the playback position, and {"{"}date{"}"} with the current date and time
+prefs-vp-screenshot-directory = Folder
+    .choose = Choose Screenshot Folder
+prefs-vp-screenshot-filename = File name
 
 # Subtitles
 # =========
@@ -54,6 +97,21 @@ prefs-vp-subs-reset =
     .label = Reset
     .tooltip = Reset subtitle settings
 
+prefs-vp-subs-track-selection =
+    .title = Language preferences
+    .subtitle = Leave empty to use the preferences from your Jellyfin account
+prefs-vp-subs-audio-language = Preferred audio language (e.g. eng)
+prefs-vp-subs-subtitle-language = Preferred subtitle language (e.g. eng)
+prefs-vp-subs-mode =
+    .title = Subtitle mode
+prefs-vp-subs-mode-options =
+    .server = Use Jellyfin account setting
+    .default = Default
+    .always = Always
+    .only-forced = Only forced
+    .smart = Smart
+    .none = None
+
 prefs-vp-subs-style = Subtitle style
 prefs-vp-subs-style-ass-warning =
     These settings don't apply to ASS/SSA subtitles.
@@ -81,6 +139,34 @@ prefs-vp-subs-scale =
 prefs-vp-subs-position =
     .title = Subtitle position
     .subtitle = Where 0 is the top of the screen, and 100 is the bottom
+prefs-vp-subs-secondary-position =
+    .title = Secondary subtitle position
+    .subtitle = Position of the secondary subtitle track, using the same scale
+
+# Media Segments
+# ==============
+
+prefs-vp-media-segments = Skipping
+prefs-vp-media-segments-description = Choose what happens when playback reaches a part of an episode or movie marked by your server
+prefs-vp-media-segments-options =
+    .ask = Show skip button
+    .auto-skip = Skip automatically
+    .ignore = Do nothing
+prefs-vp-media-segments-intro =
+    .title = Intros
+    .subtitle = Opening sequences and title cards
+prefs-vp-media-segments-outro =
+    .title = Credits
+    .subtitle = End credits and outros
+prefs-vp-media-segments-recap =
+    .title = Recaps
+    .subtitle = Summaries of previous episodes
+prefs-vp-media-segments-preview =
+    .title = Previews
+    .subtitle = Previews of upcoming episodes
+prefs-vp-media-segments-commercial =
+    .title = Commercials
+    .subtitle = Commercial breaks
 
 # Plugins
 # =======
@@ -89,11 +175,8 @@ prefs-vp-plugins = Plugins
 prefs-vp-intro-skipper =
     .title = Intro Skipper plugin
     .subtitle =
-        Shows a Skip Intro button during episode intros.
-        This requires the <a href="{ $introSkipperUrl }">Intro Skipper</a> plugin to be installed on your server.
-prefs-vp-intro-skipper-auto-skip =
-    .title = Automatically skip intros
-    .subtitle = Skip intros without having to press the Skip Intro button
+        Detect intros using the <a href="{ $introSkipperUrl }">Intro Skipper</a> plugin.
+        This is only used on servers that don't support media segments.
 prefs-vp-jellyscrub =
     .title = Jellyscrub plugin
     .subtitle =
@@ -118,3 +201,23 @@ prefs-vp-backend =
 prefs-vp-hls-playback =
     .title = HLS playback
     .subtitle = This may break audio and subtitle track selection.
+
+# Keyboard Shortcuts
+# ==================
+
+prefs-kb-page = Keyboard Shortcuts
+prefs-kb-video-player =
+    .title = Video Player
+    .description = Select a shortcut to change its key.
+prefs-kb-reset =
+    .label = Reset
+    .tooltip = Reset keyboard shortcuts
+prefs-kb-disabled = Disabled
+prefs-kb-dialog =
+    .heading = Set Shortcut
+    .body = Press a key to use for “{ $action }”.
+    .waiting = Waiting for a key…
+    .conflict = This key is used for “{ $action }”, which will be left without a shortcut.
+    .response-cancel = Cancel
+    .response-disable = Disable
+    .response-set = Set
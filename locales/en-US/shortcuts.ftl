@@ -13,11 +13,32 @@ sc-lib-refresh = Refresh
 # Video Player
 # ============
 sc-vp = Video Player
-sc-vp-play-pause = Play/pause
-sc-vp-seek = Seek
-sc-vp-volume = Adjust volume
-sc-vp-mute = Mute/unmute
-sc-vp-subtitles = Toggle subtitles
-sc-vp-fullscreen = Toggle fullscreen
-sc-vp-frame-step = Seek frame-by-frame
-sc-vp-playback-speed = Adjust playback speed
+kb-action-toggle-play = Play/pause
+kb-action-skip-backwards = Skip backwards
+kb-action-skip-forwards = Skip forwards
+kb-action-seek = Seek { $amount } seconds
+kb-action-volume = Change volume by { $amount }%
+kb-action-toggle-mute = Mute/unmute
+kb-action-toggle-night-mode = Toggle night mode
+kb-action-toggle-fullscreen = Toggle fullscreen
+kb-action-exit-fullscreen = Exit fullscreen
+kb-action-toggle-subtitles = Toggle subtitles
+kb-action-subtitle-delay = Change subtitle delay by { $amount } ms
+kb-action-audio-delay = Change audio delay by { $amount } ms
+kb-action-frame-step-backwards = Previous frame
+kb-action-frame-step-forwards = Next frame
+kb-action-speed-down = Decrease playback speed
+kb-action-speed-up = Increase playback speed
+kb-action-previous-chapter = Previous chapter
+kb-action-next-chapter = Next chapter
+kb-action-cycle-aspect-ratio = Cycle aspect ratio
+kb-action-toggle-panscan = Toggle crop to fill
+kb-action-zoom-in = Zoom in
+kb-action-zoom-out = Zoom out
+kb-action-reset-video = Reset aspect ratio and zoom
+kb-action-screenshot = Take screenshot
+kb-action-screenshot-without-subtitles = Take screenshot without subtitles
+kb-action-picture-in-picture = Picture-in-picture
+kb-action-toggle-stats = Toggle playback statistics
+kb-action-cycle-ab-loop = Set loop start, then loop end
+kb-action-clear-ab-loop = Clear loop
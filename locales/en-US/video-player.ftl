@@ -17,6 +17,8 @@ vp-next-prev-episode-tooltip =
         [next] Next
        *[previous] Previous
     } episode
+vp-pip-tooltip = Picture-in-picture
+vp-pip-close-tooltip = Return to player
 vp-play-pause-tooltip =
     { $playing ->
         [true] Pause
@@ -33,28 +35,137 @@ vp-skip-forwards-backwards-tooltip =
         [one] second
        *[other] seconds
     }
-vp-skip-intro =
-    .manual = Skip Intro
-    .auto = Skipping intro in { $seconds }…
+vp-skip-segment =
+    .manual =
+        Skip { $segmentType ->
+            [intro] Intro
+            [outro] Credits
+            [recap] Recap
+            [preview] Preview
+            [commercial] Commercial
+           *[other] Segment
+        }
+    .auto =
+        Skipping { $segmentType ->
+            [intro] intro
+            [outro] credits
+            [recap] recap
+            [preview] preview
+            [commercial] commercial
+           *[other] segment
+        } in { $seconds }…
 vp-audio-track-tooltip = Audio Tracks
 vp-audio-track-menu = Audio Track
+vp-audio-delay-toast = Audio delay: { $delay } ms
+vp-audio-settings-tooltip = Audio Settings
+vp-audio-device-menu = Audio Output
+vp-audio-device-auto = Automatic
+vp-audio-filters-menu = Audio Filters
+vp-night-mode = Night Mode
+vp-normalize-loudness = Normalize Loudness
+vp-night-mode-toast =
+    { $enabled ->
+        [true] Night mode on
+       *[false] Night mode off
+    }
+vp-ab-loop-menu =
+    .set-start = Set Loop Start
+    .set-end = Set Loop End
+    .clear = Clear Loop
+vp-ab-loop-indicator =
+    .start = Loop from { $start }
+    .active = Looping { $start } – { $end }
+vp-stats =
+    .playback = Playback
+    .container = Container
+    .video-codec = Video Codec
+    .resolution = Resolution
+    .frame-rate = Frame Rate
+    .hardware-decoding = Hardware Decoding
+    .hardware-decoding-off = Off
+    .video-bitrate = Video Bitrate
+    .audio-codec = Audio Codec
+    .audio-bitrate = Audio Bitrate
+    .buffered = Buffered
+    .dropped-frames = Dropped Frames
+    .unknown = Unknown
+vp-stats-play-method =
+    { $method ->
+        [direct-play] Direct Play
+        [direct-stream] Direct Stream
+       *[transcode] Transcode
+    }
+vp-osd-volume = Volume { $volume }%
+vp-osd-seek = { $seconds } s
+vp-error-page =
+    .title = Couldn't Play Video
+    .description = Something went wrong while playing this video ({ $error }).
+    .description-unknown = Something went wrong while playing this video.
+    .retry = Try Again
+    .transcode = Try Transcoding
+    .back = Go Back
+vp-screenshot-saved = Screenshot saved
+vp-screenshot-error = Couldn't save screenshot
 vp-subtitle-track-tooltip =
     { $subtitlesAvailable ->
         [true] Subtitle Tracks
        *[false] No Subtitle Tracks Available
     }
 vp-subtitle-track-menu = Subtitle Track
+vp-secondary-subtitle-track-menu = Secondary Subtitle Track
 vp-subtitle-track-external = External Subtitle Track
 vp-subtitle-track-off = Off
 vp-no-subtitles-available = No subtitles available.
+vp-subtitle-delay-toast = Subtitle delay: { $delay } ms
+vp-subtitle-search = Find Subtitles…
+vp-subtitle-open-file = Open Subtitle File…
+vp-subtitle-open-file-title = Open Subtitle File
+vp-subtitle-open-file-filter = Subtitle Files
+vp-subtitle-search-title = Find Subtitles
+vp-subtitle-search-language = Language (e.g. eng)
+vp-subtitle-search-no-results = No Subtitles Found
+vp-subtitle-search-error = Error Searching for Subtitles
+vp-subtitle-search-download = Download
+vp-subtitle-search-download-error = Error downloading subtitles.
+vp-subtitle-search-hash-match = Matches this file exactly
+vp-subtitle-search-downloads =
+    { $count ->
+        [one] { $count } download
+       *[other] { $count } downloads
+    }
 vp-volume-mute-tooltip =
     { $muted ->
         [true] Unmute
        *[false] Mute
     }
+vp-video-settings-tooltip = Video
+vp-video-settings-aspect-ratio = Aspect Ratio
+vp-video-settings-aspect-ratio-auto = Automatic
+vp-video-settings-aspect-ratio-toast = Aspect ratio: { $aspectRatio }
+vp-video-settings-zoom = Zoom
+vp-video-settings-zoom-in = Zoom In
+vp-video-settings-zoom-out = Zoom Out
+vp-video-settings-zoom-toast = Zoom: { $zoom }%
+vp-video-settings-panscan = Crop to Fill
+vp-video-settings-panscan-toast =
+    { $enabled ->
+        [true] Cropping video to fill
+       *[false] Showing entire video
+    }
+vp-video-settings-reset = Reset
 vp-playback-speed-tooltip = Playback Speed
 vp-playback-speed-normal = Normal
 vp-playback-speed-toast = Playback speed: { $speed }x
+vp-chapters = Chapters
+    .unnamed = Chapter { $number }
+    .toast = Chapter: { $name }
+vp-resume-dialog =
+    .heading = Resume Playback?
+    .body = You stopped watching { $name } at { $position }.
+    .body-unnamed = You stopped watching this at { $position }.
+    .response-cancel = Cancel
+    .response-restart = Start from Beginning
+    .response-resume = Resume from { $position }
 vp-unnamed-track = Unnamed Track
 vp-backend-mpv-track-name =
     .title-and-language = { $title } – { $language }
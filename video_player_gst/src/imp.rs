@@ -1,4 +1,7 @@
-use std::cell::OnceCell;
+use std::{
+    cell::{Cell, OnceCell},
+    sync::{Arc, Mutex},
+};
 
 use gst::prelude::*;
 use gstplay::PlaySignalAdapter;
@@ -9,13 +12,23 @@ use gtk::glib;
 use gtk::prelude::*;
 use gtk::subclass::prelude::*;
 use relm4::gtk;
-use relm4::gtk::gdk;
+use relm4::gtk::{gdk, graphene, gsk};
+
+use crate::SubtitleStyle;
 
 #[derive(Debug, Default)]
 pub struct GstVideoPlayer {
     pub player: OnceCell<gstplay::Play>,
     pub signal_adapter: OnceCell<PlaySignalAdapter>,
     picture: OnceCell<gtk::Picture>,
+    // Overrides the video's aspect ratio (width / height)
+    pub aspect_ratio: Cell<Option<f64>>,
+    // Zoom level, where 0 is the original size, 1 is twice the size, and -1 is half the size
+    pub zoom: Cell<f64>,
+    // How much to crop the video to fill the widget, from 0 to 1
+    pub panscan: Cell<f64>,
+    // Shared with the pipeline's streaming threads, which create the elements subtitles are drawn by
+    pub subtitle_style: Arc<Mutex<SubtitleStyle>>,
 }
 
 #[glib::object_subclass]
@@ -23,10 +36,6 @@ impl ObjectSubclass for GstVideoPlayer {
     const NAME: &'static str = "GstVideoPlayer";
     type Type = super::GstVideoPlayer;
     type ParentType = gtk::Widget;
-
-    fn class_init(class: &mut Self::Class) {
-        class.set_layout_manager_type::<gtk::BoxLayout>();
-    }
 }
 
 impl ObjectImpl for GstVideoPlayer {
@@ -38,6 +47,8 @@ impl ObjectImpl for GstVideoPlayer {
         obj.add_css_class("gst-video-player");
         obj.set_hexpand(true);
         obj.set_vexpand(true);
+        // Zoomed in video is larger than the widget
+        obj.set_overflow(gtk::Overflow::Hidden);
 
         let sink = ElementFactory::make("gtk4paintablesink").build().unwrap();
         let paintable = sink.property::<gdk::Paintable>("paintable");
@@ -52,10 +63,24 @@ impl ObjectImpl for GstVideoPlayer {
 
         let signal_adapter = PlaySignalAdapter::new(&player);
 
+        if let Ok(pipeline) = player.pipeline().downcast::<gst::Bin>() {
+            let subtitle_style = self.subtitle_style.clone();
+            pipeline.connect_deep_element_added(move |_, _, element| {
+                if element
+                    .factory()
+                    .is_some_and(|factory| factory.name() == "textoverlay")
+                {
+                    subtitle_style.lock().unwrap().apply(element);
+                }
+            });
+        }
+
         let picture = gtk::Picture::new();
         picture.add_css_class("gst-video-player__video-out");
         picture.set_hexpand(true);
         picture.set_vexpand(true);
+        // Sized in size_allocate() to apply the aspect ratio, zoom, and panscan
+        picture.set_content_fit(gtk::ContentFit::Fill);
         picture.set_parent(&*obj);
         picture.set_paintable(Some(&paintable));
 
@@ -70,7 +95,52 @@ impl ObjectImpl for GstVideoPlayer {
     }
 }
 
-impl WidgetImpl for GstVideoPlayer {}
+impl WidgetImpl for GstVideoPlayer {
+    fn measure(&self, orientation: gtk::Orientation, for_size: i32) -> (i32, i32, i32, i32) {
+        self.picture.get().unwrap().measure(orientation, for_size)
+    }
+
+    fn size_allocate(&self, width: i32, height: i32, baseline: i32) {
+        let picture = self.picture.get().unwrap();
+
+        let video_aspect_ratio = self.aspect_ratio.get().or_else(|| {
+            picture
+                .paintable()
+                .map(|paintable| paintable.intrinsic_aspect_ratio())
+                .filter(|aspect_ratio| *aspect_ratio > 0.0)
+        });
+        let Some(video_aspect_ratio) = video_aspect_ratio.filter(|_| width > 0 && height > 0)
+        else {
+            picture.allocate(width, height, baseline, None);
+            return;
+        };
+
+        let (width, height) = (width as f64, height as f64);
+
+        // Largest size that fits inside the widget
+        let (fit_width, fit_height) = if video_aspect_ratio > width / height {
+            (width, width / video_aspect_ratio)
+        } else {
+            (height * video_aspect_ratio, height)
+        };
+
+        // Scale the video up to fill the widget with panscan, cropping the edges
+        let fill_scale = (width / fit_width).max(height / fit_height);
+        let scale = (1.0 + self.panscan.get() * (fill_scale - 1.0)) * self.zoom.get().exp2();
+        let (video_width, video_height) = (fit_width * scale, fit_height * scale);
+
+        let transform = gsk::Transform::new().translate(&graphene::Point::new(
+            ((width - video_width) / 2.0) as f32,
+            ((height - video_height) / 2.0) as f32,
+        ));
+        picture.allocate(
+            video_width.round() as i32,
+            video_height.round() as i32,
+            baseline,
+            Some(transform),
+        );
+    }
+}
 
 fn load_css() {
     let provider = gtk::CssProvider::new();
@@ -3,16 +3,64 @@ mod imp;
 use std::cell::OnceCell;
 
 use gst::glib::{Error, SignalHandlerId};
-use gst::prelude::ObjectExt;
+use gst::prelude::{Cast, ElementExt, ElementExtManual, GstBinExtManual, ObjectExt};
 use gst::{ClockTime, Structure};
-use gstplay::{PlayAudioInfo, PlayMediaInfo, PlayState, PlaySubtitleInfo};
+use gstplay::{PlayAudioInfo, PlayMediaInfo, PlayState, PlaySubtitleInfo, PlayVideoInfo};
 use gtk::glib;
+use gtk::pango;
 use gtk::subclass::prelude::*;
 use relm4::gtk;
 
 pub use gst;
 pub use gstplay;
 
+// textoverlay sizes text relative to 640 pixel wide video, while subtitle font sizes are relative
+// to 720 pixel high video like mpv's, so convert between them for 16:9 video
+const SUBTITLE_FONT_SCALE: f64 = 640.0 / 1280.0;
+
+/// How text subtitles are drawn. Subtitles with their own styling, like ASS, ignore this.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubtitleStyle {
+    /// Pango font description, with the size relative to 720 pixel high video.
+    pub font: String,
+    pub scale: f64,
+    /// Colours are in ARGB format.
+    pub colour: u32,
+    pub background_colour: u32,
+    /// From 0 - 100, where 0 is the top of the video and 100 is the bottom.
+    pub position: u32,
+}
+
+impl Default for SubtitleStyle {
+    fn default() -> Self {
+        Self {
+            font: "Sans 55".into(),
+            scale: 1.0,
+            colour: 0xFFFFFFFF,
+            background_colour: 0x00000000,
+            position: 100,
+        }
+    }
+}
+
+impl SubtitleStyle {
+    fn apply(&self, text_overlay: &gst::Element) {
+        let mut font = pango::FontDescription::from_string(&self.font);
+        let size = font.size() as f64 / pango::SCALE as f64;
+        font.set_absolute_size(size * self.scale * SUBTITLE_FONT_SCALE * pango::SCALE as f64);
+        text_overlay.set_property("font-desc", font.to_str().as_str());
+        text_overlay.set_property("color", self.colour);
+
+        // textoverlay can only darken the background, so use the background colour's opacity
+        let background_alpha = self.background_colour >> 24;
+        text_overlay.set_property("shaded-background", background_alpha > 0);
+        text_overlay.set_property("shading-value", background_alpha.max(1));
+
+        text_overlay.set_property_from_str("valignment", "position");
+        text_overlay.set_property("ypos", (self.position as f64 / 100.0).clamp(0.0, 1.0));
+    }
+}
+
 pub fn init_gst() {
     gst::init().expect("Error initializing GStreamer");
     gstgtk4::plugin_register_static().expect("Error registering GST GTK4 plugin");
@@ -55,6 +103,14 @@ impl GstVideoPlayer {
             .unblock_signal(handler_id);
     }
 
+    pub fn disconnect(&self, handler_id: SignalHandlerId) {
+        self.imp()
+            .signal_adapter
+            .get()
+            .unwrap()
+            .disconnect(handler_id);
+    }
+
     pub fn play_uri(&self, uri: &str) {
         let imp = self.imp();
 
@@ -99,6 +155,12 @@ impl GstVideoPlayer {
         player.set_mute(muted);
     }
 
+    pub fn volume(&self) -> f64 {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.volume()
+    }
+
     pub fn set_volume(&self, volume: f64) {
         let imp = self.imp();
         let player = imp.player.get().unwrap();
@@ -111,6 +173,56 @@ impl GstVideoPlayer {
         player.position()
     }
 
+    pub fn rate(&self) -> f64 {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.rate()
+    }
+
+    pub fn set_rate(&self, rate: f64) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.set_rate(rate);
+    }
+
+    /// Pause and show the next frame.
+    pub fn frame_step_forwards(&self) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.pause();
+        player.pipeline().send_event(gst::event::Step::new(
+            gst::format::Buffers::ONE,
+            1.0,
+            true,
+            false,
+        ));
+    }
+
+    /// Pause and show the previous frame.
+    pub fn frame_step_backwards(&self) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.pause();
+
+        // Frames can only be stepped forwards, so seek back by one frame instead
+        let (Some(position), Some(video)) = (player.position(), player.current_video_track())
+        else {
+            return;
+        };
+        let framerate = video.framerate();
+        if framerate.numer() <= 0 || framerate.denom() <= 0 {
+            return;
+        }
+        let frame_duration = ClockTime::from_nseconds(
+            ClockTime::SECOND.nseconds() * framerate.denom() as u64 / framerate.numer() as u64,
+        );
+        // Player seeks snap to keyframes, so seek the pipeline directly
+        let _ = player.pipeline().seek_simple(
+            gst::SeekFlags::FLUSH | gst::SeekFlags::ACCURATE,
+            position.saturating_sub(frame_duration),
+        );
+    }
+
     pub fn current_subtitle_track(&self) -> Option<PlaySubtitleInfo> {
         let imp = self.imp();
         let player = imp.player.get().unwrap();
@@ -129,6 +241,35 @@ impl GstVideoPlayer {
         player.set_subtitle_track(stream_index)
     }
 
+    /// Load subtitles from an external file, replacing any that were loaded before.
+    pub fn set_subtitle_uri(&self, uri: Option<&str>) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.set_subtitle_uri(uri);
+    }
+
+    /// Change how text subtitles are drawn.
+    pub fn update_subtitle_style<F>(&self, update: F)
+    where
+        F: FnOnce(&mut SubtitleStyle),
+    {
+        let imp = self.imp();
+        let mut subtitle_style = imp.subtitle_style.lock().unwrap();
+        update(&mut subtitle_style);
+
+        // Restyle subtitles that are already showing, new ones are styled as they're created
+        let player = imp.player.get().unwrap();
+        if let Ok(pipeline) = player.pipeline().downcast::<gst::Bin>() {
+            for text_overlay in pipeline
+                .iterate_all_by_element_factory_name("textoverlay")
+                .into_iter()
+                .flatten()
+            {
+                subtitle_style.apply(&text_overlay);
+            }
+        }
+    }
+
     pub fn current_audio_track(&self) -> Option<PlayAudioInfo> {
         let imp = self.imp();
         let player = imp.player.get().unwrap();
@@ -147,14 +288,84 @@ impl GstVideoPlayer {
         player.set_audio_track(stream_index)
     }
 
+    pub fn current_video_track(&self) -> Option<PlayVideoInfo> {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.current_video_track()
+    }
+
+    pub fn media_info(&self) -> Option<PlayMediaInfo> {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.media_info()
+    }
+
+    pub fn set_subtitle_offset(&self, offset: i64) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.set_subtitle_video_offset(offset);
+    }
+
+    pub fn set_audio_offset(&self, offset: i64) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.set_audio_video_offset(offset);
+    }
+
+    /// Get the last video frame that was shown, encoded as a PNG image.
+    pub fn png_snapshot(&self) -> Option<gst::Sample> {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.video_snapshot(gstplay::PlaySnapshotFormat::Png, None)
+    }
+
+    /// Set the element audio is played through, or None to pick one automatically. Takes effect the
+    /// next time a video is played.
+    pub fn set_audio_sink(&self, sink: Option<&gst::Element>) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.pipeline().set_property("audio-sink", sink);
+    }
+
+    /// Set the element that audio is passed through before it's played, or None to remove it.
+    /// Takes effect the next time a video is played.
+    pub fn set_audio_filter(&self, filter: Option<&gst::Element>) {
+        let imp = self.imp();
+        let player = imp.player.get().unwrap();
+        player.pipeline().set_property("audio-filter", filter);
+    }
+
+    /// Override the video's aspect ratio (width / height), or None to use the video's own.
+    pub fn set_aspect_ratio(&self, aspect_ratio: Option<f64>) {
+        self.imp().aspect_ratio.set(aspect_ratio);
+        self.queue_allocate();
+    }
+
+    /// Set zoom, where 0 is the original size, 1 is twice the size, and -1 is half the size.
+    pub fn set_zoom(&self, zoom: f64) {
+        self.imp().zoom.set(zoom);
+        self.queue_allocate();
+    }
+
+    /// Crop the video to fill the player, from 0 (no cropping) to 1 (fills the player).
+    pub fn set_panscan(&self, panscan: f64) {
+        self.imp().panscan.set(panscan.clamp(0.0, 1.0));
+        self.queue_allocate();
+    }
+
+    // The signal adapter emits signals on the main thread, so callbacks for the player state and
+    // errors don't need to be Send
     pub fn connect_state_changed<F>(&self, callback: F) -> SignalHandlerId
     where
-        F: Fn(&PlayState) + Send + 'static,
+        F: Fn(PlayState) + 'static,
     {
         let imp = self.imp();
         let signal_adapter = imp.signal_adapter.get().unwrap();
-        signal_adapter.connect_state_changed(move |_, play_state| {
-            callback(&play_state);
+        signal_adapter.connect_local("state-changed", false, move |values| {
+            if let Ok(play_state) = values[1].get::<PlayState>() {
+                callback(play_state);
+            }
+            None
         })
     }
 
@@ -173,14 +384,18 @@ impl GstVideoPlayer {
 
     pub fn connect_error<F>(&self, callback: F) -> SignalHandlerId
     where
-        F: Fn(&Error, Option<&Structure>) + Send + 'static,
+        F: Fn(&Error, Option<&Structure>) + 'static,
     {
         let imp = self.imp();
 
         let signal_adapter = imp.signal_adapter.get().unwrap();
 
-        signal_adapter.connect_error(move |_, error, structure| {
-            callback(error, structure);
+        signal_adapter.connect_local("error", false, move |values| {
+            if let Ok(error) = values[1].get::<Error>() {
+                let structure = values[2].get::<Option<Structure>>().ok().flatten();
+                callback(&error, structure.as_ref());
+            }
+            None
         })
     }
 
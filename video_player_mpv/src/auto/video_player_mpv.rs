@@ -39,11 +39,25 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_audio_device_list")]
+    pub fn audio_device_list(&self) -> Vec<glib::GString> {
+        unsafe {
+            FromGlibPtrContainer::from_glib_full(ffi::vpm_video_player_mpv_audio_device_list(
+                self.to_glib_none().0,
+            ))
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_current_audio_track")]
     pub fn current_audio_track(&self) -> i32 {
         unsafe { ffi::vpm_video_player_mpv_current_audio_track(self.to_glib_none().0) }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_current_secondary_subtitle_track")]
+    pub fn current_secondary_subtitle_track(&self) -> i32 {
+        unsafe { ffi::vpm_video_player_mpv_current_secondary_subtitle_track(self.to_glib_none().0) }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_current_subtitle_track")]
     pub fn current_subtitle_track(&self) -> i32 {
         unsafe { ffi::vpm_video_player_mpv_current_subtitle_track(self.to_glib_none().0) }
@@ -99,6 +113,27 @@ impl VideoPlayerMpv {
         unsafe { ffi::vpm_video_player_mpv_position(self.to_glib_none().0) }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_property_string")]
+    pub fn property_string(&self, name: &str) -> Option<glib::GString> {
+        unsafe {
+            from_glib_full(ffi::vpm_video_player_mpv_property_string(
+                self.to_glib_none().0,
+                name.to_glib_none().0,
+            ))
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_screenshot_to_file")]
+    pub fn screenshot_to_file(&self, filename: &str, subtitles: bool) -> bool {
+        unsafe {
+            ffi::vpm_video_player_mpv_screenshot_to_file(
+                self.to_glib_none().0,
+                filename.to_glib_none().0,
+                subtitles,
+            )
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_seek_by")]
     pub fn seek_by(&self, seconds: i32) {
         unsafe {
@@ -113,6 +148,64 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_set_ab_loop_a")]
+    pub fn set_ab_loop_a(&self, seconds: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_ab_loop_a(self.to_glib_none().0, seconds);
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_ab_loop_b")]
+    pub fn set_ab_loop_b(&self, seconds: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_ab_loop_b(self.to_glib_none().0, seconds);
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_aspect_ratio")]
+    pub fn set_aspect_ratio(&self, aspect_ratio: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_aspect_ratio(self.to_glib_none().0, aspect_ratio);
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_audio_delay")]
+    pub fn set_audio_delay(&self, seconds: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_audio_delay(self.to_glib_none().0, seconds);
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_audio_device")]
+    pub fn set_audio_device(&self, name: &str) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_audio_device(
+                self.to_glib_none().0,
+                name.to_glib_none().0,
+            );
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_audio_filter")]
+    pub fn set_audio_filter(&self, filter: &str) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_audio_filter(
+                self.to_glib_none().0,
+                filter.to_glib_none().0,
+            );
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_audio_spdif")]
+    pub fn set_audio_spdif(&self, codecs: &str) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_audio_spdif(
+                self.to_glib_none().0,
+                codecs.to_glib_none().0,
+            );
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_set_audio_track")]
     pub fn set_audio_track(&self, audio_track_id: u32) {
         unsafe {
@@ -127,6 +220,13 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_set_panscan")]
+    pub fn set_panscan(&self, panscan: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_panscan(self.to_glib_none().0, panscan);
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_set_playback_speed")]
     pub fn set_playback_speed(&self, playback_speed: f64) {
         unsafe {
@@ -134,6 +234,26 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_set_secondary_subtitle_position")]
+    pub fn set_secondary_subtitle_position(&self, position: u32) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_secondary_subtitle_position(
+                self.to_glib_none().0,
+                position,
+            );
+        }
+    }
+
+    #[doc(alias = "vpm_video_player_mpv_set_secondary_subtitle_track")]
+    pub fn set_secondary_subtitle_track(&self, subtitle_track_id: u32) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_secondary_subtitle_track(
+                self.to_glib_none().0,
+                subtitle_track_id,
+            );
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_set_subtitle_background_colour")]
     pub fn set_subtitle_background_colour(&self, colour: &str) {
         unsafe {
@@ -154,6 +274,13 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_set_subtitle_delay")]
+    pub fn set_subtitle_delay(&self, seconds: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_subtitle_delay(self.to_glib_none().0, seconds);
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_set_subtitle_font_bold")]
     pub fn set_subtitle_font_bold(&self, bold: bool) {
         unsafe {
@@ -213,6 +340,13 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "vpm_video_player_mpv_set_zoom")]
+    pub fn set_zoom(&self, zoom: f64) {
+        unsafe {
+            ffi::vpm_video_player_mpv_set_zoom(self.to_glib_none().0, zoom);
+        }
+    }
+
     #[doc(alias = "vpm_video_player_mpv_stop")]
     pub fn stop(&self) {
         unsafe {
@@ -225,6 +359,33 @@ impl VideoPlayerMpv {
         unsafe { ffi::vpm_video_player_mpv_volume(self.to_glib_none().0) }
     }
 
+    #[doc(alias = "audio-device-list-updated")]
+    pub fn connect_audio_device_list_updated<F: Fn(&Self) + 'static>(
+        &self,
+        f: F,
+    ) -> SignalHandlerId {
+        unsafe extern "C" fn audio_device_list_updated_trampoline<
+            F: Fn(&VideoPlayerMpv) + 'static,
+        >(
+            this: *mut ffi::VpmVideoPlayerMpv,
+            f: glib::ffi::gpointer,
+        ) {
+            let f: &F = &*(f as *const F);
+            f(&from_glib_borrow(this))
+        }
+        unsafe {
+            let f: Box_<F> = Box_::new(f);
+            connect_raw(
+                self.as_ptr() as *mut _,
+                b"audio-device-list-updated\0".as_ptr() as *const _,
+                Some(std::mem::transmute::<_, unsafe extern "C" fn()>(
+                    audio_device_list_updated_trampoline::<F> as *const (),
+                )),
+                Box_::into_raw(f),
+            )
+        }
+    }
+
     #[doc(alias = "core-idle")]
     pub fn connect_core_idle<F: Fn(&Self, bool) + 'static>(&self, f: F) -> SignalHandlerId {
         unsafe extern "C" fn core_idle_trampoline<F: Fn(&VideoPlayerMpv, bool) + 'static>(
@@ -319,6 +480,32 @@ impl VideoPlayerMpv {
         }
     }
 
+    #[doc(alias = "error")]
+    pub fn connect_error<F: Fn(&Self, &str) + 'static>(&self, f: F) -> SignalHandlerId {
+        unsafe extern "C" fn error_trampoline<F: Fn(&VideoPlayerMpv, &str) + 'static>(
+            this: *mut ffi::VpmVideoPlayerMpv,
+            object: *mut libc::c_char,
+            f: glib::ffi::gpointer,
+        ) {
+            let f: &F = &*(f as *const F);
+            f(
+                &from_glib_borrow(this),
+                &glib::GString::from_glib_borrow(object),
+            )
+        }
+        unsafe {
+            let f: Box_<F> = Box_::new(f);
+            connect_raw(
+                self.as_ptr() as *mut _,
+                b"error\0".as_ptr() as *const _,
+                Some(std::mem::transmute::<_, unsafe extern "C" fn()>(
+                    error_trampoline::<F> as *const (),
+                )),
+                Box_::into_raw(f),
+            )
+        }
+    }
+
     #[doc(alias = "mute-updated")]
     pub fn connect_mute_updated<F: Fn(&Self, bool) + 'static>(&self, f: F) -> SignalHandlerId {
         unsafe extern "C" fn mute_updated_trampoline<F: Fn(&VideoPlayerMpv, bool) + 'static>(
@@ -128,7 +128,13 @@ extern "C" {
         url: *const c_char,
         title: *const c_char,
     );
+    pub fn vpm_video_player_mpv_audio_device_list(
+        self_: *mut VpmVideoPlayerMpv,
+    ) -> *mut *mut c_char;
     pub fn vpm_video_player_mpv_current_audio_track(self_: *mut VpmVideoPlayerMpv) -> c_int;
+    pub fn vpm_video_player_mpv_current_secondary_subtitle_track(
+        self_: *mut VpmVideoPlayerMpv,
+    ) -> c_int;
     pub fn vpm_video_player_mpv_current_subtitle_track(self_: *mut VpmVideoPlayerMpv) -> c_int;
     pub fn vpm_video_player_mpv_frame_step_backwards(self_: *mut VpmVideoPlayerMpv);
     pub fn vpm_video_player_mpv_frame_step_forwards(self_: *mut VpmVideoPlayerMpv);
@@ -138,17 +144,54 @@ extern "C" {
     pub fn vpm_video_player_mpv_play_uri(self_: *mut VpmVideoPlayerMpv, uri: *const c_char);
     pub fn vpm_video_player_mpv_playback_speed(self_: *mut VpmVideoPlayerMpv) -> c_double;
     pub fn vpm_video_player_mpv_position(self_: *mut VpmVideoPlayerMpv) -> c_double;
+    pub fn vpm_video_player_mpv_property_string(
+        self_: *mut VpmVideoPlayerMpv,
+        name: *const c_char,
+    ) -> *mut c_char;
+    pub fn vpm_video_player_mpv_screenshot_to_file(
+        self_: *mut VpmVideoPlayerMpv,
+        filename: *const c_char,
+        subtitles: bool,
+    ) -> bool;
     pub fn vpm_video_player_mpv_seek_by(self_: *mut VpmVideoPlayerMpv, seconds: c_int);
     pub fn vpm_video_player_mpv_seek_to(self_: *mut VpmVideoPlayerMpv, seconds: c_uint);
+    pub fn vpm_video_player_mpv_set_ab_loop_a(self_: *mut VpmVideoPlayerMpv, seconds: c_double);
+    pub fn vpm_video_player_mpv_set_ab_loop_b(self_: *mut VpmVideoPlayerMpv, seconds: c_double);
+    pub fn vpm_video_player_mpv_set_aspect_ratio(
+        self_: *mut VpmVideoPlayerMpv,
+        aspect_ratio: c_double,
+    );
+    pub fn vpm_video_player_mpv_set_audio_delay(self_: *mut VpmVideoPlayerMpv, seconds: c_double);
+    pub fn vpm_video_player_mpv_set_audio_device(
+        self_: *mut VpmVideoPlayerMpv,
+        name: *const c_char,
+    );
+    pub fn vpm_video_player_mpv_set_audio_filter(
+        self_: *mut VpmVideoPlayerMpv,
+        filter: *const c_char,
+    );
+    pub fn vpm_video_player_mpv_set_audio_spdif(
+        self_: *mut VpmVideoPlayerMpv,
+        codecs: *const c_char,
+    );
     pub fn vpm_video_player_mpv_set_audio_track(
         self_: *mut VpmVideoPlayerMpv,
         audio_track_id: c_uint,
     );
     pub fn vpm_video_player_mpv_set_mute(self_: *mut VpmVideoPlayerMpv, mute: bool);
+    pub fn vpm_video_player_mpv_set_panscan(self_: *mut VpmVideoPlayerMpv, panscan: c_double);
     pub fn vpm_video_player_mpv_set_playback_speed(
         self_: *mut VpmVideoPlayerMpv,
         playback_speed: c_double,
     );
+    pub fn vpm_video_player_mpv_set_secondary_subtitle_position(
+        self_: *mut VpmVideoPlayerMpv,
+        position: c_uint,
+    );
+    pub fn vpm_video_player_mpv_set_secondary_subtitle_track(
+        self_: *mut VpmVideoPlayerMpv,
+        subtitle_track_id: c_uint,
+    );
     pub fn vpm_video_player_mpv_set_subtitle_background_colour(
         self_: *mut VpmVideoPlayerMpv,
         colour: *mut c_char,
@@ -157,6 +200,10 @@ extern "C" {
         self_: *mut VpmVideoPlayerMpv,
         colour: *mut c_char,
     );
+    pub fn vpm_video_player_mpv_set_subtitle_delay(
+        self_: *mut VpmVideoPlayerMpv,
+        seconds: c_double,
+    );
     pub fn vpm_video_player_mpv_set_subtitle_font_bold(self_: *mut VpmVideoPlayerMpv, bold: bool);
     pub fn vpm_video_player_mpv_set_subtitle_font_family(
         self_: *mut VpmVideoPlayerMpv,
@@ -180,6 +227,7 @@ extern "C" {
         subtitle_track_id: c_uint,
     );
     pub fn vpm_video_player_mpv_set_volume(self_: *mut VpmVideoPlayerMpv, volume: c_double);
+    pub fn vpm_video_player_mpv_set_zoom(self_: *mut VpmVideoPlayerMpv, zoom: c_double);
     pub fn vpm_video_player_mpv_stop(self_: *mut VpmVideoPlayerMpv);
     pub fn vpm_video_player_mpv_volume(self_: *mut VpmVideoPlayerMpv) -> c_double;
 
@@ -33,9 +33,11 @@ enum {
   SIGNAL_VOLUME_UPDATED,
   SIGNAL_MUTE_UPDATED,
   SIGNAL_END_OF_FILE,
+  SIGNAL_ERROR,
   SIGNAL_SEEKING,
   SIGNAL_CORE_IDLE,
   SIGNAL_CURRENT_AO,
+  SIGNAL_AUDIO_DEVICE_LIST_UPDATED,
   SIGNAL_PAUSE,
   SIGNAL_LAST,
 };
@@ -88,6 +90,9 @@ static void vpm_video_player_mpv_class_init(VpmVideoPlayerMpvClass *klass) {
   signals[SIGNAL_END_OF_FILE] =
       g_signal_new("end-of-file", G_TYPE_FROM_CLASS(klass), G_SIGNAL_RUN_LAST,
                    0, NULL, NULL, NULL, G_TYPE_NONE, 0);
+  signals[SIGNAL_ERROR] =
+      g_signal_new("error", G_TYPE_FROM_CLASS(klass), G_SIGNAL_RUN_LAST, 0,
+                   NULL, NULL, NULL, G_TYPE_NONE, 1, G_TYPE_STRING);
   signals[SIGNAL_SEEKING] =
       g_signal_new("seeking", G_TYPE_FROM_CLASS(klass), G_SIGNAL_RUN_LAST, 0,
                    NULL, NULL, NULL, G_TYPE_NONE, 1, G_TYPE_BOOLEAN);
@@ -97,6 +102,9 @@ static void vpm_video_player_mpv_class_init(VpmVideoPlayerMpvClass *klass) {
   signals[SIGNAL_CURRENT_AO] =
       g_signal_new("current-ao", G_TYPE_FROM_CLASS(klass), G_SIGNAL_RUN_LAST, 0,
                    NULL, NULL, NULL, G_TYPE_NONE, 1, G_TYPE_STRING);
+  signals[SIGNAL_AUDIO_DEVICE_LIST_UPDATED] = g_signal_new(
+      "audio-device-list-updated", G_TYPE_FROM_CLASS(klass), G_SIGNAL_RUN_LAST,
+      0, NULL, NULL, NULL, G_TYPE_NONE, 0);
   signals[SIGNAL_PAUSE] =
       g_signal_new("pause", G_TYPE_FROM_CLASS(klass), G_SIGNAL_RUN_LAST, 0,
                    NULL, NULL, NULL, G_TYPE_NONE, 1, G_TYPE_BOOLEAN);
@@ -145,10 +153,16 @@ gboolean process_events(gpointer data) {
       // printf("mpv log: %s\n", ((mpv_event_log_message *)event->data)->text);
       break;
 
-    case MPV_EVENT_END_FILE:
-      // TODO: might want to include reason (eof, error, redirect, etc.)
-      g_signal_emit(widget, signals[SIGNAL_END_OF_FILE], 0);
+    case MPV_EVENT_END_FILE: {
+      mpv_event_end_file *end_file = (mpv_event_end_file *)event->data;
+      if (end_file->reason == MPV_END_FILE_REASON_ERROR) {
+        g_signal_emit(widget, signals[SIGNAL_ERROR], 0,
+                      mpv_error_string(end_file->error));
+      } else {
+        g_signal_emit(widget, signals[SIGNAL_END_OF_FILE], 0);
+      }
       break;
+    }
 
     case MPV_EVENT_GET_PROPERTY_REPLY: {
       // TODO: We should probably check the error value here
@@ -192,6 +206,8 @@ gboolean process_events(gpointer data) {
             g_signal_emit(widget, signals[SIGNAL_TRACKS_UPDATED], 0,
                           &track_list);
           }
+        } else if (strcmp(prop->name, "audio-device-list") == 0) {
+          g_signal_emit(widget, signals[SIGNAL_AUDIO_DEVICE_LIST_UPDATED], 0);
         }
         break;
 
@@ -309,6 +325,8 @@ static void vpm_video_player_mpv_init(VpmVideoPlayerMpv *widget) {
   mpv_observe_property(mpv_ctx->handle, 0, "seeking", MPV_FORMAT_FLAG);
   mpv_observe_property(mpv_ctx->handle, 0, "core-idle", MPV_FORMAT_FLAG);
   mpv_observe_property(mpv_ctx->handle, 0, "current-ao", MPV_FORMAT_STRING);
+  mpv_observe_property(mpv_ctx->handle, 0, "audio-device-list",
+                       MPV_FORMAT_NODE);
   mpv_observe_property(mpv_ctx->handle, 0, "pause", MPV_FORMAT_FLAG);
 
   g_signal_connect(&widget->parent, "realize", G_CALLBACK(realize), NULL);
@@ -348,7 +366,10 @@ void vpm_video_player_mpv_play_uri(VpmVideoPlayerMpv *self, const char *uri) {
       uri,
       NULL,
   };
-  mpv_command(self->mpv_ctx->handle, cmd);
+  int err = mpv_command(self->mpv_ctx->handle, cmd);
+  if (err < 0) {
+    g_signal_emit(self, signals[SIGNAL_ERROR], 0, mpv_error_string(err));
+  }
 }
 
 void vpm_video_player_mpv_play(VpmVideoPlayerMpv *self) {
@@ -401,6 +422,48 @@ void vpm_video_player_mpv_frame_step_backwards(VpmVideoPlayerMpv *self) {
   mpv_command(self->mpv_ctx->handle, cmd);
 }
 
+bool vpm_video_player_mpv_screenshot_to_file(VpmVideoPlayerMpv *self,
+                                             const char *filename,
+                                             bool subtitles) {
+  const char *cmd[] = {
+      "screenshot-to-file",
+      filename,
+      subtitles ? "subtitles" : "video",
+      NULL,
+  };
+  int err = mpv_command(self->mpv_ctx->handle, cmd);
+  if (err < 0) {
+    printf("Error taking screenshot: %d\n", err);
+    return false;
+  }
+  return true;
+}
+
+// Negative seconds clear the loop point
+static void set_ab_loop_point(VpmVideoPlayerMpv *self, const char *name,
+                              double seconds) {
+  int err;
+  if (seconds < 0) {
+    err = mpv_set_property_string(self->mpv_ctx->handle, name, "no");
+  } else {
+    err = mpv_set_property(self->mpv_ctx->handle, name, MPV_FORMAT_DOUBLE,
+                           &seconds);
+  }
+  if (err < 0) {
+    printf("Error setting %s: %d\n", name, err);
+  }
+}
+
+void vpm_video_player_mpv_set_ab_loop_a(VpmVideoPlayerMpv *self,
+                                       double seconds) {
+  set_ab_loop_point(self, "ab-loop-a", seconds);
+}
+
+void vpm_video_player_mpv_set_ab_loop_b(VpmVideoPlayerMpv *self,
+                                       double seconds) {
+  set_ab_loop_point(self, "ab-loop-b", seconds);
+}
+
 bool vpm_video_player_mpv_mute(VpmVideoPlayerMpv *self) {
   bool mute = false;
   if (mpv_get_property(self->mpv_ctx->handle, "ao-mute", MPV_FORMAT_FLAG,
@@ -448,6 +511,32 @@ void vpm_video_player_mpv_set_playback_speed(VpmVideoPlayerMpv *self,
   }
 }
 
+void vpm_video_player_mpv_set_aspect_ratio(VpmVideoPlayerMpv *self,
+                                           double aspect_ratio) {
+  // -1 uses the video's own aspect ratio
+  int err = mpv_set_property(self->mpv_ctx->handle, "video-aspect-override",
+                             MPV_FORMAT_DOUBLE, &aspect_ratio);
+  if (err < 0) {
+    printf("Error setting aspect ratio: %d\n", err);
+  }
+}
+
+void vpm_video_player_mpv_set_zoom(VpmVideoPlayerMpv *self, double zoom) {
+  int err = mpv_set_property(self->mpv_ctx->handle, "video-zoom",
+                             MPV_FORMAT_DOUBLE, &zoom);
+  if (err < 0) {
+    printf("Error setting zoom: %d\n", err);
+  }
+}
+
+void vpm_video_player_mpv_set_panscan(VpmVideoPlayerMpv *self, double panscan) {
+  int err = mpv_set_property(self->mpv_ctx->handle, "panscan",
+                             MPV_FORMAT_DOUBLE, &panscan);
+  if (err < 0) {
+    printf("Error setting panscan: %d\n", err);
+  }
+}
+
 int vpm_video_player_mpv_current_audio_track(VpmVideoPlayerMpv *self) {
   static int id;
   if (mpv_get_property(self->mpv_ctx->handle, "current-tracks/audio/id",
@@ -464,6 +553,91 @@ void vpm_video_player_mpv_set_audio_track(VpmVideoPlayerMpv *self,
   mpv_set_property(self->mpv_ctx->handle, "aid", MPV_FORMAT_INT64, &id);
 }
 
+void vpm_video_player_mpv_set_audio_delay(VpmVideoPlayerMpv *self,
+                                          double seconds) {
+  int err = mpv_set_property(self->mpv_ctx->handle, "audio-delay",
+                             MPV_FORMAT_DOUBLE, &seconds);
+  if (err < 0) {
+    printf("Error setting audio delay: %d\n", err);
+  }
+}
+
+char **vpm_video_player_mpv_audio_device_list(VpmVideoPlayerMpv *self) {
+  GStrvBuilder *builder = g_strv_builder_new();
+
+  mpv_node node;
+  if (mpv_get_property(self->mpv_ctx->handle, "audio-device-list",
+                       MPV_FORMAT_NODE, &node) < 0) {
+    fprintf(stderr, "Error getting audio-device-list.\n");
+    return g_strv_builder_unref_to_strv(builder);
+  }
+
+  if (node.format == MPV_FORMAT_NODE_ARRAY) {
+    for (int i = 0; i < node.u.list->num; i++) {
+      mpv_node device = node.u.list->values[i];
+      if (device.format != MPV_FORMAT_NODE_MAP) {
+        continue;
+      }
+
+      const char *name = NULL;
+      const char *description = NULL;
+      for (int j = 0; j < device.u.list->num; j++) {
+        mpv_node value = device.u.list->values[j];
+        if (value.format != MPV_FORMAT_STRING) {
+          continue;
+        }
+        if (strcmp(device.u.list->keys[j], "name") == 0) {
+          name = value.u.string;
+        } else if (strcmp(device.u.list->keys[j], "description") == 0) {
+          description = value.u.string;
+        }
+      }
+
+      if (name != NULL) {
+        g_strv_builder_add(builder, name);
+        g_strv_builder_add(builder, description != NULL ? description : name);
+      }
+    }
+  }
+
+  mpv_free_node_contents(&node);
+  return g_strv_builder_unref_to_strv(builder);
+}
+
+// Only sets the property if it changed, as mpv reinitializes the audio output
+// whenever these are set
+static void set_audio_property(VpmVideoPlayerMpv *self, const char *name,
+                               const char *value) {
+  char *current = mpv_get_property_string(self->mpv_ctx->handle, name);
+  gboolean changed = current == NULL || strcmp(current, value) != 0;
+  mpv_free(current);
+
+  if (changed) {
+    int err = mpv_set_property_string(self->mpv_ctx->handle, name, value);
+    if (err < 0) {
+      printf("Error setting %s: %d\n", name, err);
+    }
+  }
+}
+
+void vpm_video_player_mpv_set_audio_device(VpmVideoPlayerMpv *self,
+                                           const char *name) {
+  set_audio_property(self, "audio-device", name);
+}
+
+void vpm_video_player_mpv_set_audio_spdif(VpmVideoPlayerMpv *self,
+                                          const char *codecs) {
+  set_audio_property(self, "audio-spdif", codecs);
+}
+
+void vpm_video_player_mpv_set_audio_filter(VpmVideoPlayerMpv *self,
+                                           const char *filter) {
+  int err = mpv_set_property_string(self->mpv_ctx->handle, "af", filter);
+  if (err < 0) {
+    printf("Error setting audio filter: %d\n", err);
+  }
+}
+
 int vpm_video_player_mpv_current_subtitle_track(VpmVideoPlayerMpv *self) {
   static int id;
   if (mpv_get_property(self->mpv_ctx->handle, "current-tracks/sub/id",
@@ -491,6 +665,32 @@ void vpm_video_player_mpv_add_subtitle_track(VpmVideoPlayerMpv *self,
   }
 }
 
+void vpm_video_player_mpv_set_subtitle_delay(VpmVideoPlayerMpv *self,
+                                             double seconds) {
+  int err = mpv_set_property(self->mpv_ctx->handle, "sub-delay",
+                             MPV_FORMAT_DOUBLE, &seconds);
+  if (err < 0) {
+    printf("Error setting subtitle delay: %d\n", err);
+  }
+}
+
+int vpm_video_player_mpv_current_secondary_subtitle_track(
+    VpmVideoPlayerMpv *self) {
+  static int id;
+  if (mpv_get_property(self->mpv_ctx->handle, "current-tracks/sub2/id",
+                       MPV_FORMAT_INT64, &id) < 0) {
+    return -1;
+  }
+  return id;
+}
+
+void vpm_video_player_mpv_set_secondary_subtitle_track(
+    VpmVideoPlayerMpv *self, uint subtitle_track_id) {
+  uint64_t id = (uint64_t)subtitle_track_id;
+  mpv_set_property(self->mpv_ctx->handle, "secondary-sid", MPV_FORMAT_INT64,
+                   &id);
+}
+
 void vpm_video_player_mpv_set_subtitle_scale(VpmVideoPlayerMpv *self,
                                              double subtitle_scale) {
   mpv_set_option(self->mpv_ctx->handle, "sub-scale", MPV_FORMAT_DOUBLE,
@@ -516,6 +716,16 @@ void vpm_video_player_mpv_set_subtitle_position(VpmVideoPlayerMpv *self,
                  &position_int);
 }
 
+void vpm_video_player_mpv_set_secondary_subtitle_position(
+    VpmVideoPlayerMpv *self, uint position) {
+  uint64_t position_int = (uint64_t)position;
+  int err = mpv_set_option(self->mpv_ctx->handle, "secondary-sub-pos",
+                           MPV_FORMAT_INT64, &position_int);
+  if (err < 0) {
+    printf("Error setting secondary-sub-pos: %d\n", err);
+  }
+}
+
 void vpm_video_player_mpv_set_subtitle_font_family(VpmVideoPlayerMpv *self,
                                                    char *family) {
   int err = mpv_set_option(self->mpv_ctx->handle, "sub-font", MPV_FORMAT_STRING,
@@ -546,3 +756,16 @@ void vpm_video_player_mpv_set_subtitle_font_italic(VpmVideoPlayerMpv *self,
   int val = italic;
   mpv_set_property(self->mpv_ctx->handle, "sub-italic", MPV_FORMAT_FLAG, &val);
 }
+
+// Returns NULL if the property isn't available, e.g. when nothing is playing
+char *vpm_video_player_mpv_property_string(VpmVideoPlayerMpv *self,
+                                           const char *name) {
+  char *value = mpv_get_property_string(self->mpv_ctx->handle, name);
+  if (value == NULL) {
+    return NULL;
+  }
+
+  char *copy = g_strdup(value);
+  mpv_free(value);
+  return copy;
+}
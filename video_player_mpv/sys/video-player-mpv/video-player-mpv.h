@@ -22,6 +22,13 @@ void vpm_video_player_mpv_seek_to(VpmVideoPlayerMpv *self, uint seconds);
 void vpm_video_player_mpv_seek_by(VpmVideoPlayerMpv *self, int seconds);
 void vpm_video_player_mpv_frame_step_forwards(VpmVideoPlayerMpv *self);
 void vpm_video_player_mpv_frame_step_backwards(VpmVideoPlayerMpv *self);
+bool vpm_video_player_mpv_screenshot_to_file(VpmVideoPlayerMpv *self,
+                                             const char *filename,
+                                             bool subtitles);
+void vpm_video_player_mpv_set_ab_loop_a(VpmVideoPlayerMpv *self,
+                                       double seconds);
+void vpm_video_player_mpv_set_ab_loop_b(VpmVideoPlayerMpv *self,
+                                       double seconds);
 
 bool vpm_video_player_mpv_mute(VpmVideoPlayerMpv *self);
 void vpm_video_player_mpv_set_mute(VpmVideoPlayerMpv *self, bool mute);
@@ -32,9 +39,27 @@ double vpm_video_player_mpv_playback_speed(VpmVideoPlayerMpv *self);
 void vpm_video_player_mpv_set_playback_speed(VpmVideoPlayerMpv *self,
                                              double playback_speed);
 
+void vpm_video_player_mpv_set_aspect_ratio(VpmVideoPlayerMpv *self,
+                                           double aspect_ratio);
+void vpm_video_player_mpv_set_zoom(VpmVideoPlayerMpv *self, double zoom);
+void vpm_video_player_mpv_set_panscan(VpmVideoPlayerMpv *self, double panscan);
+
 int vpm_video_player_mpv_current_audio_track(VpmVideoPlayerMpv *self);
 void vpm_video_player_mpv_set_audio_track(VpmVideoPlayerMpv *self,
                                           uint audio_track_id);
+void vpm_video_player_mpv_set_audio_delay(VpmVideoPlayerMpv *self,
+                                          double seconds);
+
+// Returns the name and description of each audio device, one after the other
+char **vpm_video_player_mpv_audio_device_list(VpmVideoPlayerMpv *self);
+void vpm_video_player_mpv_set_audio_device(VpmVideoPlayerMpv *self,
+                                           const char *name);
+// Comma-separated list of codecs to pass through, or empty to disable
+void vpm_video_player_mpv_set_audio_spdif(VpmVideoPlayerMpv *self,
+                                          const char *codecs);
+// Audio filter chain in mpv's --af syntax, or empty to remove all filters
+void vpm_video_player_mpv_set_audio_filter(VpmVideoPlayerMpv *self,
+                                           const char *filter);
 
 int vpm_video_player_mpv_current_subtitle_track(VpmVideoPlayerMpv *self);
 void vpm_video_player_mpv_set_subtitle_track(VpmVideoPlayerMpv *self,
@@ -42,6 +67,12 @@ void vpm_video_player_mpv_set_subtitle_track(VpmVideoPlayerMpv *self,
 void vpm_video_player_mpv_add_subtitle_track(VpmVideoPlayerMpv *self,
                                              const char *url,
                                              const char *title);
+void vpm_video_player_mpv_set_subtitle_delay(VpmVideoPlayerMpv *self,
+                                             double seconds);
+int vpm_video_player_mpv_current_secondary_subtitle_track(
+    VpmVideoPlayerMpv *self);
+void vpm_video_player_mpv_set_secondary_subtitle_track(
+    VpmVideoPlayerMpv *self, uint subtitle_track_id);
 
 void vpm_video_player_mpv_set_subtitle_scale(VpmVideoPlayerMpv *self,
                                              double subtitle_scale);
@@ -51,6 +82,8 @@ void vpm_video_player_mpv_set_subtitle_background_colour(
     VpmVideoPlayerMpv *self, char *colour);
 void vpm_video_player_mpv_set_subtitle_position(VpmVideoPlayerMpv *self,
                                                 uint position);
+void vpm_video_player_mpv_set_secondary_subtitle_position(
+    VpmVideoPlayerMpv *self, uint position);
 void vpm_video_player_mpv_set_subtitle_font_family(VpmVideoPlayerMpv *self,
                                                    char *family);
 void vpm_video_player_mpv_set_subtitle_font_size(VpmVideoPlayerMpv *self,
@@ -60,4 +93,7 @@ void vpm_video_player_mpv_set_subtitle_font_bold(VpmVideoPlayerMpv *self,
 void vpm_video_player_mpv_set_subtitle_font_italic(VpmVideoPlayerMpv *self,
                                                    bool italic);
 
+char *vpm_video_player_mpv_property_string(VpmVideoPlayerMpv *self,
+                                           const char *name);
+
 G_END_DECLS